@@ -5,7 +5,7 @@ This file provides type hints for IDE autocomplete and static type checking.
 """
 
 from __future__ import annotations
-from typing import Optional, List, Dict
+from typing import Optional, List, Dict, Tuple
 from zoneinfo import ZoneInfo
 import datetime
 
@@ -30,6 +30,8 @@ class models:
         action: Optional[str]
         subject: Optional[str]
         trackers: List[str]
+        id: Optional[str]
+        billable: Optional[bool]
 
         def __init__(
             self,
@@ -38,9 +40,30 @@ class models:
             objective: Optional[str] = None,
             action: Optional[str] = None,
             subject: Optional[str] = None,
-            trackers: List[str] = []
+            trackers: List[str] = [],
+            id: Optional[str] = None,
+            billable: Optional[bool] = None,
         ) -> None: ...
 
+        def with_billable(self, billable: Optional[bool]) -> models.Intent:
+            """
+            Copy of this intent marked billable or not, or unmarked with None.
+
+            Unmarked intents are billable if a plan marks one of their
+            trackers billable, or else if their role is.
+            """
+            ...
+
+        def effective_id(self) -> str:
+            """
+            Stable identifier for this intent.
+
+            The explicit id if set, otherwise one derived from the fields.
+            Renaming an intent through PlanManager pins its id so that
+            sessions logged before the rename still resolve to it.
+            """
+            ...
+
         def as_dict(self) -> Dict: ...
         def __hash__(self) -> int: ...
         def __eq__(self, other: object) -> bool: ...
@@ -48,6 +71,128 @@ class models:
         def __repr__(self) -> str: ...
         def __str__(self) -> str: ...
 
+    class IntentMatch:
+        """An intent ranked against a search query."""
+        intent: models.Intent
+        score: float
+        matched_fields: List[str]
+        last_used: Optional[datetime.date]
+
+        def __repr__(self) -> str: ...
+        def __str__(self) -> str: ...
+
+    class IntentStats:
+        """Usage statistics for a single intent, relative to the current time."""
+        intent: models.Intent
+        session_count: int
+        total_duration: datetime.timedelta
+        week_session_count: int
+        week_duration: datetime.timedelta
+        starts_near_now: int
+        last_used: Optional[datetime.date]
+        last_used_on_weekday: Optional[datetime.date]
+
+        def __repr__(self) -> str: ...
+
+    class IntentSuggestions:
+        """Quick-pick lists of intents suggested from history."""
+        most_used_this_week: List[models.IntentStats]
+        usual_at_this_time: List[models.IntentStats]
+        last_used_on_weekday: List[models.IntentStats]
+
+        def __repr__(self) -> str: ...
+
+    class ReportRow:
+        """One group in a report: its key values, session count and duration."""
+        key: List[Optional[str]]
+        sessions: int
+        duration: datetime.timedelta
+
+        def __repr__(self) -> str: ...
+
+    class Report:
+        """Recorded time over a date range, grouped by one or more dimensions."""
+        start: datetime.date
+        end: datetime.date
+        group_by: List[str]
+        rows: List[models.ReportRow]
+        sessions: int
+        total: datetime.timedelta
+
+        def table(self) -> List[List[str]]:
+            """Rows of strings: a header, one row per group and a total row."""
+            ...
+
+        def __repr__(self) -> str: ...
+
+    class BudgetStatus:
+        """How much of a budget or target has been used in its current period."""
+        label: str
+        kind: str
+        period: str
+        start: datetime.date
+        end: datetime.date
+        budget: datetime.timedelta
+        consumed: datetime.timedelta
+        remaining: datetime.timedelta
+        projected: datetime.timedelta
+        overrun: Optional[datetime.timedelta]
+        projected_overrun: Optional[datetime.timedelta]
+        warning: Optional[str]
+
+        def __repr__(self) -> str: ...
+
+    class Balance:
+        """Expected and recorded time over one day or one ISO week."""
+        start: datetime.date
+        end: datetime.date
+        expected: datetime.timedelta
+        recorded: datetime.timedelta
+        overtime: datetime.timedelta
+        undertime: datetime.timedelta
+        balance: datetime.timedelta
+
+        def __repr__(self) -> str: ...
+
+    class WorkingHours:
+        """
+        Expected versus recorded hours over a date range.
+
+        Overtime and undertime are summed over the days.
+        """
+        start: datetime.date
+        end: datetime.date
+        days: List[models.Balance]
+        weeks: List[models.Balance]
+        expected: datetime.timedelta
+        recorded: datetime.timedelta
+        overtime: datetime.timedelta
+        undertime: datetime.timedelta
+        balance: datetime.timedelta
+
+        def __repr__(self) -> str: ...
+
+    class ImportReport:
+        """What an import did, or would do with dry_run."""
+        dry_run: bool
+        added: List[models.Session]
+        duplicates: List[models.Session]
+        overlaps: List[Tuple[models.Session, models.Session]]
+        skipped: List[Tuple[str, str]]
+        days: List[models.ImportDay]
+
+        def __repr__(self) -> str: ...
+
+    class ImportDay:
+        """What an import added to one day's log."""
+        date: datetime.date
+        added: int
+        duplicates: int
+        overlaps: int
+        recorded: datetime.timedelta
+
+        def __repr__(self) -> str: ...
+
     class Session:
         """
         A work session with start/end times and intent classification.
@@ -188,6 +333,7 @@ class models:
         subjects: List[str]
         trackers: Dict[str, str]
         intents: List[models.Intent]
+        billable: Dict[str, bool]
 
         def __init__(
             self,
@@ -211,6 +357,15 @@ class models:
             """Generate a slug ID from the source."""
             ...
 
+        def set_billable(self, id: str, billable: Optional[bool]) -> models.Plan:
+            """
+            Mark a tracker billable or not, or clear the mark with None.
+
+            Returns:
+                New Plan instance with the mark set.
+            """
+            ...
+
         def add_intent(self, intent: models.Intent) -> models.Plan:
             """
             Add an intent to the plan (deduplicating if already present).
@@ -227,6 +382,26 @@ class models:
         def __repr__(self) -> str: ...
         def __str__(self) -> str: ...
 
+    class PlanDiagnostic:
+        """A single problem found in a plan."""
+        severity: str  # "error" or "warning"
+        code: str
+        message: str
+
+        def __repr__(self) -> str: ...
+        def __str__(self) -> str: ...
+
+    class PlanFileReport:
+        """Diagnostics for one plan file."""
+        path: str
+        diagnostics: List[models.PlanDiagnostic]
+
+        def has_errors(self) -> bool:
+            """Whether any diagnostic is an error."""
+            ...
+
+        def __repr__(self) -> str: ...
+
     class TimesheetMeta:
         """
         Metadata about a timesheet (not included in signed content).
@@ -335,6 +510,7 @@ class models:
         plan_remotes: List[models.PlanRemote]
         audiences: List[models.TimesheetAudience]
         roles: List[models.Role]
+        allocation: models.Allocation
 
         @classmethod
         def from_dict(cls, data: Dict) -> models.Config: ...
@@ -366,6 +542,12 @@ class models:
         def config(self) -> Dict: ...
         @property
         def signing_ids(self) -> List[str]: ...
+        @property
+        def include(self) -> str:
+            """Which sessions the audience sees: "all", "billable" or "non-billable"."""
+            ...
+        @property
+        def rounding(self) -> models.Rounding: ...
 
         def __repr__(self) -> str: ...
 
@@ -376,6 +558,100 @@ class models:
         def name(self) -> str: ...
         @property
         def config(self) -> Dict: ...
+        @property
+        def billable(self) -> Optional[bool]: ...
+
+        def __repr__(self) -> str: ...
+
+    class Allocation:
+        """
+        How a session's time is credited among its intent's trackers.
+
+        "even" splits time equally, "weighted" in proportion to each
+        tracker's weight (default 1), and "full" credits every tracker with
+        the whole session. Even and weighted totals add up to the day total.
+        """
+        strategy: str
+        weights: Dict[str, float]
+
+        def __init__(
+            self,
+            strategy: str = "even",
+            weights: Optional[Dict[str, float]] = None,
+        ) -> None: ...
+
+        def tracker_totals(
+            self, sessions: List[models.Session]
+        ) -> Dict[Optional[str], datetime.timedelta]:
+            """
+            Time credited to each tracker by the closed sessions.
+
+            Time in sessions without trackers is keyed by None.
+            """
+            ...
+
+        def __repr__(self) -> str: ...
+
+    class Rounding:
+        """
+        A timesheet audience's rounding policy.
+
+        increment is in minutes (0 leaves time as recorded). mode is "up",
+        "nearest" or "down"; scope "session" rounds each session and "day"
+        each day's total.
+        """
+        increment: int
+        mode: str
+        scope: str
+
+        def __init__(
+            self, increment: int = 0, mode: str = "nearest", scope: str = "session"
+        ) -> None: ...
+
+        def round(self, duration: datetime.timedelta) -> datetime.timedelta:
+            """Round a duration, such as an aggregate, to the increment."""
+            ...
+
+        def apply(self, logs: List[models.Log]) -> models.RoundedTime:
+            """
+            Round the closed sessions in the logs per the policy's scope.
+
+            Sessions belong to the date of the log they're in.
+            """
+            ...
+
+        def __repr__(self) -> str: ...
+
+    class RoundedTime:
+        """Recorded and rounded time side by side."""
+        sessions: List[
+            Tuple[datetime.datetime, Optional[str], datetime.timedelta, datetime.timedelta]
+        ]
+        days: List[Tuple[datetime.date, datetime.timedelta, datetime.timedelta]]
+        raw: datetime.timedelta
+        rounded: datetime.timedelta
+        adjustment: datetime.timedelta
+
+        def __repr__(self) -> str: ...
+
+    class Charge:
+        """Time on one day charged at one rate."""
+        date: datetime.date
+        rate: str
+        currency: str
+        hourly: float
+        raw: datetime.timedelta
+        rounded: datetime.timedelta
+        amount: float
+
+        def __repr__(self) -> str: ...
+
+    class Charges:
+        """Logged time turned into money, by day and rate."""
+        kind: str
+        charges: List[models.Charge]
+        totals: Dict[str, float]
+        unrated: datetime.timedelta
 
         def __repr__(self) -> str: ...
 
@@ -423,6 +699,79 @@ class LogManager:
         """Write a log to storage."""
         ...
 
+    def intent_stats(self) -> List[models.IntentStats]:
+        """
+        Usage statistics for today's plan intents, computed from recent logs.
+
+        Auto-fills the current time and intents from workspace.
+        """
+        ...
+
+    def suggest_intents(self, limit: int = 5) -> models.IntentSuggestions:
+        """
+        Suggest today's plan intents for quick-pick lists.
+
+        Auto-fills the current time and intents from workspace.
+
+        Args:
+            limit: Maximum number of intents in each list
+        """
+        ...
+
+    def report(
+        self,
+        start: datetime.date,
+        end: datetime.date,
+        group_by: List[str] = ...,
+        billable: str = "all",
+    ) -> models.Report:
+        """
+        Recorded time in logs dated start..=end, grouped by dimensions.
+
+        Args:
+            start: First date to include
+            end: Last date to include
+            group_by: Any of alias, role, objective, action, subject,
+                tracker, date, weekday, week and billable. Tracker time is
+                credited per the config's allocation.
+            billable: "all", "billable" or "non-billable" sessions
+
+        Raises:
+            ValueError: If a dimension is unknown
+        """
+        ...
+
+    def working_hours(
+        self, start: datetime.date, end: datetime.date
+    ) -> models.WorkingHours:
+        """Expected versus recorded hours in start..=end under the config's schedule."""
+        ...
+
+    def budget_status(self) -> List[models.BudgetStatus]:
+        """Progress of the config's budgets and targets in their current periods."""
+        ...
+
+    def budget_warnings(self, intent: models.Intent) -> List[str]:
+        """
+        Warnings to show before starting a session with the given intent.
+
+        Lists budgets the intent counts towards that are exceeded or on
+        course to be.
+        """
+        ...
+
+    def rewrite_intents(self) -> List[datetime.date]:
+        """
+        Rewrite logged sessions to use today's intent definitions.
+
+        Sessions logged against an intent that has since been renamed are
+        matched by id and updated.
+
+        Returns:
+            Dates of the logs that changed
+        """
+        ...
+
     def start_intent_now(self, intent: models.Intent, note: Optional[str] = None) -> None:
         """
         Start a new session with the given intent.
@@ -446,15 +795,60 @@ class PlanManager:
         """
         Get all plans valid for a given date.
 
+        Plan files that fail to load are skipped; get_plans_with_diagnostics
+        reports them.
+
         Returns:
             Dictionary mapping source names to Plans.
         """
         ...
 
+    def get_plans_with_diagnostics(
+        self,
+        date: datetime.date
+    ) -> Tuple[Dict[str, models.Plan], List[models.PlanFileReport]]:
+        """
+        Get all plans valid for a given date, as get_plans does.
+
+        Returns:
+            The plans that loaded, and a report for each file skipped
+            because it failed to load.
+        """
+        ...
+
+    def lint_plans(self) -> List[models.PlanFileReport]:
+        """
+        Lint every plan file in storage.
+
+        Checks for parse errors, intents referencing unknown trackers,
+        valid_until before valid_from, duplicate aliases and misnamed files.
+
+        Returns:
+            One report per file with at least one diagnostic.
+        """
+        ...
+
     def get_intents(self, date: datetime.date) -> List[models.Intent]:
         """Get all intents from plans valid for a given date."""
         ...
 
+    def search_intents(self, query: str, date: datetime.date) -> List[models.IntentMatch]:
+        """
+        Search intents from plans valid for a given date.
+
+        Every whitespace-separated term in the query must match the alias, role,
+        objective, action, subject or a tracker of the intent. Matching is
+        case-insensitive and fuzzy. Recently used intents are ranked higher.
+
+        Args:
+            query: Search terms; an empty query returns all intents
+            date: Date to load plans and recent logs for
+
+        Returns:
+            Matching intents, best match first.
+        """
+        ...
+
     def get_roles(self, date: datetime.date) -> List[str]:
         """
         Get all roles from plans valid for a given date.
@@ -515,6 +909,84 @@ class PlanManager:
         """Write a plan to storage."""
         ...
 
+    def add_intent(self, date: datetime.date, intent: models.Intent) -> models.Plan:
+        """
+        Add an intent to the local plan version dated `date`.
+
+        Edits never change other plan versions: the result is written as a
+        new local plan version valid from `date`, and later versions don't
+        get the edit (see retire_intent for one that does).
+
+        Returns:
+            The new local plan version.
+        """
+        ...
+
+    def remove_intent(self, date: datetime.date, intent: models.Intent) -> models.Plan:
+        """Remove an intent from the local plan version dated `date`."""
+        ...
+
+    def rename_intent(
+        self,
+        date: datetime.date,
+        intent: models.Intent,
+        alias: str
+    ) -> models.Plan:
+        """Give an intent a new alias in the local plan version dated `date`."""
+        ...
+
+    def retire_intent(self, date: datetime.date, intent: models.Intent) -> None:
+        """
+        Retire an intent from a date onward.
+
+        Also removes the intent from local plan versions dated after `date`.
+        """
+        ...
+
+    def add_tracker(self, date: datetime.date, id: str, name: str) -> models.Plan:
+        """Add or rename a tracker in the local plan version dated `date`."""
+        ...
+
+    def remove_tracker(self, date: datetime.date, id: str) -> models.Plan:
+        """
+        Remove a tracker from the local plan version dated `date`.
+
+        Raises:
+            ValueError: If the tracker doesn't exist or an intent still references it.
+        """
+        ...
+
+    def add_vocabulary(self, date: datetime.date, kind: str, value: str) -> models.Plan:
+        """
+        Add a role, objective, action or subject to the local plan version dated `date`.
+
+        Args:
+            date: Date the edit takes effect
+            kind: One of "role", "objective", "action" or "subject"
+            value: The value to add
+        """
+        ...
+
+    def remove_vocabulary(self, date: datetime.date, kind: str, value: str) -> models.Plan:
+        """Remove a role, objective, action or subject from the local plan version dated `date`."""
+        ...
+
+    def rename_vocabulary(self, date: datetime.date, kind: str, old: str, new: str) -> models.Plan:
+        """
+        Rename a role, objective, action or subject in the local plan version dated `date`.
+
+        Intents using the old value are updated and keep their id, so sessions
+        logged before the rename still resolve to them.
+
+        Raises:
+            ValueError: If the local plan doesn't contain the old value
+        """
+        ...
+
+    def resolve_intent(self, intent: models.Intent, date: datetime.date) -> Optional[models.Intent]:
+        """Find the current definition of a (possibly outdated) logged intent."""
+        ...
+
     def clear_cache(self) -> None:
         """Clear the plan cache."""
         ...
@@ -553,6 +1025,19 @@ class IdentityManager:
         """
         ...
 
+    def workspace_key(self, name: str) -> Optional[bytes]:
+        """
+        Derive the workspace encryption key from an identity.
+
+        Args:
+            name: Identity name
+
+        Returns:
+            The 32-byte key to pass as Workspace(encryption_key=...), or None
+            if the identity doesn't exist
+        """
+        ...
+
     def list_identities(self) -> Dict[str, bytes]:
         """
         List all identities.
@@ -729,13 +1214,210 @@ class Workspace:
     plugins: PluginManager
     """Plugin manager for loading and executing Python plugins."""
 
-    def __init__(self, storage: Optional[object] = None) -> None:
+    def __init__(
+        self,
+        storage: Optional[object] = None,
+        encryption_key: Optional[bytes] = None,
+        index: bool = False,
+        git: bool = False,
+    ) -> None:
         """
         Initialize workspace.
 
         Args:
             storage: Optional custom storage implementation. If None, uses
                     FileSystemStorage and searches for .faff directory from cwd.
+            encryption_key: Optional 32-byte key. If given, file contents are
+                    encrypted at rest (identity keys and config.toml
+                    excepted), and files that aren't encrypted are rejected.
+            index: If True, range reads of logs, plans and timesheets go
+                    through a SQLite index (index.sqlite next to the config
+                    file). The index is kept up to date on writes, and files
+                    changed outside the workspace are re-read when next
+                    needed, so it never needs rebuilding by hand.
+            git: If True, each change (starting a session, editing a plan,
+                    ...) is committed to the git repository the workspace is
+                    in. Identity keys are never committed.
+
+        Raises:
+            RuntimeError: If git is True and the workspace isn't in a git
+                    repository with a working tree
+        """
+        ...
+
+    def export(
+        self,
+        start: datetime.date,
+        end: datetime.date,
+        format: str = "csv",
+        billable: str = "all",
+    ) -> str:
+        """
+        Export the sessions in logs dated start..=end.
+
+        Each session has its intent fields, trackers and their names from
+        the plans valid on the day, start and end in UTC and local time, and
+        duration. iCalendar exports leave out sessions still running.
+
+        Args:
+            start: First date to include
+            end: Last date to include
+            format: "csv", "jsonl" or "ics"
+            billable: "all", "billable" or "non-billable" sessions
+
+        Raises:
+            ValueError: If the format or billable filter is unknown
+        """
+        ...
+
+    def audience_log(
+        self, audience: str, date: datetime.date
+    ) -> Optional[models.Log]:
+        """
+        The log for date as the named audience sees it.
+
+        Only sessions the audience's include rule admits are kept, with
+        billable resolved on each intent. None if there is no log.
+
+        Raises:
+            ValueError: If no audience has that name
+        """
+        ...
+
+    def compile_timesheet(
+        self, audience: str, date: datetime.date
+    ) -> Optional[models.Timesheet]:
+        """
+        Compile the named audience's timesheet for date with its plugin.
+
+        The plugin's compile_time_sheet is given the log as audience_log
+        returns it, so only sessions the audience's include rule admits
+        reach the timesheet, along with its time per tracker credited per
+        the config's allocation. None if there is no log.
+
+        Raises:
+            RuntimeError: If no audience has that name, or the plugin fails
+        """
+        ...
+
+    def rounded_time(
+        self, audience: str, start: datetime.date, end: datetime.date
+    ) -> models.RoundedTime:
+        """
+        Time recorded in logs dated start..=end, raw and rounded.
+
+        Only sessions the [[timesheet_audience]] named audience includes
+        count, rounded per its policy, e.g.
+        rounding = { increment = 15, mode = "up" }.
+
+        Raises:
+            ValueError: If no audience has that name
+        """
+        ...
+
+    def charges(
+        self,
+        start: datetime.date,
+        end: datetime.date,
+        kind: str = "revenue",
+        audience: Optional[str] = None,
+    ) -> models.Charges:
+        """
+        Logged time in logs dated start..=end charged at the config's rates.
+
+        Sessions with several trackers are split among them per the config's
+        allocation. Each tracker's share is charged at its [[rate]] of the
+        kind, or else at the role's, then the audience's, using rates valid
+        on the log's date. Revenue is only earned on billable time. For a
+        named audience, only the sessions it includes are charged and time
+        is rounded per its rounding policy, the same way as rounded_time;
+        time split across rates is rounded per rate.
+
+        Args:
+            start: First date to include
+            end: Last date to include
+            kind: "revenue" or "cost"
+            audience: Name of a [[timesheet_audience]]
+
+        Raises:
+            ValueError: If the kind or a rate is invalid, or no audience
+                has that name
+        """
+        ...
+
+    def import_sessions(
+        self, content: str, format: str, dry_run: bool = False
+    ) -> models.ImportReport:
+        """
+        Import sessions from iCalendar or CSV content into the logs.
+
+        Events are matched to intents by the config's [[import_rule]]
+        entries (title regex to alias) and merged into the log for the day
+        they start on. Events that no rule matches, or that duplicate or
+        overlap logged sessions, are reported rather than written.
+
+        Args:
+            content: The file's contents
+            format: "ics" or "csv"
+            dry_run: Report what would be imported without writing anything
+
+        Raises:
+            ValueError: If the format is unknown
+        """
+        ...
+
+    def import_from(
+        self,
+        content: str,
+        source: str,
+        mapping: Optional[str] = None,
+        dry_run: bool = False,
+    ) -> models.ImportReport:
+        """
+        Import entries exported from another time tracker into the logs.
+
+        Reads Toggl, Clockify and Harvest detailed report CSVs and the JSON
+        written by `timew export`. The mapping (TOML) gives the intent
+        fields for each project, client and tag:
+
+            role = "engineer"
+            [project."Website redesign"]
+            subject = "website"
+            trackers = ["jira:WEB-1"]
+            [tag.meeting]
+            action = "meeting"
+
+        Harvest has no start times, so each day's entries are laid end to
+        end from the mapping's day_start ("09:00" by default). The report's
+        days summarise what each day's log gained.
+
+        Args:
+            content: The export's contents
+            source: "toggl", "clockify", "harvest" or "timewarrior"
+            mapping: The mapping file's contents
+            dry_run: Report what would be imported without writing anything
+
+        Raises:
+            ValueError: If the source is unknown or the mapping is invalid
+        """
+        ...
+
+    def repair_layout(
+        self, dry_run: bool = False
+    ) -> Tuple[List[Tuple[str, str]], List[Tuple[str, str]]]:
+        """
+        Rename files written under older, inconsistent layouts.
+
+        Plans are moved to <source>.<YYYYMMDD>.toml and timesheet metadata to
+        <audience>.<YYYY-MM-DD>.json.meta. Files that can't be repaired (not a
+        plan, or the canonical name is taken) are left alone and reported.
+
+        Args:
+            dry_run: Report what would be renamed without changing anything
+
+        Returns:
+            (renamed, skipped): (from, to) path pairs for the files renamed,
+            and (path, reason) pairs for the files left alone
         """
         ...
 
@@ -9,7 +9,7 @@ from __future__ import annotations
 import datetime
 from abc import ABC, abstractmethod
 from pathlib import Path
-from typing import Dict, Any
+from typing import Dict, Any, Optional
 
 from slugify import slugify
 
@@ -77,12 +77,23 @@ class Audience(Plugin):
     """
 
     @abstractmethod
-    def compile_time_sheet(self, log: Log) -> Timesheet:
+    def compile_time_sheet(
+        self,
+        log: Log,
+        tracker_totals: Dict[Optional[str], datetime.timedelta],
+    ) -> Timesheet:
         """
         Compile a timesheet from a log.
 
+        Workspace.compile_timesheet passes the log as this audience sees
+        it: only sessions its include rule admits, with billable resolved.
+
         Args:
             log: The log to compile into a timesheet
+            tracker_totals: The log's time per tracker, credited per the
+                    config's allocation (time without trackers keyed by
+                    None). Use these rather than splitting multi-tracker
+                    sessions yourself, so timesheets agree with reports.
 
         Returns:
             A compiled Timesheet
@@ -1,4 +1,5 @@
 use crate::python::storage::PyStorage;
+use faff_core::encrypted_storage::WorkspaceKey;
 use faff_core::managers::IdentityManager as RustIdentityManager;
 use pyo3::prelude::*;
 use pyo3::types::PyBytes;
@@ -66,6 +67,27 @@ impl PyIdentityManager {
         Ok(signing_key.map(|key| PyBytes::new(py, &key.to_bytes())))
     }
 
+    /// Derive the workspace encryption key from an identity
+    ///
+    /// Args:
+    ///     name: Identity name
+    ///
+    /// Returns:
+    ///     The 32-byte key to pass as Workspace(encryption_key=...), or None
+    ///     if the identity doesn't exist
+    pub fn workspace_key<'py>(
+        &self,
+        py: Python<'py>,
+        name: &str,
+    ) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        let signing_key = self
+            .manager
+            .get_identity(name)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+        Ok(signing_key.map(|key| PyBytes::new(py, WorkspaceKey::from_identity(&key).as_bytes())))
+    }
+
     /// List all identities
     ///
     /// Returns:
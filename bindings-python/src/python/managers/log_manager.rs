@@ -6,6 +6,11 @@ use std::sync::Arc;
 
 use crate::python::storage::PyStorage;
 use faff_core::managers::LogManager as RustLogManager;
+use faff_core::models::{BillableFilter, Dimension};
+use faff_core::py_models::budget::PyBudgetStatus;
+use faff_core::py_models::intent_stats::{PyIntentStats, PyIntentSuggestions};
+use faff_core::py_models::report::PyReport;
+use faff_core::py_models::working_hours::PyWorkingHours;
 use faff_core::type_mapping::{date_py_to_rust, date_rust_to_py};
 use faff_core::workspace::Workspace as RustWorkspace;
 
@@ -158,6 +163,187 @@ impl PyLogManager {
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
+    /// Usage statistics for today's plan intents
+    ///
+    /// Auto-fills the current time and intents from workspace
+    fn intent_stats(&self) -> PyResult<Vec<PyIntentStats>> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "LogManager has no workspace reference. This should not happen.",
+            )
+        })?;
+
+        let now = workspace.now();
+        let intents = workspace
+            .plans()
+            .get_intents(workspace.today())
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let stats = self
+            .inner
+            .intent_stats(&intents, now)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(stats
+            .into_iter()
+            .map(|inner| PyIntentStats { inner })
+            .collect())
+    }
+
+    /// Suggest today's plan intents for quick-pick lists
+    ///
+    /// Auto-fills the current time and intents from workspace
+    #[pyo3(signature = (limit=5))]
+    fn suggest_intents(&self, limit: usize) -> PyResult<PyIntentSuggestions> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "LogManager has no workspace reference. This should not happen.",
+            )
+        })?;
+
+        let now = workspace.now();
+        let intents = workspace
+            .plans()
+            .get_intents(workspace.today())
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let suggestions = self
+            .inner
+            .suggest_intents(&intents, now, limit)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyIntentSuggestions { inner: suggestions })
+    }
+
+    /// Recorded time in logs dated `start..=end`, grouped by `group_by`
+    ///
+    /// Dimensions are alias, role, objective, action, subject, tracker,
+    /// date, weekday, week and billable. Time is credited to trackers per
+    /// the config's allocation; billable filters to "billable" or
+    /// "non-billable" sessions.
+    #[pyo3(signature = (start, end, group_by=Vec::new(), billable="all"))]
+    fn report(
+        &self,
+        start: Bound<'_, PyDate>,
+        end: Bound<'_, PyDate>,
+        group_by: Vec<String>,
+        billable: &str,
+    ) -> PyResult<PyReport> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "LogManager has no workspace reference. This should not happen.",
+            )
+        })?;
+        let dimensions = group_by
+            .iter()
+            .map(|d| d.parse::<Dimension>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PyValueError::new_err)?;
+        let filter: BillableFilter = billable.parse().map_err(PyValueError::new_err)?;
+        let report = workspace
+            .report(
+                date_py_to_rust(start)?,
+                date_py_to_rust(end)?,
+                &dimensions,
+                filter,
+            )
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyReport { inner: report })
+    }
+
+    /// Expected versus recorded hours in `start..=end` under the config's schedule
+    fn working_hours(
+        &self,
+        start: Bound<'_, PyDate>,
+        end: Bound<'_, PyDate>,
+    ) -> PyResult<PyWorkingHours> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "LogManager has no workspace reference. This should not happen.",
+            )
+        })?;
+        let hours = self
+            .inner
+            .working_hours(
+                &workspace.config().schedule,
+                date_py_to_rust(start)?,
+                date_py_to_rust(end)?,
+            )
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyWorkingHours { inner: hours })
+    }
+
+    /// Progress of the config's budgets and targets in their current periods
+    fn budget_status(&self) -> PyResult<Vec<PyBudgetStatus>> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "LogManager has no workspace reference. This should not happen.",
+            )
+        })?;
+        let config = workspace.config();
+        let statuses = self
+            .inner
+            .budget_status(&config.budget, workspace.today(), &config.allocation)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(statuses
+            .into_iter()
+            .map(|inner| PyBudgetStatus { inner })
+            .collect())
+    }
+
+    /// Warnings to show before starting a session with the given intent
+    ///
+    /// Lists budgets the intent counts towards that are exceeded or on
+    /// course to be.
+    fn budget_warnings(
+        &self,
+        intent: &faff_core::py_models::intent::PyIntent,
+    ) -> PyResult<Vec<String>> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "LogManager has no workspace reference. This should not happen.",
+            )
+        })?;
+        let config = workspace.config();
+        self.inner
+            .budget_warnings(
+                &config.budget,
+                &intent.inner,
+                workspace.today(),
+                &config.allocation,
+            )
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Rewrite logged sessions to use today's intent definitions
+    ///
+    /// Sessions logged against an intent that has since been renamed are
+    /// matched by id and updated. Returns the dates of the logs that changed.
+    fn rewrite_intents<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDate>>> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "LogManager has no workspace reference. This should not happen.",
+            )
+        })?;
+
+        let today = workspace.today();
+        let resolver = workspace
+            .plans()
+            .intent_resolver(today)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let trackers = workspace
+            .plans()
+            .get_trackers(today)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let dates = self
+            .inner
+            .rewrite_intents(&resolver, &trackers)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        dates
+            .into_iter()
+            .map(|date| date_rust_to_py(py, &date))
+            .collect()
+    }
+
     /// Start a new session with the given intent
     ///
     /// Auto-fills current_date, current_time, and trackers from workspace
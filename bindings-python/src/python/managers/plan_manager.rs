@@ -4,8 +4,11 @@ use std::sync::Arc;
 
 use crate::python::storage::PyStorage;
 use faff_core::managers::plan_manager::PlanManager as RustPlanManager;
+use faff_core::models::Vocabulary;
 use faff_core::py_models::intent::PyIntent;
+use faff_core::py_models::intent_search::PyIntentMatch;
 use faff_core::py_models::plan::PyPlan;
+use faff_core::py_models::plan_lint::PyPlanFileReport;
 use faff_core::type_mapping::date_py_to_rust;
 use faff_core::workspace::Workspace as RustWorkspace;
 
@@ -47,7 +50,7 @@ impl PyPlanManager {
         })
     }
 
-    /// Get all plans valid for a given date
+    /// Get all plans valid for a given date, skipping files that fail to load
     ///
     /// Returns: dict[str, Plan] - mapping of source names to Plans
     pub fn get_plans(&self, py: Python, date: Bound<'_, PyDate>) -> PyResult<Py<PyAny>> {
@@ -66,6 +69,47 @@ impl PyPlanManager {
         Ok(dict.into())
     }
 
+    /// Get all plans valid for a given date, with the files skipped because they failed to load
+    ///
+    /// Returns: tuple[dict[str, Plan], list[PlanFileReport]] - loaded plans and skipped files
+    pub fn get_plans_with_diagnostics(
+        &self,
+        py: Python,
+        date: Bound<'_, PyDate>,
+    ) -> PyResult<(Py<PyAny>, Vec<PyPlanFileReport>)> {
+        let naive_date = date_py_to_rust(date)?;
+        let (plans, skipped) = self
+            .manager
+            .get_plans_with_diagnostics(naive_date)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+        let dict = PyDict::new(py);
+        for (source, plan) in plans {
+            dict.set_item(source, PyPlan { inner: plan })?;
+        }
+
+        let skipped = skipped
+            .into_iter()
+            .map(|inner| PyPlanFileReport { inner })
+            .collect();
+        Ok((dict.into(), skipped))
+    }
+
+    /// Lint every plan file in storage
+    ///
+    /// Returns: list[PlanFileReport] - one report per file with diagnostics
+    pub fn lint_plans(&self) -> PyResult<Vec<PyPlanFileReport>> {
+        let reports = self
+            .manager
+            .lint_plans()
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+        Ok(reports
+            .into_iter()
+            .map(|inner| PyPlanFileReport { inner })
+            .collect())
+    }
+
     /// Get all intents from plans valid for a given date
     ///
     /// Returns: list[Intent]
@@ -85,6 +129,34 @@ impl PyPlanManager {
         Ok(list.into())
     }
 
+    /// Search intents from plans valid for a given date
+    ///
+    /// Intents used recently in the workspace's logs are ranked higher.
+    ///
+    /// Returns: list[IntentMatch] - best match first
+    pub fn search_intents(
+        &self,
+        query: &str,
+        date: Bound<'_, PyDate>,
+    ) -> PyResult<Vec<PyIntentMatch>> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "PlanManager has no workspace reference. This should not happen.",
+            )
+        })?;
+
+        let naive_date = date_py_to_rust(date)?;
+        let matches = self
+            .manager
+            .search_intents(query, naive_date, workspace.logs())
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+        Ok(matches
+            .into_iter()
+            .map(|inner| PyIntentMatch { inner })
+            .collect())
+    }
+
     /// Get all roles from plans valid for a given date
     ///
     /// Returns: list[str]
@@ -222,6 +294,163 @@ impl PyPlanManager {
             .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
 
+    /// Add an intent to the local plan version dated `date`
+    ///
+    /// Returns: Plan - the new local plan version
+    pub fn add_intent(&self, date: Bound<'_, PyDate>, intent: &PyIntent) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let plan = self
+            .manager
+            .add_intent(naive_date, intent.inner.clone())
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Remove an intent from the local plan version dated `date`
+    ///
+    /// Returns: Plan - the new local plan version
+    pub fn remove_intent(&self, date: Bound<'_, PyDate>, intent: &PyIntent) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let plan = self
+            .manager
+            .remove_intent(naive_date, &intent.inner)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Give an intent a new alias in the local plan version dated `date`
+    ///
+    /// Returns: Plan - the new local plan version
+    pub fn rename_intent(
+        &self,
+        date: Bound<'_, PyDate>,
+        intent: &PyIntent,
+        alias: &str,
+    ) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let plan = self
+            .manager
+            .rename_intent(naive_date, &intent.inner, alias)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Retire an intent from the local plan from a date onward, including later versions
+    pub fn retire_intent(&self, date: Bound<'_, PyDate>, intent: &PyIntent) -> PyResult<()> {
+        let naive_date = date_py_to_rust(date)?;
+        self.manager
+            .retire_intent(naive_date, &intent.inner)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Add or rename a tracker in the local plan version dated `date`
+    ///
+    /// Returns: Plan - the new local plan version
+    pub fn add_tracker(&self, date: Bound<'_, PyDate>, id: &str, name: &str) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let plan = self
+            .manager
+            .add_tracker(naive_date, id, name)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Remove a tracker from the local plan version dated `date`
+    ///
+    /// Returns: Plan - the new local plan version
+    pub fn remove_tracker(&self, date: Bound<'_, PyDate>, id: &str) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let plan = self
+            .manager
+            .remove_tracker(naive_date, id)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Add a role, objective, action or subject to the local plan version dated `date`
+    ///
+    /// kind: "role", "objective", "action" or "subject"
+    /// Returns: Plan - the new local plan version
+    pub fn add_vocabulary(
+        &self,
+        date: Bound<'_, PyDate>,
+        kind: &str,
+        value: &str,
+    ) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let kind: Vocabulary = kind
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let plan = self
+            .manager
+            .add_vocabulary(naive_date, kind, value)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Remove a role, objective, action or subject from the local plan version dated `date`
+    ///
+    /// kind: "role", "objective", "action" or "subject"
+    /// Returns: Plan - the new local plan version
+    pub fn remove_vocabulary(
+        &self,
+        date: Bound<'_, PyDate>,
+        kind: &str,
+        value: &str,
+    ) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let kind: Vocabulary = kind
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let plan = self
+            .manager
+            .remove_vocabulary(naive_date, kind, value)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Rename a role, objective, action or subject in the local plan version dated `date`
+    ///
+    /// Intents using the old value are updated and keep their id, so sessions
+    /// logged before the rename still resolve to them.
+    /// kind: "role", "objective", "action" or "subject"
+    /// Returns: Plan - the new local plan version
+    pub fn rename_vocabulary(
+        &self,
+        date: Bound<'_, PyDate>,
+        kind: &str,
+        old: &str,
+        new: &str,
+    ) -> PyResult<PyPlan> {
+        let naive_date = date_py_to_rust(date)?;
+        let kind: Vocabulary = kind
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let plan = self
+            .manager
+            .rename_vocabulary(naive_date, kind, old, new)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyPlan { inner: plan })
+    }
+
+    /// Find the current definition of a (possibly outdated) logged intent
+    ///
+    /// Returns: Optional[Intent] - None if no plan valid on date contains it
+    pub fn resolve_intent(
+        &self,
+        intent: &PyIntent,
+        date: Bound<'_, PyDate>,
+    ) -> PyResult<Option<PyIntent>> {
+        let naive_date = date_py_to_rust(date)?;
+        let resolver = self
+            .manager
+            .intent_resolver(naive_date)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(resolver.resolve(&intent.inner).map(|inner| PyIntent {
+            inner: inner.clone(),
+        }))
+    }
+
     /// Get plan remote plugin instances
     ///
     /// This delegates to the Rust PlanManager's remotes() method.
@@ -4,6 +4,7 @@ use faff_core::managers::{
     PluginManager as RustPluginManager,
 };
 use faff_core::models::Config;
+use faff_core::py_models::config::PyAllocation;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use std::collections::HashMap;
@@ -224,18 +225,26 @@ impl PyAudiencePlugin {
     ///
     /// Args:
     ///     log: The Log object to compile a timesheet from
+    ///     allocation: How time is credited among trackers (default: even)
     ///
     /// Returns:
     ///     A Timesheet object
-    pub fn compile_timesheet<'py>(&self, py: Python<'py>, log: Py<PyAny>) -> PyResult<Py<PyAny>> {
+    #[pyo3(signature = (log, allocation=None))]
+    pub fn compile_timesheet<'py>(
+        &self,
+        py: Python<'py>,
+        log: Py<PyAny>,
+        allocation: Option<PyAllocation>,
+    ) -> PyResult<Py<PyAny>> {
         // Extract the Rust Log from PyLog
         use faff_core::py_models::log::PyLog;
         let pylog: PyRef<PyLog> = log.extract(py)?;
         let rust_log = &pylog.inner;
+        let allocation = allocation.map(|a| a.inner).unwrap_or_default();
 
         let timesheet = self
             .plugin
-            .compile_timesheet(rust_log)
+            .compile_timesheet(rust_log, &allocation)
             .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
 
         // Convert Timesheet to PyTimesheet
@@ -114,7 +114,7 @@ impl PyTimesheetManager {
         let mut plugin_manager = plugin_manager_arc.lock().unwrap();
 
         self.manager
-            .submit(&timesheet.inner, &mut *plugin_manager)
+            .submit(&timesheet.inner, &mut plugin_manager)
             .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
 }
@@ -7,13 +7,22 @@ pub mod workspace;
 
 pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
     let models_mod = PyModule::new(m.py(), "models")?;
+    faff_core::py_models::budget::register(&models_mod)?;
     faff_core::py_models::config::register(&models_mod)?;
+    faff_core::py_models::import::register(&models_mod)?;
     faff_core::py_models::intent::register(&models_mod)?;
+    faff_core::py_models::intent_search::register(&models_mod)?;
+    faff_core::py_models::intent_stats::register(&models_mod)?;
     faff_core::py_models::session::register(&models_mod)?;
     faff_core::py_models::log::register(&models_mod)?;
     faff_core::py_models::plan::register(&models_mod)?;
+    faff_core::py_models::plan_lint::register(&models_mod)?;
+    faff_core::py_models::rate::register(&models_mod)?;
+    faff_core::py_models::report::register(&models_mod)?;
+    faff_core::py_models::rounding::register(&models_mod)?;
     faff_core::py_models::timesheet::register(&models_mod)?;
     faff_core::py_models::toy::register(&models_mod)?;
+    faff_core::py_models::working_hours::register(&models_mod)?;
     m.add_submodule(&models_mod)?;
 
     let managers_mod = PyModule::new(m.py(), "managers")?;
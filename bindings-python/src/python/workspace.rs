@@ -3,7 +3,21 @@ use crate::python::managers::{
     plugin_manager::PyPluginManager, timesheet_manager::PyTimesheetManager,
 };
 use crate::python::storage::PyStorage;
-use faff_core::type_mapping::{date_rust_to_py, datetime_rust_to_py};
+use faff_core::encrypted_storage::{EncryptedStorage, WorkspaceKey};
+use faff_core::export::ExportFormat;
+use faff_core::file_system_storage::FileSystemStorage;
+use faff_core::git_storage::GitStorage;
+use faff_core::import::foreign::{Mapping, Source};
+use faff_core::import::ImportFormat;
+use faff_core::models::{BillableFilter, RateKind};
+use faff_core::py_models::import::PyImportReport;
+use faff_core::py_models::log::PyLog;
+use faff_core::py_models::rate::PyCharges;
+use faff_core::py_models::rounding::PyRoundedTime;
+use faff_core::py_models::timesheet::PyTimesheet;
+use faff_core::sqlite_index::SqliteIndex;
+use faff_core::storage::Storage;
+use faff_core::type_mapping::{date_py_to_rust, date_rust_to_py, datetime_rust_to_py};
 use faff_core::workspace::Workspace as RustWorkspace;
 use pyo3::prelude::*;
 use pyo3::types::{PyDate, PyDateTime};
@@ -20,25 +34,70 @@ pub struct PyWorkspace {
     plugins: PyPluginManager,
 }
 
+/// Paths paired with another path or a reason, as Python sees them
+type PathPairs = Vec<(String, String)>;
+
 pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyWorkspace>()?;
     Ok(())
 }
 
+/// Wrap `storage` in git history and encryption as asked
+///
+/// Encryption goes outermost, so what git commits is the ciphertext on disk.
+fn layer_storage<S: Storage + 'static>(
+    storage: S,
+    key: Option<WorkspaceKey>,
+    git: bool,
+) -> anyhow::Result<Arc<dyn Storage>> {
+    Ok(match (git, key) {
+        (true, Some(key)) => Arc::new(EncryptedStorage::new(GitStorage::open(storage)?, &key)),
+        (true, None) => Arc::new(GitStorage::open(storage)?),
+        (false, Some(key)) => Arc::new(EncryptedStorage::new(storage, &key)),
+        (false, None) => Arc::new(storage),
+    })
+}
+
 #[pymethods]
 impl PyWorkspace {
+    /// Open a workspace, optionally over a custom storage object
+    ///
+    /// With `encryption_key` (32 bytes, e.g. from
+    /// `IdentityManager.workspace_key`), file contents are encrypted at rest
+    /// and files that aren't encrypted are rejected.
+    ///
+    /// With `index`, range reads go through a SQLite index kept next to the
+    /// config file. With `git`, each operation is committed to the git
+    /// repository the workspace is in.
     #[new]
-    #[pyo3(signature = (storage=None))]
-    fn py_new(storage: Option<Py<PyAny>>) -> PyResult<Self> {
-        let inner = match storage {
-            Some(storage_obj) => {
-                let py_storage = PyStorage::new(storage_obj);
-                RustWorkspace::with_storage(Arc::new(py_storage))
-                    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?
-            }
-            None => RustWorkspace::new()
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?,
+    #[pyo3(signature = (storage=None, encryption_key=None, index=false, git=false))]
+    fn py_new(
+        storage: Option<Py<PyAny>>,
+        encryption_key: Option<Vec<u8>>,
+        index: bool,
+        git: bool,
+    ) -> PyResult<Self> {
+        let to_py_err = |e: anyhow::Error| pyo3::exceptions::PyRuntimeError::new_err(e.to_string());
+        let key = encryption_key
+            .map(|bytes| WorkspaceKey::from_slice(&bytes))
+            .transpose()
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+        let storage = match storage {
+            Some(storage_obj) => layer_storage(PyStorage::new(storage_obj), key, git),
+            None => layer_storage(FileSystemStorage::new().map_err(to_py_err)?, key, git),
+        }
+        .map_err(to_py_err)?;
+        let index = if index {
+            let path = SqliteIndex::default_path(storage.as_ref());
+            Some(Arc::new(SqliteIndex::open(&path).map_err(to_py_err)?))
+        } else {
+            None
         };
+        let mut inner = RustWorkspace::with_storage(storage).map_err(to_py_err)?;
+        if let Some(index) = index {
+            inner = inner.with_index(index);
+        }
 
         // Wrap the workspace in Arc so we can share it with managers
         let inner_arc = Arc::new(inner);
@@ -123,6 +182,183 @@ impl PyWorkspace {
         self.plugins.clone()
     }
 
+    /// Rename files written under older, inconsistent layouts
+    ///
+    /// Returns (renamed, skipped): (from, to) path pairs for the files
+    /// renamed, or that would be with dry_run, and (path, reason) pairs for
+    /// the misnamed files that can't be repaired and are left alone.
+    #[pyo3(signature = (dry_run=false))]
+    fn repair_layout(&self, dry_run: bool) -> PyResult<(PathPairs, PathPairs)> {
+        let report = self
+            .inner
+            .repair_layout(dry_run)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        let renamed = report
+            .renamed
+            .into_iter()
+            .map(|rename| {
+                (
+                    rename.from.to_string_lossy().into_owned(),
+                    rename.to.to_string_lossy().into_owned(),
+                )
+            })
+            .collect();
+        let skipped = report
+            .skipped
+            .into_iter()
+            .map(|(path, reason)| (path.to_string_lossy().into_owned(), reason))
+            .collect();
+        Ok((renamed, skipped))
+    }
+
+    /// Export the sessions in logs dated start..=end as a string
+    ///
+    /// format is "csv", "jsonl" or "ics". Tracker names come from the plans
+    /// valid on each log's date.
+    #[pyo3(signature = (start, end, format="csv", billable="all"))]
+    fn export(
+        &self,
+        start: Bound<'_, PyDate>,
+        end: Bound<'_, PyDate>,
+        format: &str,
+        billable: &str,
+    ) -> PyResult<String> {
+        let format: ExportFormat = format
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let filter: BillableFilter = billable
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        self.inner
+            .export(
+                date_py_to_rust(start)?,
+                date_py_to_rust(end)?,
+                format,
+                filter,
+            )
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// The log for date as the named audience sees it
+    ///
+    /// Only sessions the audience's include rule admits are kept, with
+    /// billable resolved on each intent. None if there is no log.
+    fn audience_log(&self, audience: &str, date: Bound<'_, PyDate>) -> PyResult<Option<PyLog>> {
+        let log = self
+            .inner
+            .audience_log(audience, date_py_to_rust(date)?)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(log.map(|inner| PyLog { inner }))
+    }
+
+    /// Compile the named audience's timesheet for date with its plugin
+    ///
+    /// The plugin is given the log as audience_log returns it. None if
+    /// there is no log.
+    fn compile_timesheet(
+        &self,
+        audience: &str,
+        date: Bound<'_, PyDate>,
+    ) -> PyResult<Option<PyTimesheet>> {
+        let timesheet = self
+            .inner
+            .compile_timesheet(audience, date_py_to_rust(date)?)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        Ok(timesheet.map(|inner| PyTimesheet { inner }))
+    }
+
+    /// Time recorded in logs dated start..=end, raw and rounded per the
+    /// named audience's rounding policy, counting only the sessions it
+    /// includes
+    fn rounded_time(
+        &self,
+        audience: &str,
+        start: Bound<'_, PyDate>,
+        end: Bound<'_, PyDate>,
+    ) -> PyResult<PyRoundedTime> {
+        let rounded = self
+            .inner
+            .rounded_time(audience, date_py_to_rust(start)?, date_py_to_rust(end)?)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyRoundedTime { inner: rounded })
+    }
+
+    /// Logged time in logs dated start..=end charged at the config's rates
+    ///
+    /// kind is "revenue" or "cost". For a named audience, only the sessions
+    /// it includes are charged, its rates apply and time is rounded per its
+    /// rounding policy.
+    #[pyo3(signature = (start, end, kind="revenue", audience=None))]
+    fn charges(
+        &self,
+        start: Bound<'_, PyDate>,
+        end: Bound<'_, PyDate>,
+        kind: &str,
+        audience: Option<&str>,
+    ) -> PyResult<PyCharges> {
+        let kind: RateKind = kind
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let charges = self
+            .inner
+            .charges(
+                date_py_to_rust(start)?,
+                date_py_to_rust(end)?,
+                kind,
+                audience,
+            )
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyCharges { inner: charges })
+    }
+
+    /// Import sessions from iCalendar or CSV content into the logs
+    ///
+    /// format is "ics" or "csv". Events are matched to intents by the
+    /// config's import rules. With dry_run nothing is written.
+    #[pyo3(signature = (content, format, dry_run=false))]
+    fn import_sessions(
+        &self,
+        content: &str,
+        format: &str,
+        dry_run: bool,
+    ) -> PyResult<PyImportReport> {
+        let format: ImportFormat = format
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let report = self
+            .inner
+            .import(content, format, dry_run)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PyImportReport { inner: report })
+    }
+
+    /// Import entries exported from another time tracker into the logs
+    ///
+    /// source is "toggl", "clockify", "harvest" or "timewarrior"; mapping
+    /// is the TOML mapping of projects, clients and tags to intents.
+    #[pyo3(signature = (content, source, mapping=None, dry_run=false))]
+    fn import_from(
+        &self,
+        content: &str,
+        source: &str,
+        mapping: Option<&str>,
+        dry_run: bool,
+    ) -> PyResult<PyImportReport> {
+        let source: Source = source
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let mapping = match mapping {
+            Some(mapping) => Mapping::from_toml(mapping)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{e:#}")))?,
+            None => Mapping::default(),
+        };
+        let report = self
+            .inner
+            .import_from(content, source, &mapping, dry_run)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PyImportReport { inner: report })
+    }
+
     fn __repr__(&self) -> String {
         format!("Workspace(timezone={})", self.inner.timezone().name())
     }
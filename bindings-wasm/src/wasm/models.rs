@@ -1,9 +1,16 @@
 use chrono::{DateTime, Datelike, NaiveDate};
 use chrono_tz::Tz;
+use faff_core::models::intent_search::{self, IntentMatch as RustIntentMatch};
+use faff_core::models::intent_stats::{
+    self, IntentStats as RustIntentStats, IntentSuggestions as RustIntentSuggestions,
+};
+use faff_core::models::plan_lint;
 use faff_core::models::{
     Intent as RustIntent, Log as RustLog, Plan as RustPlan, Session as RustSession,
     Timesheet as RustTimesheet,
 };
+use faff_core::storage::VersionToken;
+use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
 /// Intent represents what you're doing, classified semantically.
@@ -12,7 +19,7 @@ use wasm_bindgen::prelude::*;
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct Intent {
-    inner: RustIntent,
+    pub(crate) inner: RustIntent,
 }
 
 #[wasm_bindgen]
@@ -25,17 +32,18 @@ impl Intent {
         action: Option<String>,
         subject: Option<String>,
         trackers: Option<Vec<String>>,
+        id: Option<String>,
     ) -> Self {
-        Self {
-            inner: RustIntent::new(
-                alias,
-                role,
-                objective,
-                action,
-                subject,
-                trackers.unwrap_or_default(),
-            ),
-        }
+        let mut inner = RustIntent::new(
+            alias,
+            role,
+            objective,
+            action,
+            subject,
+            trackers.unwrap_or_default(),
+        );
+        inner.id = id;
+        Self { inner }
     }
 
     #[wasm_bindgen(getter)]
@@ -68,6 +76,17 @@ impl Intent {
         self.inner.trackers.clone()
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> Option<String> {
+        self.inner.id.clone()
+    }
+
+    /// The explicit id if set, otherwise one derived from the fields
+    #[wasm_bindgen(js_name = effectiveId)]
+    pub fn effective_id(&self) -> String {
+        self.inner.effective_id()
+    }
+
     /// Convert to JSON object
     #[wasm_bindgen(js_name = toJSON)]
     pub fn to_json(&self) -> Result<JsValue, JsValue> {
@@ -156,6 +175,8 @@ impl Session {
 #[derive(Clone)]
 pub struct Log {
     pub(crate) inner: RustLog,
+    /// Version of the log file this was read from, if any
+    pub(crate) version: Option<VersionToken>,
 }
 
 #[wasm_bindgen]
@@ -169,6 +190,7 @@ impl Log {
 
         Ok(Self {
             inner: RustLog::new(naive_date, tz, vec![]),
+            version: None,
         })
     }
 
@@ -182,6 +204,12 @@ impl Log {
         self.inner.timezone.name().to_string()
     }
 
+    /// Version of the stored log this was read from (undefined if not read from storage)
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> Option<String> {
+        self.version.as_ref().map(|v| v.to_string())
+    }
+
     #[wasm_bindgen(getter)]
     pub fn timeline(&self) -> Vec<Session> {
         self.inner
@@ -272,6 +300,14 @@ impl Plan {
             .collect()
     }
 
+    /// Check the plan for semantic problems.
+    ///
+    /// Returns an array of `{ severity, code, message }` objects.
+    pub fn lint(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&plan_lint::lint_plan(&self.inner))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     #[wasm_bindgen(js_name = toJSON)]
     pub fn to_json(&self) -> Result<JsValue, JsValue> {
         serde_wasm_bindgen::to_value(&self.inner).map_err(|e| JsValue::from_str(&e.to_string()))
@@ -331,6 +367,208 @@ impl Timesheet {
     }
 }
 
+/// An intent ranked against a search query.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct IntentMatch {
+    inner: RustIntentMatch,
+}
+
+#[wasm_bindgen]
+impl IntentMatch {
+    #[wasm_bindgen(getter)]
+    pub fn intent(&self) -> Intent {
+        Intent {
+            inner: self.inner.intent.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> f64 {
+        self.inner.score
+    }
+
+    #[wasm_bindgen(getter, js_name = matchedFields)]
+    pub fn matched_fields(&self) -> Vec<String> {
+        self.inner.matched_fields.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = lastUsed)]
+    pub fn last_used(&self) -> Option<js_sys::Date> {
+        self.inner.last_used.map(|d| naive_date_to_js_date(&d))
+    }
+}
+
+/// Search the intents of the given plans, best match first.
+///
+/// Intents that appear in the given logs are ranked higher the more recently
+/// they were used. Uses the same ranking as the Python bindings.
+#[wasm_bindgen(js_name = searchIntents)]
+pub fn search_intents(
+    query: &str,
+    plans: Vec<Plan>,
+    logs: Vec<Log>,
+    today: js_sys::Date,
+) -> Result<Vec<IntentMatch>, JsValue> {
+    let today = js_date_to_naive_date(&today)?;
+
+    let mut intents = Vec::new();
+    let mut trackers = HashMap::new();
+    for plan in &plans {
+        for intent in &plan.inner.intents {
+            if !intents.contains(intent) {
+                intents.push(intent.clone());
+            }
+        }
+        for (tracker_key, tracker_value) in &plan.inner.trackers {
+            trackers.insert(
+                format!("{}:{}", plan.inner.source, tracker_key),
+                tracker_value.clone(),
+            );
+        }
+    }
+
+    let logs: Vec<RustLog> = logs.into_iter().map(|log| log.inner).collect();
+    let last_used = intent_search::last_used_from_logs(&logs);
+
+    Ok(
+        intent_search::rank_intents(query, &intents, &trackers, &last_used, today)
+            .into_iter()
+            .map(|inner| IntentMatch { inner })
+            .collect(),
+    )
+}
+
+/// Usage statistics for a single intent, relative to a moment in time.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct IntentStats {
+    inner: RustIntentStats,
+}
+
+#[wasm_bindgen]
+impl IntentStats {
+    #[wasm_bindgen(getter)]
+    pub fn intent(&self) -> Intent {
+        Intent {
+            inner: self.inner.intent.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter, js_name = sessionCount)]
+    pub fn session_count(&self) -> usize {
+        self.inner.session_count
+    }
+
+    /// Total time of closed sessions in milliseconds
+    #[wasm_bindgen(getter, js_name = totalDuration)]
+    pub fn total_duration(&self) -> f64 {
+        self.inner.total_duration.num_milliseconds() as f64
+    }
+
+    #[wasm_bindgen(getter, js_name = weekSessionCount)]
+    pub fn week_session_count(&self) -> usize {
+        self.inner.week_session_count
+    }
+
+    /// Total time of closed sessions this week in milliseconds
+    #[wasm_bindgen(getter, js_name = weekDuration)]
+    pub fn week_duration(&self) -> f64 {
+        self.inner.week_duration.num_milliseconds() as f64
+    }
+
+    #[wasm_bindgen(getter, js_name = startsNearNow)]
+    pub fn starts_near_now(&self) -> usize {
+        self.inner.starts_near_now
+    }
+
+    #[wasm_bindgen(getter, js_name = lastUsed)]
+    pub fn last_used(&self) -> Option<js_sys::Date> {
+        self.inner.last_used.map(|d| naive_date_to_js_date(&d))
+    }
+
+    #[wasm_bindgen(getter, js_name = lastUsedOnWeekday)]
+    pub fn last_used_on_weekday(&self) -> Option<js_sys::Date> {
+        self.inner
+            .last_used_on_weekday
+            .map(|d| naive_date_to_js_date(&d))
+    }
+}
+
+/// Quick-pick lists of intents suggested from history.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct IntentSuggestions {
+    inner: RustIntentSuggestions,
+}
+
+#[wasm_bindgen]
+impl IntentSuggestions {
+    #[wasm_bindgen(getter, js_name = mostUsedThisWeek)]
+    pub fn most_used_this_week(&self) -> Vec<IntentStats> {
+        wrap_stats(&self.inner.most_used_this_week)
+    }
+
+    #[wasm_bindgen(getter, js_name = usualAtThisTime)]
+    pub fn usual_at_this_time(&self) -> Vec<IntentStats> {
+        wrap_stats(&self.inner.usual_at_this_time)
+    }
+
+    #[wasm_bindgen(getter, js_name = lastUsedOnWeekday)]
+    pub fn last_used_on_weekday(&self) -> Vec<IntentStats> {
+        wrap_stats(&self.inner.last_used_on_weekday)
+    }
+}
+
+fn wrap_stats(stats: &[RustIntentStats]) -> Vec<IntentStats> {
+    stats
+        .iter()
+        .map(|s| IntentStats { inner: s.clone() })
+        .collect()
+}
+
+/// Compute usage statistics for the given intents from a set of logs.
+///
+/// Time-of-day comparisons are made in the given timezone.
+#[wasm_bindgen(js_name = intentStats)]
+pub fn intent_stats(
+    intents: Vec<Intent>,
+    logs: Vec<Log>,
+    now: js_sys::Date,
+    timezone: &str,
+) -> Result<Vec<IntentStats>, JsValue> {
+    let tz: Tz = timezone
+        .parse()
+        .map_err(|_| JsValue::from_str(&format!("Invalid timezone: {}", timezone)))?;
+    let now = js_date_to_chrono(&now)?.with_timezone(&tz);
+
+    let intents: Vec<RustIntent> = intents.into_iter().map(|i| i.inner).collect();
+    let logs: Vec<RustLog> = logs.into_iter().map(|log| log.inner).collect();
+
+    Ok(wrap_stats(&intent_stats::compute_intent_stats(
+        &intents, &logs, now,
+    )))
+}
+
+/// Suggest intents for quick-pick lists, at most `limit` per list.
+#[wasm_bindgen(js_name = suggestIntents)]
+pub fn suggest_intents(
+    intents: Vec<Intent>,
+    logs: Vec<Log>,
+    now: js_sys::Date,
+    timezone: &str,
+    limit: usize,
+) -> Result<IntentSuggestions, JsValue> {
+    let stats: Vec<RustIntentStats> = intent_stats(intents, logs, now, timezone)?
+        .into_iter()
+        .map(|s| s.inner)
+        .collect();
+
+    Ok(IntentSuggestions {
+        inner: RustIntentSuggestions::from_stats(&stats, limit),
+    })
+}
+
 // Helper functions for date/time conversion
 
 fn js_date_to_chrono(date: &js_sys::Date) -> Result<DateTime<Tz>, JsValue> {
@@ -1,4 +1,8 @@
+use anyhow::{anyhow, Result};
+use faff_core::async_storage::AsyncStorage;
+use faff_core::encrypted_storage::{EncryptedStorage, WorkspaceKey};
 use js_sys::{Array, Uint8Array};
+use std::path::{Path, PathBuf};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 
@@ -9,6 +13,7 @@ use wasm_bindgen_futures::JsFuture;
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "StorageAdapter")]
+    #[derive(Clone)]
     pub type JsStorage;
 
     #[wasm_bindgen(structural, method, js_name = rootDir)]
@@ -53,6 +58,10 @@ extern "C" {
         data: &str,
     ) -> Result<js_sys::Promise, JsValue>;
 
+    /// Delete a file. Returns Promise<void>.
+    #[wasm_bindgen(structural, method, js_name = delete, catch)]
+    pub fn delete_promise(this: &JsStorage, path: &str) -> Result<js_sys::Promise, JsValue>;
+
     /// Check if file exists (synchronous).
     #[wasm_bindgen(structural, method)]
     pub fn exists(this: &JsStorage, path: &str) -> bool;
@@ -101,6 +110,12 @@ impl JsStorage {
         Ok(())
     }
 
+    pub async fn delete(&self, path: &str) -> Result<(), JsValue> {
+        let promise = self.delete_promise(path)?;
+        JsFuture::from(promise).await?;
+        Ok(())
+    }
+
     pub async fn create_dir_all(&self, path: &str) -> Result<(), JsValue> {
         let promise = self.create_dir_all_promise(path)?;
         JsFuture::from(promise).await?;
@@ -121,3 +136,205 @@ impl JsStorage {
         Ok(files)
     }
 }
+
+/// Lets core's async managers run over the JavaScript storage adapter
+impl AsyncStorage for JsStorage {
+    fn root_dir(&self) -> PathBuf {
+        PathBuf::from(JsStorage::root_dir(self))
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        PathBuf::from(JsStorage::log_dir(self))
+    }
+
+    fn plan_dir(&self) -> PathBuf {
+        PathBuf::from(JsStorage::plan_dir(self))
+    }
+
+    fn identity_dir(&self) -> PathBuf {
+        PathBuf::from(JsStorage::identity_dir(self))
+    }
+
+    fn timesheet_dir(&self) -> PathBuf {
+        PathBuf::from(JsStorage::timesheet_dir(self))
+    }
+
+    fn config_file(&self) -> PathBuf {
+        PathBuf::from(JsStorage::config_file(self))
+    }
+
+    async fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        JsStorage::read_bytes(self, &js_path(path))
+            .await
+            .map_err(js_error)
+    }
+
+    async fn read_string(&self, path: &Path) -> Result<String> {
+        JsStorage::read_string(self, &js_path(path))
+            .await
+            .map_err(js_error)
+    }
+
+    async fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+        JsStorage::write_bytes(self, &js_path(path), data)
+            .await
+            .map_err(js_error)
+    }
+
+    async fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+        JsStorage::write_string(self, &js_path(path), data)
+            .await
+            .map_err(js_error)
+    }
+
+    async fn delete(&self, path: &Path) -> Result<()> {
+        JsStorage::delete(self, &js_path(path))
+            .await
+            .map_err(js_error)
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        JsStorage::exists(self, &js_path(path))
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> Result<()> {
+        JsStorage::create_dir_all(self, &js_path(path))
+            .await
+            .map_err(js_error)
+    }
+
+    async fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        let files = JsStorage::list_files(self, &js_path(dir), pattern)
+            .await
+            .map_err(js_error)?;
+        Ok(files.into_iter().map(PathBuf::from).collect())
+    }
+}
+
+/// Storage behind a workspace: the JavaScript adapter, optionally with file
+/// contents encrypted at rest
+#[derive(Clone)]
+pub enum WorkspaceStorage {
+    Plain(JsStorage),
+    Encrypted(EncryptedStorage<JsStorage>),
+}
+
+impl WorkspaceStorage {
+    pub fn new(storage: JsStorage, key: Option<&WorkspaceKey>) -> Self {
+        match key {
+            Some(key) => Self::Encrypted(EncryptedStorage::new(storage, key)),
+            None => Self::Plain(storage),
+        }
+    }
+}
+
+impl AsyncStorage for WorkspaceStorage {
+    fn root_dir(&self) -> PathBuf {
+        match self {
+            Self::Plain(storage) => AsyncStorage::root_dir(storage),
+            Self::Encrypted(storage) => storage.root_dir(),
+        }
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        match self {
+            Self::Plain(storage) => AsyncStorage::log_dir(storage),
+            Self::Encrypted(storage) => storage.log_dir(),
+        }
+    }
+
+    fn plan_dir(&self) -> PathBuf {
+        match self {
+            Self::Plain(storage) => AsyncStorage::plan_dir(storage),
+            Self::Encrypted(storage) => storage.plan_dir(),
+        }
+    }
+
+    fn identity_dir(&self) -> PathBuf {
+        match self {
+            Self::Plain(storage) => AsyncStorage::identity_dir(storage),
+            Self::Encrypted(storage) => storage.identity_dir(),
+        }
+    }
+
+    fn timesheet_dir(&self) -> PathBuf {
+        match self {
+            Self::Plain(storage) => AsyncStorage::timesheet_dir(storage),
+            Self::Encrypted(storage) => storage.timesheet_dir(),
+        }
+    }
+
+    fn config_file(&self) -> PathBuf {
+        match self {
+            Self::Plain(storage) => AsyncStorage::config_file(storage),
+            Self::Encrypted(storage) => storage.config_file(),
+        }
+    }
+
+    async fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        match self {
+            Self::Plain(storage) => AsyncStorage::read_bytes(storage, path).await,
+            Self::Encrypted(storage) => storage.read_bytes(path).await,
+        }
+    }
+
+    async fn read_string(&self, path: &Path) -> Result<String> {
+        match self {
+            Self::Plain(storage) => AsyncStorage::read_string(storage, path).await,
+            Self::Encrypted(storage) => storage.read_string(path).await,
+        }
+    }
+
+    async fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+        match self {
+            Self::Plain(storage) => AsyncStorage::write_bytes(storage, path, data).await,
+            Self::Encrypted(storage) => storage.write_bytes(path, data).await,
+        }
+    }
+
+    async fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+        match self {
+            Self::Plain(storage) => AsyncStorage::write_string(storage, path, data).await,
+            Self::Encrypted(storage) => storage.write_string(path, data).await,
+        }
+    }
+
+    async fn delete(&self, path: &Path) -> Result<()> {
+        match self {
+            Self::Plain(storage) => AsyncStorage::delete(storage, path).await,
+            Self::Encrypted(storage) => storage.delete(path).await,
+        }
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        match self {
+            Self::Plain(storage) => AsyncStorage::exists(storage, path).await,
+            Self::Encrypted(storage) => storage.exists(path).await,
+        }
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> Result<()> {
+        match self {
+            Self::Plain(storage) => AsyncStorage::create_dir_all(storage, path).await,
+            Self::Encrypted(storage) => storage.create_dir_all(path).await,
+        }
+    }
+
+    async fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        match self {
+            Self::Plain(storage) => AsyncStorage::list_files(storage, dir, pattern).await,
+            Self::Encrypted(storage) => storage.list_files(dir, pattern).await,
+        }
+    }
+}
+
+fn js_path(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+fn js_error(error: JsValue) -> anyhow::Error {
+    match error.as_string() {
+        Some(message) => anyhow!(message),
+        None => anyhow!("{:?}", error),
+    }
+}
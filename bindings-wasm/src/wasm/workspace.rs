@@ -1,10 +1,14 @@
-use super::models::{Log, Plan};
-use super::storage::JsStorage;
+use super::models::{Intent, Log, Plan};
+use super::storage::{JsStorage, WorkspaceStorage};
 use chrono::{Datelike, NaiveDate};
-use faff_core::models::{Config as RustConfig, Log as RustLog, Plan as RustPlan};
-use std::collections::HashMap;
+use faff_core::async_workspace::AsyncWorkspace;
+use faff_core::encrypted_storage::WorkspaceKey;
+use faff_core::export::ExportFormat;
+use faff_core::import::foreign::{Mapping, Source};
+use faff_core::import::ImportFormat;
+use faff_core::models::{BillableFilter, BudgetStatus, Dimension, RateKind, Report};
+use serde::Serialize;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::future_to_promise;
 
 /// Workspace provides coordinated access to faff functionality.
@@ -12,22 +16,30 @@ use wasm_bindgen_futures::future_to_promise;
 /// All methods are async since they interact with JavaScript storage.
 #[wasm_bindgen]
 pub struct Workspace {
-    storage: JsStorage,
-    config: RustConfig,
+    inner: AsyncWorkspace<WorkspaceStorage>,
 }
 
 #[wasm_bindgen]
 impl Workspace {
     /// Create a new workspace with the given storage adapter.
     ///
+    /// With `encryptionKey` (a 32-byte Uint8Array), file contents are
+    /// encrypted at rest; paths are left as they are.
+    ///
     /// Returns Promise<Workspace>.
+    #[allow(clippy::new_ret_no_self)]
     #[wasm_bindgen(constructor)]
-    pub fn new(storage: JsStorage) -> js_sys::Promise {
+    pub fn new(storage: JsStorage, encryption_key: Option<Vec<u8>>) -> js_sys::Promise {
         future_to_promise(async move {
-            let workspace = Workspace::create_async(storage)
+            let key = encryption_key
+                .map(|bytes| WorkspaceKey::from_slice(&bytes))
+                .transpose()
+                .map_err(to_js_error)?;
+            let storage = WorkspaceStorage::new(storage, key.as_ref());
+            let inner = AsyncWorkspace::open(storage)
                 .await
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
-            Ok(JsValue::from(workspace))
+            Ok(JsValue::from(Workspace { inner }))
         })
     }
 
@@ -36,115 +48,453 @@ impl Workspace {
     /// Returns Promise<Log>.
     #[wasm_bindgen(js_name = getLog)]
     pub fn get_log(&self, date: js_sys::Date) -> js_sys::Promise {
-        let storage: JsStorage = self.storage.clone().unchecked_into();
-        let timezone = self.config.timezone;
+        let logs = self.inner.logs().clone();
 
         future_to_promise(async move {
             let naive_date = js_date_to_naive_date(&date)?;
-            let log_path = Self::log_path_for_date(&storage, &naive_date);
-
-            let log = if storage.exists(&log_path) {
-                let content = storage
-                    .read_string(&log_path)
-                    .await
-                    .map_err(|e| JsValue::from_str(&format!("Failed to read log: {:?}", e)))?;
-
-                let rust_log = RustLog::from_log_file(&content)
-                    .map_err(|e| JsValue::from_str(&format!("Failed to parse log: {}", e)))?;
-
-                Log { inner: rust_log }
-            } else {
-                // Return empty log if file doesn't exist
-                Log {
-                    inner: RustLog::new(naive_date, timezone, vec![]),
-                }
-            };
+            let (inner, version) = logs
+                .get_log_versioned(naive_date)
+                .await
+                .map_err(to_js_error)?;
+            Ok(JsValue::from(Log { inner, version }))
+        })
+    }
+
+    /// The log for a date as the named timesheet audience sees it.
+    ///
+    /// Only sessions the audience's `include` rule admits are kept, with
+    /// `billable` resolved on each intent.
+    ///
+    /// Returns Promise<Log | null>.
+    #[wasm_bindgen(js_name = audienceLog)]
+    pub fn audience_log(&self, audience: String, date: js_sys::Date) -> js_sys::Promise {
+        let workspace = self.inner.clone();
 
-            Ok(JsValue::from(log))
+        future_to_promise(async move {
+            let date = js_date_to_naive_date(&date)?;
+            let log = workspace
+                .audience_log(&audience, date)
+                .await
+                .map_err(to_js_error)?;
+            Ok(log.map_or(JsValue::NULL, |inner| {
+                JsValue::from(Log {
+                    inner,
+                    version: None,
+                })
+            }))
         })
     }
 
     /// Save a log to disk.
     ///
-    /// Returns Promise<void>.
+    /// Fails if the stored log changed since `log` was read with `getLog`
+    /// (e.g. by a sync or another process), or if a log created in memory
+    /// would overwrite one already stored.
+    ///
+    /// Returns Promise<string> - the new version of the stored log.
     #[wasm_bindgen(js_name = saveLog)]
     pub fn save_log(&self, log: &Log) -> js_sys::Promise {
-        let storage: JsStorage = self.storage.clone().unchecked_into();
+        let logs = self.inner.logs().clone();
+        let plans = self.inner.plans().clone();
         let log_inner = log.inner.clone();
+        let expected = log.version.clone();
 
         future_to_promise(async move {
-            let log_path = Self::log_path_for_date(&storage, &log_inner.date);
-            let trackers = HashMap::new(); // TODO: Get trackers from config/plan
-            let content = log_inner.to_log_file(&trackers);
+            let trackers = plans
+                .get_trackers(log_inner.date)
+                .await
+                .map_err(to_js_error)?;
+            let version = logs
+                .write_log_if(&log_inner, &trackers, expected.as_ref())
+                .await
+                .map_err(to_js_error)?;
+            Ok(JsValue::from_str(version.as_str()))
+        })
+    }
+
+    /// Recorded time in logs dated `start..=end`, grouped by `groupBy`.
+    ///
+    /// Dimensions are alias, role, objective, action, subject, tracker,
+    /// date, weekday, week and billable. Time is credited to trackers per
+    /// the config's `[allocation]`. `billable` ("all", "billable" or
+    /// "non-billable", default all) picks which sessions count. The result
+    /// has the same fields as the core report, plus `table`: a header, one
+    /// row per group and a total row, all as strings.
+    ///
+    /// Returns Promise<object>.
+    #[wasm_bindgen(js_name = report)]
+    pub fn report(
+        &self,
+        start: js_sys::Date,
+        end: js_sys::Date,
+        group_by: Vec<String>,
+        billable: Option<String>,
+    ) -> js_sys::Promise {
+        let workspace = self.inner.clone();
 
-            // Ensure log directory exists
-            let log_dir = storage.log_dir();
-            storage
-                .create_dir_all(&log_dir)
+        future_to_promise(async move {
+            let start = js_date_to_naive_date(&start)?;
+            let end = js_date_to_naive_date(&end)?;
+            let dimensions = group_by
+                .iter()
+                .map(|d| d.parse::<Dimension>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| JsValue::from_str(&e))?;
+            let filter = parse_billable_filter(billable)?;
+            let report = workspace
+                .report(start, end, &dimensions, filter)
                 .await
-                .map_err(|e| JsValue::from_str(&format!("Failed to create log dir: {:?}", e)))?;
+                .map_err(to_js_error)?;
+
+            #[derive(serde::Serialize)]
+            struct ReportWithTable<'a> {
+                #[serde(flatten)]
+                report: &'a Report,
+                table: Vec<Vec<String>>,
+            }
+            let value = ReportWithTable {
+                table: report.table(),
+                report: &report,
+            };
+            value
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
 
-            storage
-                .write_string(&log_path, &content)
+    /// Expected versus recorded hours in `start..=end` under the config's
+    /// `[schedule]`.
+    ///
+    /// Returns Promise<object> with per-day and per-week balances.
+    #[wasm_bindgen(js_name = workingHours)]
+    pub fn working_hours(&self, start: js_sys::Date, end: js_sys::Date) -> js_sys::Promise {
+        let logs = self.inner.logs().clone();
+        let schedule = self.inner.config().schedule.clone();
+
+        future_to_promise(async move {
+            let start = js_date_to_naive_date(&start)?;
+            let end = js_date_to_naive_date(&end)?;
+            let hours = logs
+                .working_hours(&schedule, start, end)
                 .await
-                .map_err(|e| JsValue::from_str(&format!("Failed to write log: {:?}", e)))?;
+                .map_err(to_js_error)?;
+            hours
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
 
-            Ok(JsValue::undefined())
+    /// Time recorded in `start..=end`, raw and rounded per the rounding
+    /// policy of the timesheet audience named `audience`. Only the sessions
+    /// that audience includes count.
+    ///
+    /// Returns Promise<object> with per-session and per-day figures.
+    #[wasm_bindgen(js_name = roundedTime)]
+    pub fn rounded_time(
+        &self,
+        audience: String,
+        start: js_sys::Date,
+        end: js_sys::Date,
+    ) -> js_sys::Promise {
+        let workspace = self.inner.clone();
+
+        future_to_promise(async move {
+            let start = js_date_to_naive_date(&start)?;
+            let end = js_date_to_naive_date(&end)?;
+            let rounded = workspace
+                .rounded_time(&audience, start, end)
+                .await
+                .map_err(to_js_error)?;
+            rounded
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
         })
     }
 
-    /// Get a plan by ID.
+    /// Logged time in `start..=end` charged at the config's `[[rate]]`s.
     ///
-    /// Returns Promise<Plan>.
-    #[wasm_bindgen(js_name = getPlan)]
-    pub fn get_plan(&self, plan_id: &str) -> js_sys::Promise {
-        let storage: JsStorage = self.storage.clone().unchecked_into();
-        let plan_id = plan_id.to_string();
+    /// `kind` is "revenue" (the default) or "cost". With `audience`, only
+    /// the sessions that timesheet audience includes are charged, its rates
+    /// apply and time is rounded per its `rounding`. Sessions with several
+    /// trackers are split per the config's `allocation`.
+    ///
+    /// Returns Promise<object> with per-day charges and totals by currency.
+    #[wasm_bindgen(js_name = charges)]
+    pub fn charges(
+        &self,
+        start: js_sys::Date,
+        end: js_sys::Date,
+        kind: Option<String>,
+        audience: Option<String>,
+    ) -> js_sys::Promise {
+        let workspace = self.inner.clone();
 
         future_to_promise(async move {
-            let plan_path = format!("{}/{}.toml", storage.plan_dir(), plan_id);
+            let start = js_date_to_naive_date(&start)?;
+            let end = js_date_to_naive_date(&end)?;
+            let kind: RateKind = kind
+                .map(|k| k.parse().map_err(|e: String| JsValue::from_str(&e)))
+                .transpose()?
+                .unwrap_or_default();
+            let charges = workspace
+                .charges(start, end, kind, audience.as_deref())
+                .await
+                .map_err(to_js_error)?;
+            charges
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
+
+    /// Progress of the config's budgets and targets in their current periods.
+    ///
+    /// Returns Promise<object[]>, each with `warning` set when a budget is
+    /// exceeded or on course to be.
+    #[wasm_bindgen(js_name = budgetStatus)]
+    pub fn budget_status(&self, today: js_sys::Date) -> js_sys::Promise {
+        let logs = self.inner.logs().clone();
+        let budgets = self.inner.config().budget.clone();
+        let allocation = self.inner.config().allocation.clone();
 
-            let content = storage
-                .read_string(&plan_path)
+        future_to_promise(async move {
+            let today = js_date_to_naive_date(&today)?;
+            let statuses = logs
+                .budget_status(&budgets, today, &allocation)
                 .await
-                .map_err(|e| JsValue::from_str(&format!("Failed to read plan: {:?}", e)))?;
+                .map_err(to_js_error)?;
+
+            #[derive(serde::Serialize)]
+            struct StatusWithWarning<'a> {
+                #[serde(flatten)]
+                status: &'a BudgetStatus,
+                warning: Option<String>,
+            }
+            let values: Vec<StatusWithWarning> = statuses
+                .iter()
+                .map(|status| StatusWithWarning {
+                    status,
+                    warning: status.warning(),
+                })
+                .collect();
+            values
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
 
-            let rust_plan: RustPlan = toml::from_str(&content)
-                .map_err(|e| JsValue::from_str(&format!("Failed to parse plan: {}", e)))?;
+    /// Warnings to show before starting a session with `intent`.
+    ///
+    /// Returns Promise<string[]>.
+    #[wasm_bindgen(js_name = budgetWarnings)]
+    pub fn budget_warnings(&self, intent: &Intent, today: js_sys::Date) -> js_sys::Promise {
+        let logs = self.inner.logs().clone();
+        let budgets = self.inner.config().budget.clone();
+        let allocation = self.inner.config().allocation.clone();
+        let intent = intent.inner.clone();
 
-            let plan = Plan { inner: rust_plan };
-            Ok(JsValue::from(plan))
+        future_to_promise(async move {
+            let today = js_date_to_naive_date(&today)?;
+            let warnings = logs
+                .budget_warnings(&budgets, &intent, today, &allocation)
+                .await
+                .map_err(to_js_error)?;
+            warnings
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
         })
     }
 
-    /// Save a plan to disk.
+    /// Export the sessions in logs dated `start..=end`.
     ///
-    /// Returns Promise<void>.
-    #[wasm_bindgen(js_name = savePlan)]
-    pub fn save_plan(&self, plan: &Plan) -> js_sys::Promise {
-        let storage: JsStorage = self.storage.clone().unchecked_into();
-        let plan_inner = plan.inner.clone();
+    /// `format` is "csv", "jsonl" or "ics". Tracker names come from the
+    /// plans valid on each log's date. `billable` ("all", "billable" or
+    /// "non-billable", default all) picks which sessions are exported.
+    ///
+    /// Returns Promise<string>.
+    #[wasm_bindgen(js_name = export)]
+    pub fn export(
+        &self,
+        start: js_sys::Date,
+        end: js_sys::Date,
+        format: String,
+        billable: Option<String>,
+    ) -> js_sys::Promise {
+        let workspace = self.inner.clone();
 
         future_to_promise(async move {
-            let plan_id = plan_inner.id();
-            let plan_path = format!("{}/{}.toml", storage.plan_dir(), plan_id);
+            let format: ExportFormat = format.parse().map_err(|e: String| JsValue::from_str(&e))?;
+            let start = js_date_to_naive_date(&start)?;
+            let end = js_date_to_naive_date(&end)?;
+            let filter = parse_billable_filter(billable)?;
+            let output = workspace
+                .export(start, end, format, filter)
+                .await
+                .map_err(to_js_error)?;
+            Ok(JsValue::from_str(&output))
+        })
+    }
 
-            let content = plan_inner
-                .to_toml()
-                .map_err(|e| JsValue::from_str(&format!("Failed to serialize plan: {}", e)))?;
+    /// Import sessions from iCalendar or CSV content into the logs.
+    ///
+    /// `format` is "ics" or "csv". Events are matched to intents by the
+    /// config's import rules; with `dryRun` nothing is written.
+    ///
+    /// Returns Promise<object> listing the sessions added, duplicates,
+    /// overlaps and skipped events.
+    #[wasm_bindgen(js_name = importSessions)]
+    pub fn import_sessions(
+        &self,
+        content: String,
+        format: String,
+        dry_run: bool,
+    ) -> js_sys::Promise {
+        let workspace = self.inner.clone();
 
-            // Ensure plan directory exists
-            let plan_dir = storage.plan_dir();
-            storage
-                .create_dir_all(&plan_dir)
+        future_to_promise(async move {
+            let format: ImportFormat = format.parse().map_err(|e: String| JsValue::from_str(&e))?;
+            let report = workspace
+                .import(&content, format, dry_run)
                 .await
-                .map_err(|e| JsValue::from_str(&format!("Failed to create plan dir: {:?}", e)))?;
+                .map_err(to_js_error)?;
+            report
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
+
+    /// Import entries exported from another time tracker into the logs.
+    ///
+    /// `source` is "toggl", "clockify", "harvest" or "timewarrior", and
+    /// `mapping` the TOML mapping of projects, clients and tags to intents
+    /// (empty for none). With `dryRun` nothing is written.
+    ///
+    /// Returns Promise<object> like importSessions, with a summary of each
+    /// day in `days`.
+    #[wasm_bindgen(js_name = importFrom)]
+    pub fn import_from(
+        &self,
+        content: String,
+        source: String,
+        mapping: String,
+        dry_run: bool,
+    ) -> js_sys::Promise {
+        let workspace = self.inner.clone();
 
-            storage
-                .write_string(&plan_path, &content)
+        future_to_promise(async move {
+            let source: Source = source.parse().map_err(|e: String| JsValue::from_str(&e))?;
+            let mapping = Mapping::from_toml(&mapping).map_err(to_js_error)?;
+            let report = workspace
+                .import_from(&content, source, &mapping, dry_run)
                 .await
-                .map_err(|e| JsValue::from_str(&format!("Failed to write plan: {:?}", e)))?;
+                .map_err(to_js_error)?;
+            report
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
+
+    /// Rename files written under older, inconsistent layouts.
+    ///
+    /// Plans are moved to `<source>.<YYYYMMDD>.toml` and timesheet metadata
+    /// to `<audience>.<YYYY-MM-DD>.json.meta`. Files that can't be repaired
+    /// (not a plan, or the canonical name is taken) are left alone. With
+    /// `dryRun` nothing is changed.
+    ///
+    /// Returns Promise<object> with `renamed` ({from, to} objects) and
+    /// `skipped` ([path, reason] pairs).
+    #[wasm_bindgen(js_name = repairLayout)]
+    pub fn repair_layout(&self, dry_run: bool) -> js_sys::Promise {
+        let workspace = self.inner.clone();
+
+        future_to_promise(async move {
+            let report = workspace
+                .repair_layout(dry_run)
+                .await
+                .map_err(to_js_error)?;
+            report
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
+
+    /// Get all plans valid for a date, skipping files that fail to load.
+    ///
+    /// Returns Promise<Plan[]>.
+    #[wasm_bindgen(js_name = getPlans)]
+    pub fn get_plans(&self, date: js_sys::Date) -> js_sys::Promise {
+        let plans = self.inner.plans().clone();
+
+        future_to_promise(async move {
+            let naive_date = js_date_to_naive_date(&date)?;
+            let plans = plans.get_plans(naive_date).await.map_err(to_js_error)?;
+
+            let mut sources: Vec<_> = plans.into_iter().collect();
+            sources.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let array = js_sys::Array::new();
+            for (_, inner) in sources {
+                array.push(&JsValue::from(Plan { inner }));
+            }
+            Ok(array.into())
+        })
+    }
 
+    /// Reports for the plan files `getPlans` skips on a date because they
+    /// failed to load.
+    ///
+    /// Returns Promise<PlanFileReport[]>.
+    #[wasm_bindgen(js_name = getPlanDiagnostics)]
+    pub fn get_plan_diagnostics(&self, date: js_sys::Date) -> js_sys::Promise {
+        let plans = self.inner.plans().clone();
+
+        future_to_promise(async move {
+            let naive_date = js_date_to_naive_date(&date)?;
+            let (_, skipped) = plans
+                .get_plans_with_diagnostics(naive_date)
+                .await
+                .map_err(to_js_error)?;
+            skipped
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        })
+    }
+
+    /// Get a plan by ID, as valid on `date` (today if omitted).
+    ///
+    /// Returns Promise<Plan>.
+    #[wasm_bindgen(js_name = getPlan)]
+    pub fn get_plan(&self, plan_id: &str, date: Option<js_sys::Date>) -> js_sys::Promise {
+        let plans = self.inner.plans().clone();
+        let plan_id = plan_id.to_string();
+        let today = self.now_rust().date_naive();
+
+        future_to_promise(async move {
+            let naive_date = match date {
+                Some(date) => js_date_to_naive_date(&date)?,
+                None => today,
+            };
+            let inner = plans
+                .get_plans(naive_date)
+                .await
+                .map_err(to_js_error)?
+                .into_values()
+                .find(|plan| plan.id() == plan_id)
+                .ok_or_else(|| {
+                    JsValue::from_str(&format!("No plan '{}' valid on {}", plan_id, naive_date))
+                })?;
+            Ok(JsValue::from(Plan { inner }))
+        })
+    }
+
+    /// Save a plan to disk.
+    ///
+    /// Returns Promise<void>.
+    #[wasm_bindgen(js_name = savePlan)]
+    pub fn save_plan(&self, plan: &Plan) -> js_sys::Promise {
+        let plans = self.inner.plans().clone();
+        let plan_inner = plan.inner.clone();
+
+        future_to_promise(async move {
+            plans.write_plan(&plan_inner).await.map_err(to_js_error)?;
             Ok(JsValue::undefined())
         })
     }
@@ -153,7 +503,7 @@ impl Workspace {
     #[wasm_bindgen(js_name = now)]
     pub fn now(&self) -> js_sys::Date {
         let now_utc = chrono::Utc::now();
-        let now_local = now_utc.with_timezone(&self.config.timezone);
+        let now_local = now_utc.with_timezone(&self.inner.config().timezone);
         chrono_to_js_date(&now_local)
     }
 
@@ -167,31 +517,26 @@ impl Workspace {
     /// Get configured timezone name.
     #[wasm_bindgen(js_name = timezone)]
     pub fn timezone(&self) -> String {
-        self.config.timezone.name().to_string()
+        self.inner.config().timezone.name().to_string()
     }
 }
 
 // Internal methods
 impl Workspace {
-    async fn create_async(storage: JsStorage) -> Result<Workspace, anyhow::Error> {
-        let config_path = storage.config_file();
-        let config_content = storage
-            .read_string(&config_path)
-            .await
-            .map_err(|_| anyhow::anyhow!("Failed to read config file"))?;
-
-        let config = RustConfig::from_toml(&config_content)?;
-
-        Ok(Workspace { storage, config })
+    fn now_rust(&self) -> chrono::DateTime<chrono_tz::Tz> {
+        chrono::Utc::now().with_timezone(&self.inner.config().timezone)
     }
+}
 
-    fn log_path_for_date(storage: &JsStorage, date: &NaiveDate) -> String {
-        format!("{}/{}.toml", storage.log_dir(), date.format("%Y-%m-%d"))
-    }
+fn parse_billable_filter(billable: Option<String>) -> Result<BillableFilter, JsValue> {
+    billable
+        .map(|b| b.parse().map_err(|e: String| JsValue::from_str(&e)))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
 
-    fn now_rust(&self) -> chrono::DateTime<chrono_tz::Tz> {
-        chrono::Utc::now().with_timezone(&self.config.timezone)
-    }
+fn to_js_error(e: anyhow::Error) -> JsValue {
+    JsValue::from_str(&format!("{:#}", e))
 }
 
 // Helper functions for date/time conversion (duplicated from models.rs for now)
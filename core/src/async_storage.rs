@@ -0,0 +1,242 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::layout;
+use crate::storage::{Storage, StorageLock, VersionConflict, VersionToken};
+
+/// Asynchronous counterpart of `Storage`.
+///
+/// For backing stores whose I/O is asynchronous, such as the JavaScript
+/// storage adapter used by the wasm bindings. Directory paths stay
+/// synchronous since they're configuration, not I/O.
+///
+/// Futures aren't required to be `Send`: JavaScript values can't cross
+/// threads, and the wasm bindings run on a single-threaded executor.
+///
+/// Any `Arc<impl Storage>` is an `AsyncStorage` whose futures complete
+/// immediately, so the async managers work over synchronous storage too.
+#[allow(async_fn_in_trait)]
+pub trait AsyncStorage {
+    // Directory and file paths
+    fn root_dir(&self) -> PathBuf;
+    fn log_dir(&self) -> PathBuf;
+    fn plan_dir(&self) -> PathBuf;
+    fn identity_dir(&self) -> PathBuf;
+    fn timesheet_dir(&self) -> PathBuf;
+    fn config_file(&self) -> PathBuf;
+
+    // File operations
+    async fn read_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    async fn read_string(&self, path: &Path) -> Result<String>;
+    async fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()>;
+    async fn write_string(&self, path: &Path, data: &str) -> Result<()>;
+    async fn delete(&self, path: &Path) -> Result<()>;
+
+    // Directory operations
+    async fn exists(&self, path: &Path) -> bool;
+    async fn create_dir_all(&self, path: &Path) -> Result<()>;
+    async fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>>;
+
+    // Versioned file operations (see `Storage`)
+    async fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+        if !self.exists(path).await {
+            return Ok(None);
+        }
+        Ok(Some(VersionToken::of(&self.read_bytes(path).await?)))
+    }
+
+    async fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+        let data = self.read_string(path).await?;
+        let version = VersionToken::of(data.as_bytes());
+        Ok((data, version))
+    }
+
+    /// Write only if the file is still at `expected` (None: must not exist)
+    ///
+    /// Fails with a `VersionConflict` otherwise. Returns the new version.
+    async fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        let actual = self.version(path).await?;
+        if actual.as_ref() != expected {
+            return Err(VersionConflict {
+                path: path.to_path_buf(),
+                expected: expected.cloned(),
+                actual,
+            }
+            .into());
+        }
+        self.write_string(path, data).await?;
+        Ok(VersionToken::of(data.as_bytes()))
+    }
+
+    // Coordination and history (see `Storage`)
+    async fn lock(&self) -> Result<StorageLock> {
+        Ok(StorageLock::unlocked())
+    }
+
+    async fn commit(&self, _message: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn discard(&self) {}
+
+    // Faffage-specific path construction helpers (see `layout`)
+    fn log_file_path(&self, date: NaiveDate) -> PathBuf {
+        self.log_dir().join(layout::log_file_name(date))
+    }
+
+    fn plan_file_path(&self, source: &str, date: NaiveDate) -> PathBuf {
+        self.plan_dir().join(layout::plan_file_name(source, date))
+    }
+
+    fn timesheet_file_path(&self, audience_id: &str, date: NaiveDate) -> PathBuf {
+        self.timesheet_dir()
+            .join(layout::timesheet_file_name(audience_id, date))
+    }
+
+    fn timesheet_meta_file_path(&self, audience_id: &str, date: NaiveDate) -> PathBuf {
+        self.timesheet_dir()
+            .join(layout::timesheet_meta_file_name(audience_id, date))
+    }
+}
+
+/// Drive a future to completion on the current thread
+///
+/// For the async managers over synchronous storage, whose futures never
+/// wait on anything; this is how the synchronous managers run them. It polls
+/// in a loop without a real waker, so a future that is ever pending would
+/// spin forever.
+pub(crate) fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    use std::task::{Context, Poll, Waker};
+
+    let mut future = std::pin::pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Run one manager operation, which commits its own writes on success
+///
+/// If it fails, its uncommitted writes are discarded instead.
+pub(crate) async fn operation<S: AsyncStorage, T>(
+    storage: &S,
+    op: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    let result = op.await;
+    if result.is_err() {
+        storage.discard().await;
+    }
+    result
+}
+
+impl<S: Storage + ?Sized> AsyncStorage for Arc<S> {
+    fn root_dir(&self) -> PathBuf {
+        Storage::root_dir(self.as_ref())
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        Storage::log_dir(self.as_ref())
+    }
+
+    fn plan_dir(&self) -> PathBuf {
+        Storage::plan_dir(self.as_ref())
+    }
+
+    fn identity_dir(&self) -> PathBuf {
+        Storage::identity_dir(self.as_ref())
+    }
+
+    fn timesheet_dir(&self) -> PathBuf {
+        Storage::timesheet_dir(self.as_ref())
+    }
+
+    fn config_file(&self) -> PathBuf {
+        Storage::config_file(self.as_ref())
+    }
+
+    async fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        Storage::read_bytes(self.as_ref(), path)
+    }
+
+    async fn read_string(&self, path: &Path) -> Result<String> {
+        Storage::read_string(self.as_ref(), path)
+    }
+
+    async fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+        Storage::write_bytes(self.as_ref(), path, data)
+    }
+
+    async fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+        Storage::write_string(self.as_ref(), path, data)
+    }
+
+    async fn delete(&self, path: &Path) -> Result<()> {
+        Storage::delete(self.as_ref(), path)
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        Storage::exists(self.as_ref(), path)
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> Result<()> {
+        Storage::create_dir_all(self.as_ref(), path)
+    }
+
+    async fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        Storage::list_files(self.as_ref(), dir, pattern)
+    }
+
+    async fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+        Storage::version(self.as_ref(), path)
+    }
+
+    async fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+        Storage::read_string_versioned(self.as_ref(), path)
+    }
+
+    async fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        Storage::write_string_if(self.as_ref(), path, data, expected)
+    }
+
+    async fn lock(&self) -> Result<StorageLock> {
+        Storage::lock(self.as_ref())
+    }
+
+    async fn commit(&self, message: &str) -> Result<()> {
+        Storage::commit(self.as_ref(), message)
+    }
+
+    async fn discard(&self) {
+        Storage::discard(self.as_ref())
+    }
+
+    fn log_file_path(&self, date: NaiveDate) -> PathBuf {
+        Storage::log_file_path(self.as_ref(), date)
+    }
+
+    fn plan_file_path(&self, source: &str, date: NaiveDate) -> PathBuf {
+        Storage::plan_file_path(self.as_ref(), source, date)
+    }
+
+    fn timesheet_file_path(&self, audience_id: &str, date: NaiveDate) -> PathBuf {
+        Storage::timesheet_file_path(self.as_ref(), audience_id, date)
+    }
+
+    fn timesheet_meta_file_path(&self, audience_id: &str, date: NaiveDate) -> PathBuf {
+        Storage::timesheet_meta_file_path(self.as_ref(), audience_id, date)
+    }
+}
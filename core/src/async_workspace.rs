@@ -0,0 +1,376 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+#[cfg(feature = "sqlite")]
+use std::sync::Arc;
+
+use crate::async_storage::AsyncStorage;
+use crate::export::{self, ExportFormat, ExportRow};
+use crate::import::foreign::{self, Mapping, Source};
+use crate::import::{self, ImportFormat, ImportReport, Rules};
+use crate::layout::{self, RepairReport};
+use crate::managers::{AsyncLogManager, AsyncPlanManager};
+use crate::models::{
+    Billability, BillableFilter, Charges, Config, Dimension, Log, RateKind, Report, RoundedTime,
+    Rounding, Session,
+};
+#[cfg(feature = "sqlite")]
+use crate::sqlite_index::SqliteIndex;
+
+/// Workspace operations that combine logs, plans and config, over `AsyncStorage`
+///
+/// This is the implementation behind those operations on `Workspace`, which
+/// drives it over synchronous storage; the wasm bindings await it directly.
+#[derive(Clone)]
+pub struct AsyncWorkspace<S: AsyncStorage> {
+    storage: S,
+    config: Config,
+    logs: AsyncLogManager<S>,
+    plans: AsyncPlanManager<S>,
+}
+
+impl<S: AsyncStorage + Clone> AsyncWorkspace<S> {
+    /// Open the workspace in `storage`, reading its config file
+    pub async fn open(storage: S) -> Result<Self> {
+        let config_str = storage
+            .read_string(&storage.config_file())
+            .await
+            .context("Failed to read config file")?;
+        let config = Config::from_toml(&config_str)
+            .map_err(|e| anyhow::anyhow!("Failed to parse config: {}", e))?;
+        Ok(Self::new(storage, config))
+    }
+
+    pub fn new(storage: S, config: Config) -> Self {
+        Self {
+            logs: AsyncLogManager::new(storage.clone(), config.timezone),
+            plans: AsyncPlanManager::new(storage.clone()),
+            storage,
+            config,
+        }
+    }
+}
+
+impl<S: AsyncStorage> AsyncWorkspace<S> {
+    /// Read and update logs and plans through `index`
+    #[cfg(feature = "sqlite")]
+    pub fn with_index(self, index: Arc<SqliteIndex>) -> Self {
+        Self {
+            logs: self.logs.with_index(index.clone()),
+            plans: self.plans.with_index(index),
+            ..self
+        }
+    }
+
+    /// Get a reference to the config
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Get the log manager
+    pub fn logs(&self) -> &AsyncLogManager<S> {
+        &self.logs
+    }
+
+    /// Get the plan manager
+    pub fn plans(&self) -> &AsyncPlanManager<S> {
+        &self.plans
+    }
+
+    /// Rename files written under older, inconsistent layouts
+    ///
+    /// See `layout::repair`.
+    pub async fn repair_layout(&self, dry_run: bool) -> Result<RepairReport> {
+        layout::repair_async(&self.storage, dry_run).await
+    }
+
+    /// What decides whether time is billable on `date`
+    ///
+    /// Uses the config's roles and the plans valid on the date.
+    pub async fn billability(&self, date: NaiveDate) -> Result<Billability> {
+        Ok(Billability::new(
+            &self.config.role,
+            self.plans.get_billable(date).await?,
+        ))
+    }
+
+    /// Logs dated `start..=end` with billable resolved on every session,
+    /// keeping only the sessions `filter` admits
+    pub async fn billable_logs(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: BillableFilter,
+    ) -> Result<Vec<Log>> {
+        let mut resolved = Vec::new();
+        for log in self.logs.get_logs_between(start, end).await? {
+            let billability = self.billability(log.date).await?;
+            resolved.push(filter.filter_log(&billability.resolve(&log)));
+        }
+        Ok(resolved)
+    }
+
+    /// The log for `date` as the named audience sees it
+    ///
+    /// Only sessions the audience's `include` rule admits are kept, with
+    /// billable resolved. None if there is no log for the date.
+    pub async fn audience_log(&self, audience: &str, date: NaiveDate) -> Result<Option<Log>> {
+        let Some(audience) = self.config.audience(audience) else {
+            anyhow::bail!("No timesheet audience named '{audience}'");
+        };
+        if self.logs.get_log(date).await?.is_none() {
+            return Ok(None);
+        }
+        Ok(self
+            .billable_logs(date, date, audience.include)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// Recorded time in logs dated `start..=end`, grouped by `group_by`
+    ///
+    /// Like `AsyncLogManager::report`, but with billable resolved so reports
+    /// can be grouped by it, and only sessions `filter` admits counted.
+    pub async fn report(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &[Dimension],
+        filter: BillableFilter,
+    ) -> Result<Report> {
+        let logs = self.billable_logs(start, end, filter).await?;
+        Ok(Report::build(
+            &logs,
+            start,
+            end,
+            group_by,
+            &self.config.allocation,
+        ))
+    }
+
+    /// Time recorded in `start..=end`, raw and rounded for `audience`
+    ///
+    /// Only the sessions the audience's `include` rule admits count, rounded
+    /// per its `rounding` from the config.
+    pub async fn rounded_time(
+        &self,
+        audience: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<RoundedTime> {
+        let Some(audience) = self.config.audience(audience) else {
+            anyhow::bail!("No timesheet audience named '{audience}'");
+        };
+        let logs = self.billable_logs(start, end, audience.include).await?;
+        Ok(audience.rounding.apply(&logs))
+    }
+
+    /// Logged time in `start..=end` charged at the config's `kind` rates
+    ///
+    /// For an audience, only the sessions its `include` rule admits are
+    /// charged, its rates apply, and time is rounded per its `rounding`.
+    /// Sessions with several trackers are split per the config's
+    /// `allocation`; see `Charges::build` for which rate each part is
+    /// charged at.
+    pub async fn charges(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        kind: RateKind,
+        audience: Option<&str>,
+    ) -> Result<Charges> {
+        let (filter, rounding) = match audience {
+            Some(name) => {
+                let Some(audience) = self.config.audience(name) else {
+                    anyhow::bail!("No timesheet audience named '{name}'");
+                };
+                (audience.include, audience.rounding.clone())
+            }
+            None => (BillableFilter::All, Rounding::default()),
+        };
+        let logs = self.billable_logs(start, end, filter).await?;
+        Charges::build(
+            &logs,
+            &self.config.rate,
+            kind,
+            audience,
+            &self.config.allocation,
+            &rounding,
+        )
+    }
+
+    /// Sessions in logs dated `start..=end`, resolved for export
+    ///
+    /// Tracker names come from the plans valid on each log's date. Only
+    /// sessions `filter` admits are included.
+    pub async fn export_rows(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: BillableFilter,
+    ) -> Result<Vec<ExportRow>> {
+        let mut rows = Vec::new();
+        for log in self.billable_logs(start, end, filter).await? {
+            let trackers = self.plans.get_trackers(log.date).await?;
+            rows.extend(ExportRow::from_log(&log, &trackers));
+        }
+        Ok(rows)
+    }
+
+    /// Sessions in logs dated `start..=end`, written in `format`
+    pub async fn export(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        format: ExportFormat,
+        filter: BillableFilter,
+    ) -> Result<String> {
+        export::write(&self.export_rows(start, end, filter).await?, format)
+    }
+
+    /// Import sessions from an iCalendar or CSV file into the logs
+    ///
+    /// Events are matched to intents by the config's import rules and merged
+    /// into the log for the day they start on. Events that no rule matches,
+    /// or that duplicate or overlap logged sessions, are reported instead.
+    /// With `dry_run` nothing is written.
+    pub async fn import(
+        &self,
+        content: &str,
+        format: ImportFormat,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let rules = Rules::new(&self.config.import_rule)?;
+        let (events, skipped) = import::parse(content, format, self.config.timezone)?;
+        let (matched, unmatched) = import::match_events(events, &rules);
+
+        let mut report = ImportReport::new(dry_run);
+        report.skipped = skipped;
+        report.skipped.extend(unmatched);
+        for (date, events) in matched {
+            let intents = self.plans.get_intents(date).await?;
+            let trackers = self.plans.get_trackers(date).await?;
+            let sessions = import::sessions_for(&events, &intents);
+            report.add(
+                date,
+                self.logs
+                    .merge_sessions(date, sessions, &trackers, dry_run)
+                    .await?,
+            );
+        }
+        Ok(report)
+    }
+
+    /// Import entries exported from another time tracker into the logs
+    ///
+    /// Each entry becomes a session whose intent comes from `mapping`, and
+    /// is merged into the log for the day it starts on, as with `import`.
+    /// The report's `days` summarise what each day gained.
+    pub async fn import_from(
+        &self,
+        content: &str,
+        source: Source,
+        mapping: &Mapping,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let (entries, skipped) = foreign::parse(content, source, mapping, self.config.timezone)?;
+        let mut by_date: BTreeMap<NaiveDate, Vec<Session>> = BTreeMap::new();
+        for entry in &entries {
+            by_date
+                .entry(entry.date())
+                .or_default()
+                .push(entry.to_session(mapping));
+        }
+
+        let mut report = ImportReport::new(dry_run);
+        report.skipped = skipped;
+        for (date, sessions) in by_date {
+            let trackers = self.plans.get_trackers(date).await?;
+            report.add(
+                date,
+                self.logs
+                    .merge_sessions(date, sessions, &trackers, dry_run)
+                    .await?,
+            );
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_storage::MemoryStorage;
+    use crate::test_utils::{block_on, date, session};
+    use crate::workspace::Workspace;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn storage() -> Arc<MemoryStorage> {
+        let storage = Arc::new(MemoryStorage::new());
+        storage.add_file(
+            PathBuf::from("/faff/config.toml"),
+            r#"
+            timezone = "UTC"
+
+            [[role]]
+            name = "engineer"
+            billable = true
+            "#
+            .to_string(),
+        );
+        storage
+    }
+
+    #[test]
+    fn test_open_fails_without_config() {
+        let Err(err) = block_on(AsyncWorkspace::open(Arc::new(MemoryStorage::new()))) else {
+            panic!("opened a workspace without a config file");
+        };
+        assert!(format!("{:#}", err).contains("Failed to read config file"));
+    }
+
+    #[test]
+    fn test_matches_sync_workspace() {
+        let storage = storage();
+        let workspace = block_on(AsyncWorkspace::open(storage.clone())).unwrap();
+        let log = crate::test_utils::log(
+            10,
+            vec![
+                session(10, 9, 60).role("engineer").build(),
+                session(10, 11, 30).build(),
+            ],
+        );
+        block_on(workspace.logs().write_log(&log, &Default::default())).unwrap();
+
+        let billable =
+            block_on(workspace.billable_logs(date(1), date(31), BillableFilter::Billable)).unwrap();
+        assert_eq!(billable[0].timeline.len(), 1);
+        assert_eq!(billable[0].timeline[0].intent.billable, Some(true));
+        assert_eq!(
+            billable,
+            Workspace::with_storage(storage)
+                .unwrap()
+                .billable_logs(date(1), date(31), BillableFilter::Billable)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_repair_layout_renames_legacy_plans() {
+        let storage = storage();
+        let plan = crate::managers::plan_helpers::empty_local_plan(date(1));
+        let legacy = storage.plan_dir().join("2025-03-01.json");
+        storage.add_file(legacy.clone(), serde_json::to_string(&plan).unwrap());
+        let workspace = block_on(AsyncWorkspace::open(storage.clone())).unwrap();
+
+        let report = block_on(workspace.repair_layout(false)).unwrap();
+        assert_eq!(report.renamed.len(), 1);
+        assert_eq!(report.renamed[0].from, legacy);
+        assert_eq!(
+            block_on(workspace.plans().get_plan("local", date(1))).unwrap(),
+            Some(plan)
+        );
+    }
+}
@@ -0,0 +1,688 @@
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use ed25519_dalek::SigningKey;
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
+
+use crate::async_storage::AsyncStorage;
+use crate::storage::{Storage, StorageLock, VersionToken};
+
+/// Marks a file written by `EncryptedStorage` (format version 1), followed by
+/// the base64 of nonce and ciphertext
+const MAGIC: &str = "FAFFENC1";
+const NONCE_LEN: usize = 24;
+
+/// Symmetric key that encrypts a workspace's files
+#[derive(Clone, PartialEq, Eq)]
+pub struct WorkspaceKey([u8; 32]);
+
+impl WorkspaceKey {
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Key from a byte slice, which must be 32 bytes long
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow!("Workspace key must be 32 bytes, got {}", bytes.len()))?;
+        Ok(Self(bytes))
+    }
+
+    /// A new random key
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    /// Derive the key from an identity (HKDF-SHA256 over its private key)
+    ///
+    /// Anyone holding the identity can open the workspace, and the same
+    /// identity always yields the same key.
+    pub fn from_identity(identity: &SigningKey) -> Self {
+        let hkdf = Hkdf::<Sha256>::new(None, identity.as_bytes());
+        let mut bytes = [0u8; 32];
+        hkdf.expand(b"faff workspace encryption key v1", &mut bytes)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for WorkspaceKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WorkspaceKey(..)")
+    }
+}
+
+/// EncryptedStorage decorates a Storage (or AsyncStorage), encrypting file
+/// contents at rest with a workspace key.
+///
+/// Paths and directory structure pass through unchanged, so listing and
+/// date-based lookups keep working; only contents are encrypted, with
+/// XChaCha20-Poly1305 and a random nonce per write. The path relative to the
+/// root directory is authenticated along with the contents, so a file can't
+/// be passed off as another by moving it. Encrypted files are stored as text,
+/// which lets conditional writes go to the wrapped storage. Reading a file
+/// that isn't encrypted is an error, so plaintext put in place of an
+/// encrypted file isn't accepted; to migrate an existing workspace, run
+/// `encrypt_existing` or open it with `allow_plaintext`.
+///
+/// Files under the identity directory are never encrypted, since the
+/// workspace key may be derived from them, and neither is the config file,
+/// which `FileSystemStorage` reads directly to find the workspace layout.
+///
+/// Version tokens are the wrapped storage's, over the stored (encrypted)
+/// contents.
+#[derive(Clone)]
+pub struct EncryptedStorage<S> {
+    inner: S,
+    cipher: XChaCha20Poly1305,
+    allow_plaintext: bool,
+}
+
+impl<S> EncryptedStorage<S> {
+    pub fn new(inner: S, key: &WorkspaceKey) -> Self {
+        Self {
+            inner,
+            cipher: XChaCha20Poly1305::new(Key::from_slice(key.as_bytes())),
+            allow_plaintext: false,
+        }
+    }
+
+    /// Read files that aren't encrypted yet as they are, encrypting them the
+    /// next time they're written
+    ///
+    /// For migrating a workspace in place; once it's migrated, open it
+    /// without this so that unencrypted files are rejected.
+    pub fn allow_plaintext(mut self) -> Self {
+        self.allow_plaintext = true;
+        self
+    }
+
+    /// The wrapped storage
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Seal `data` for the file at `path` under `root`
+    fn encrypt(&self, root: &Path, path: &Path, data: &[u8]) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let aad = associated_data(root, path);
+        let payload = Payload {
+            msg: data,
+            aad: aad.as_bytes(),
+        };
+        let ciphertext = self
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), payload)
+            .map_err(|_| anyhow!("Failed to encrypt {}", path.display()))?;
+
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(sealed);
+        Ok(format!("{}{}", MAGIC, encoded))
+    }
+
+    /// Open the file at `path` under `root`, passing plaintext through only
+    /// if it's allowed
+    fn decrypt(&self, root: &Path, path: &Path, data: Vec<u8>) -> Result<Vec<u8>> {
+        let Some(encoded) = data.strip_prefix(MAGIC.as_bytes()) else {
+            if self.allow_plaintext {
+                return Ok(data);
+            }
+            anyhow::bail!(
+                "{} is not encrypted; encrypt the workspace's existing files before opening it with a key",
+                path.display()
+            );
+        };
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .with_context(|| format!("Encrypted file {} is corrupted", path.display()))?;
+        if sealed.len() < NONCE_LEN {
+            anyhow::bail!("Encrypted file {} is truncated", path.display());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let aad = associated_data(root, path);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: aad.as_bytes(),
+        };
+        self.cipher
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map_err(|_| {
+                anyhow!(
+                    "Failed to decrypt {}: wrong workspace key, or the file was corrupted or moved",
+                    path.display()
+                )
+            })
+    }
+}
+
+/// The path relative to the storage root, with `/` separators on every platform
+fn associated_data(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Files that are stored in plaintext: identities, and the config file that
+/// `FileSystemStorage` reads for the layout before any storage wraps it
+fn passes_through(path: &Path, identity_dir: &Path, config_file: &Path) -> bool {
+    path.starts_with(identity_dir) || path == config_file
+}
+
+fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC.as_bytes())
+}
+
+fn into_string(path: &Path, data: Vec<u8>) -> Result<String> {
+    String::from_utf8(data).with_context(|| format!("{} is not valid UTF-8", path.display()))
+}
+
+impl<S: Storage> EncryptedStorage<S> {
+    /// Encrypt any plaintext logs, plans and timesheets
+    ///
+    /// Returns the number of files encrypted.
+    pub fn encrypt_existing(&self) -> Result<usize> {
+        let _lock = self.inner.lock()?;
+        let count = self.encrypt_files().inspect_err(|_| self.inner.discard())?;
+        if count > 0 {
+            self.inner
+                .commit(&format!("encrypt workspace: {} files", count))?;
+        }
+        Ok(count)
+    }
+
+    fn encrypt_files(&self) -> Result<usize> {
+        let mut files = Vec::new();
+        for dir in [
+            self.inner.log_dir(),
+            self.inner.plan_dir(),
+            self.inner.timesheet_dir(),
+        ] {
+            if self.inner.exists(&dir) {
+                files.extend(self.inner.list_files(&dir, "*")?);
+            }
+        }
+
+        let mut count = 0;
+        for path in files {
+            if !self.inner.exists(&path) || self.passes_through(&path) {
+                continue;
+            }
+            let data = self.inner.read_bytes(&path)?;
+            if is_encrypted(&data) {
+                continue;
+            }
+            let sealed = self.encrypt(&self.inner.root_dir(), &path, &data)?;
+            self.inner.write_string(&path, &sealed)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn passes_through(&self, path: &Path) -> bool {
+        passes_through(path, &self.inner.identity_dir(), &self.inner.config_file())
+    }
+}
+
+impl<S: Storage> Storage for EncryptedStorage<S> {
+    fn root_dir(&self) -> PathBuf {
+        self.inner.root_dir()
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        self.inner.log_dir()
+    }
+
+    fn plan_dir(&self) -> PathBuf {
+        self.inner.plan_dir()
+    }
+
+    fn identity_dir(&self) -> PathBuf {
+        self.inner.identity_dir()
+    }
+
+    fn timesheet_dir(&self) -> PathBuf {
+        self.inner.timesheet_dir()
+    }
+
+    fn config_file(&self) -> PathBuf {
+        self.inner.config_file()
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        let data = self.inner.read_bytes(path)?;
+        if self.passes_through(path) {
+            return Ok(data);
+        }
+        self.decrypt(&self.inner.root_dir(), path, data)
+    }
+
+    fn read_string(&self, path: &Path) -> Result<String> {
+        if self.passes_through(path) {
+            return self.inner.read_string(path);
+        }
+        into_string(path, self.read_bytes(path)?)
+    }
+
+    fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+        if self.passes_through(path) {
+            return self.inner.write_bytes(path, data);
+        }
+        let sealed = self.encrypt(&self.inner.root_dir(), path, data)?;
+        self.inner.write_string(path, &sealed)
+    }
+
+    fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+        if self.passes_through(path) {
+            return self.inner.write_string(path, data);
+        }
+        self.write_bytes(path, data.as_bytes())
+    }
+
+    fn delete(&self, path: &Path) -> Result<()> {
+        self.inner.delete(path)
+    }
+
+    fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+        self.inner.version(path)
+    }
+
+    fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+        let (data, version) = self.inner.read_string_versioned(path)?;
+        if self.passes_through(path) {
+            return Ok((data, version));
+        }
+        let data = self.decrypt(&self.inner.root_dir(), path, data.into_bytes())?;
+        Ok((into_string(path, data)?, version))
+    }
+
+    fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        if self.passes_through(path) {
+            return self.inner.write_string_if(path, data, expected);
+        }
+        let sealed = self.encrypt(&self.inner.root_dir(), path, data.as_bytes())?;
+        self.inner.write_string_if(path, &sealed, expected)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        self.inner.create_dir_all(path)
+    }
+
+    fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        self.inner.list_files(dir, pattern)
+    }
+
+    fn lock(&self) -> Result<StorageLock> {
+        self.inner.lock()
+    }
+
+    fn commit(&self, message: &str) -> Result<()> {
+        self.inner.commit(message)
+    }
+
+    fn discard(&self) {
+        self.inner.discard()
+    }
+}
+
+impl<S: AsyncStorage> EncryptedStorage<S> {
+    fn passes_through_async(&self, path: &Path) -> bool {
+        passes_through(path, &self.inner.identity_dir(), &self.inner.config_file())
+    }
+}
+
+/// Encrypts over asynchronous storage too, e.g. the wasm bindings' `JsStorage`
+impl<S: AsyncStorage> AsyncStorage for EncryptedStorage<S> {
+    fn root_dir(&self) -> PathBuf {
+        self.inner.root_dir()
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        self.inner.log_dir()
+    }
+
+    fn plan_dir(&self) -> PathBuf {
+        self.inner.plan_dir()
+    }
+
+    fn identity_dir(&self) -> PathBuf {
+        self.inner.identity_dir()
+    }
+
+    fn timesheet_dir(&self) -> PathBuf {
+        self.inner.timesheet_dir()
+    }
+
+    fn config_file(&self) -> PathBuf {
+        self.inner.config_file()
+    }
+
+    async fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        let data = self.inner.read_bytes(path).await?;
+        if self.passes_through_async(path) {
+            return Ok(data);
+        }
+        self.decrypt(&self.inner.root_dir(), path, data)
+    }
+
+    async fn read_string(&self, path: &Path) -> Result<String> {
+        if self.passes_through_async(path) {
+            return self.inner.read_string(path).await;
+        }
+        into_string(path, AsyncStorage::read_bytes(self, path).await?)
+    }
+
+    async fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+        if self.passes_through_async(path) {
+            return self.inner.write_bytes(path, data).await;
+        }
+        let sealed = self.encrypt(&self.inner.root_dir(), path, data)?;
+        self.inner.write_string(path, &sealed).await
+    }
+
+    async fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+        if self.passes_through_async(path) {
+            return self.inner.write_string(path, data).await;
+        }
+        AsyncStorage::write_bytes(self, path, data.as_bytes()).await
+    }
+
+    async fn delete(&self, path: &Path) -> Result<()> {
+        self.inner.delete(path).await
+    }
+
+    async fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+        self.inner.version(path).await
+    }
+
+    async fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+        let (data, version) = self.inner.read_string_versioned(path).await?;
+        if self.passes_through_async(path) {
+            return Ok((data, version));
+        }
+        let data = self.decrypt(&self.inner.root_dir(), path, data.into_bytes())?;
+        Ok((into_string(path, data)?, version))
+    }
+
+    async fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        if self.passes_through_async(path) {
+            return self.inner.write_string_if(path, data, expected).await;
+        }
+        let sealed = self.encrypt(&self.inner.root_dir(), path, data.as_bytes())?;
+        self.inner.write_string_if(path, &sealed, expected).await
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path).await
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> Result<()> {
+        self.inner.create_dir_all(path).await
+    }
+
+    async fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        self.inner.list_files(dir, pattern).await
+    }
+
+    async fn lock(&self) -> Result<StorageLock> {
+        self.inner.lock().await
+    }
+
+    async fn commit(&self, message: &str) -> Result<()> {
+        self.inner.commit(message).await
+    }
+
+    async fn discard(&self) {
+        self.inner.discard().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system_storage::FileSystemStorage;
+    use crate::managers::{IdentityManager, LogManager};
+    use crate::memory_storage::MemoryStorage;
+    use crate::models::Intent;
+    use crate::storage::VersionConflict;
+    use crate::test_utils::block_on;
+    use chrono::{NaiveDate, TimeZone};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn log_path() -> PathBuf {
+        PathBuf::from("/faff/logs/2025-03-15.toml")
+    }
+
+    #[test]
+    fn test_contents_are_encrypted_at_rest() {
+        let inner = MemoryStorage::new();
+        let key = WorkspaceKey::generate();
+        let storage = Arc::new(EncryptedStorage::new(inner, &key));
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+
+        let intent = Intent::new(
+            Some("Acme Corp review".to_string()),
+            None,
+            None,
+            None,
+            None,
+            vec![],
+        );
+        logs.start_intent_now(intent, None, date, start, &HashMap::new())
+            .unwrap();
+
+        let raw = storage.inner().files()[&log_path()].clone();
+        assert!(is_encrypted(&raw));
+        assert!(!String::from_utf8_lossy(&raw).contains("Acme"));
+
+        let log = logs.get_log(date).unwrap().unwrap();
+        assert_eq!(
+            log.timeline[0].intent.alias.as_deref(),
+            Some("Acme Corp review")
+        );
+        assert_eq!(
+            Storage::list_files(
+                storage.as_ref(),
+                &Storage::log_dir(storage.as_ref()),
+                "*.toml"
+            )
+            .unwrap(),
+            vec![log_path()]
+        );
+    }
+
+    #[test]
+    fn test_wrong_key_fails_to_decrypt() {
+        let storage = EncryptedStorage::new(MemoryStorage::new(), &WorkspaceKey::generate());
+        Storage::write_string(&storage, &log_path(), "secret").unwrap();
+
+        let inner = MemoryStorage::new();
+        inner.restore(&storage.inner().snapshot());
+        let other = EncryptedStorage::new(inner, &WorkspaceKey::generate());
+        let err = Storage::read_string(&other, &log_path()).unwrap_err();
+        assert!(err.to_string().contains("wrong workspace key"));
+    }
+
+    #[test]
+    fn test_moved_file_fails_to_decrypt() {
+        let storage = EncryptedStorage::new(MemoryStorage::new(), &WorkspaceKey::generate());
+        Storage::write_string(&storage, &log_path(), "secret").unwrap();
+
+        let other_path = PathBuf::from("/faff/logs/2025-03-16.toml");
+        let sealed = storage.inner().files()[&log_path()].clone();
+        storage.inner().add_file(other_path.clone(), sealed);
+        let err = Storage::read_string(&storage, &other_path).unwrap_err();
+        assert!(err.to_string().contains("moved"));
+    }
+
+    #[test]
+    fn test_conditional_writes_reach_inner_storage() {
+        let storage = EncryptedStorage::new(MemoryStorage::new(), &WorkspaceKey::generate());
+        let first = Storage::write_string_if(&storage, &log_path(), "one", None).unwrap();
+        assert_eq!(
+            Storage::version(&storage, &log_path()).unwrap(),
+            Some(first.clone())
+        );
+
+        let (data, version) = Storage::read_string_versioned(&storage, &log_path()).unwrap();
+        assert_eq!((data.as_str(), &version), ("one", &first));
+        Storage::write_string_if(&storage, &log_path(), "two", Some(&version)).unwrap();
+
+        let err =
+            Storage::write_string_if(&storage, &log_path(), "three", Some(&first)).unwrap_err();
+        assert!(err.downcast_ref::<VersionConflict>().is_some());
+        assert!(is_encrypted(&storage.inner().files()[&log_path()]));
+        assert_eq!(Storage::read_string(&storage, &log_path()).unwrap(), "two");
+    }
+
+    #[test]
+    fn test_plaintext_is_migrated() {
+        let inner = MemoryStorage::new();
+        inner.add_file(log_path(), "plain");
+        inner.add_file(PathBuf::from("/faff/keys/id_me"), "private");
+        inner.add_file(PathBuf::from("/faff/config.toml"), "timezone = \"UTC\"");
+        let storage = EncryptedStorage::new(inner, &WorkspaceKey::generate()).allow_plaintext();
+
+        assert_eq!(
+            Storage::read_string(&storage, &log_path()).unwrap(),
+            "plain"
+        );
+        assert_eq!(storage.encrypt_existing().unwrap(), 1);
+        assert_eq!(storage.encrypt_existing().unwrap(), 0);
+
+        let files = storage.inner().files();
+        assert!(is_encrypted(&files[&log_path()]));
+        assert_eq!(files[&PathBuf::from("/faff/keys/id_me")], b"private");
+        assert_eq!(
+            files[&PathBuf::from("/faff/config.toml")],
+            b"timezone = \"UTC\""
+        );
+        assert_eq!(
+            Storage::read_string(&storage, &log_path()).unwrap(),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn test_plaintext_is_rejected() {
+        let storage = EncryptedStorage::new(MemoryStorage::new(), &WorkspaceKey::generate());
+        Storage::write_string(&storage, &log_path(), "secret").unwrap();
+        storage.inner().add_file(log_path(), "swapped in");
+        let err = Storage::read_string(&storage, &log_path()).unwrap_err();
+        assert!(err.to_string().contains("not encrypted"));
+
+        let inner = Arc::new(MemoryStorage::new());
+        inner.add_file(log_path(), "plain");
+        let storage = EncryptedStorage::new(inner, &WorkspaceKey::generate());
+        let err = block_on(AsyncStorage::read_string(&storage, &log_path())).unwrap_err();
+        assert!(err.to_string().contains("not encrypted"));
+    }
+
+    #[test]
+    fn test_config_stays_plaintext() {
+        let storage = EncryptedStorage::new(MemoryStorage::new(), &WorkspaceKey::generate());
+        let config = Storage::config_file(&storage);
+        Storage::write_string(&storage, &config, "timezone = \"UTC\"").unwrap();
+
+        assert_eq!(storage.inner().files()[&config], b"timezone = \"UTC\"");
+        assert_eq!(
+            Storage::read_string(&storage, &config).unwrap(),
+            "timezone = \"UTC\""
+        );
+    }
+
+    #[test]
+    fn test_encrypted_workspace_reopens() {
+        let dir = tempfile::tempdir().unwrap();
+        let faff_dir = dir.path().join(".faff");
+        std::fs::create_dir_all(faff_dir.join("journal")).unwrap();
+        std::fs::write(
+            faff_dir.join("config.toml"),
+            "timezone = \"UTC\"\n\n[directories]\nlogs = \"journal\"\n",
+        )
+        .unwrap();
+        std::fs::write(faff_dir.join("journal/2025-03-15.toml"), "plain").unwrap();
+
+        let key = WorkspaceKey::generate();
+        let storage =
+            EncryptedStorage::new(FileSystemStorage::open(faff_dir.clone()).unwrap(), &key);
+        assert_eq!(storage.encrypt_existing().unwrap(), 1);
+
+        let reopened =
+            EncryptedStorage::new(FileSystemStorage::open(faff_dir.clone()).unwrap(), &key);
+        let log = Storage::log_dir(&reopened).join("2025-03-15.toml");
+        assert_eq!(log, faff_dir.join("journal/2025-03-15.toml"));
+        assert!(is_encrypted(&std::fs::read(&log).unwrap()));
+        assert_eq!(Storage::read_string(&reopened, &log).unwrap(), "plain");
+    }
+
+    #[test]
+    fn test_key_from_identity() {
+        let storage = Arc::new(MemoryStorage::new());
+        let identities = IdentityManager::new(storage.clone());
+        let identity = identities.create_identity("me", false).unwrap();
+        let key = WorkspaceKey::from_identity(&identity);
+
+        // Same identity, same key; identity files stay readable in plaintext
+        let inner = MemoryStorage::new();
+        inner.restore(&storage.snapshot());
+        let encrypted = Arc::new(EncryptedStorage::new(inner, &key));
+        let reloaded = IdentityManager::new(encrypted)
+            .get_identity("me")
+            .unwrap()
+            .unwrap();
+        assert_eq!(WorkspaceKey::from_identity(&reloaded), key);
+        assert_ne!(
+            key,
+            WorkspaceKey::from_identity(&SigningKey::from_bytes(&[7; 32]))
+        );
+    }
+
+    #[test]
+    fn test_async_storage_round_trip() {
+        let inner = Arc::new(MemoryStorage::new());
+        let storage = EncryptedStorage::new(inner.clone(), &WorkspaceKey::generate());
+
+        block_on(AsyncStorage::write_string(&storage, &log_path(), "async")).unwrap();
+        assert!(is_encrypted(&inner.files()[&log_path()]));
+        assert_eq!(
+            block_on(AsyncStorage::read_string(&storage, &log_path())).unwrap(),
+            "async"
+        );
+    }
+}
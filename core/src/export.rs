@@ -0,0 +1,422 @@
+//! Export of logged sessions as CSV, JSON Lines or iCalendar
+//!
+//! Each session becomes one row, with its intent's fields, its trackers and
+//! their names from the plans valid on the log's date, its start and end in
+//! both UTC and the log's timezone, and its duration.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::log::Log;
+use crate::models::session::Session;
+
+/// A file format sessions can be exported as
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    /// iCalendar, with a VEVENT per closed session
+    Ical,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Ical => "ics",
+        }
+    }
+
+    /// The usual file extension, without a dot
+    pub fn extension(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" | "ndjson" => Ok(ExportFormat::Jsonl),
+            "ics" | "ical" | "icalendar" => Ok(ExportFormat::Ical),
+            other => Err(format!(
+                "Unknown export format '{other}', expected csv, jsonl or ics"
+            )),
+        }
+    }
+}
+
+/// One session, fully resolved for export
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExportRow {
+    pub date: NaiveDate,
+    pub intent_id: String,
+    pub alias: Option<String>,
+    pub role: Option<String>,
+    pub objective: Option<String>,
+    pub action: Option<String>,
+    pub subject: Option<String>,
+    pub trackers: Vec<String>,
+    /// Names of `trackers`, in the same order; the id where a plan has none
+    pub tracker_names: Vec<String>,
+    /// Unset unless the log was resolved with `Billability::resolve`
+    pub billable: Option<bool>,
+    pub start_utc: String,
+    pub end_utc: Option<String>,
+    pub start_local: String,
+    pub end_local: Option<String>,
+    pub timezone: String,
+    /// Unset while the session is still running
+    pub duration_seconds: Option<i64>,
+    pub note: Option<String>,
+    #[serde(skip)]
+    start: DateTime<Utc>,
+    #[serde(skip)]
+    end: Option<DateTime<Utc>>,
+}
+
+impl ExportRow {
+    /// Resolve a session from `log`, naming trackers from `trackers`
+    /// (tracker id to name, as from `PlanManager::get_trackers`)
+    pub fn new(log: &Log, session: &Session, trackers: &HashMap<String, String>) -> Self {
+        let intent = &session.intent;
+        let mut ids = intent.trackers.clone();
+        ids.sort();
+        ids.dedup();
+        let names = ids
+            .iter()
+            .map(|id| trackers.get(id).cloned().unwrap_or_else(|| id.clone()))
+            .collect();
+
+        let start = session.start.with_timezone(&Utc);
+        let end = session.end.map(|end| end.with_timezone(&Utc));
+        Self {
+            date: log.date,
+            intent_id: intent.effective_id(),
+            alias: intent.alias.clone(),
+            role: intent.role.clone(),
+            objective: intent.objective.clone(),
+            action: intent.action.clone(),
+            subject: intent.subject.clone(),
+            trackers: ids,
+            tracker_names: names,
+            billable: intent.billable,
+            start_utc: utc_string(&start),
+            end_utc: end.as_ref().map(utc_string),
+            start_local: local_string(&start, log.timezone),
+            end_local: end.as_ref().map(|end| local_string(end, log.timezone)),
+            timezone: log.timezone.name().to_string(),
+            duration_seconds: session.duration().ok().map(|d| d.num_seconds()),
+            note: session.note.clone(),
+            start,
+            end,
+        }
+    }
+
+    /// Rows for every session in `log`
+    pub fn from_log(log: &Log, trackers: &HashMap<String, String>) -> Vec<Self> {
+        log.timeline
+            .iter()
+            .map(|session| Self::new(log, session, trackers))
+            .collect()
+    }
+
+    fn label(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.intent_id)
+    }
+}
+
+fn utc_string(datetime: &DateTime<Utc>) -> String {
+    datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn local_string(datetime: &DateTime<Utc>, timezone: Tz) -> String {
+    datetime.with_timezone(&timezone).to_rfc3339()
+}
+
+/// Write `rows` in `format`
+pub fn write(rows: &[ExportRow], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Csv => Ok(to_csv(rows)),
+        ExportFormat::Jsonl => to_jsonl(rows),
+        ExportFormat::Ical => Ok(to_ical(rows)),
+    }
+}
+
+const CSV_HEADER: [&str; 17] = [
+    "date",
+    "intent_id",
+    "alias",
+    "role",
+    "objective",
+    "action",
+    "subject",
+    "trackers",
+    "tracker_names",
+    "billable",
+    "start_utc",
+    "end_utc",
+    "start_local",
+    "end_local",
+    "timezone",
+    "duration_seconds",
+    "note",
+];
+
+/// CSV with a header row, per RFC 4180
+///
+/// Several trackers are joined with "; " within their cell.
+pub fn to_csv(rows: &[ExportRow]) -> String {
+    let mut out = String::new();
+    push_csv_record(&mut out, CSV_HEADER.iter().map(|s| s.to_string()));
+    for row in rows {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        push_csv_record(
+            &mut out,
+            [
+                row.date.to_string(),
+                row.intent_id.clone(),
+                optional(&row.alias),
+                optional(&row.role),
+                optional(&row.objective),
+                optional(&row.action),
+                optional(&row.subject),
+                row.trackers.join("; "),
+                row.tracker_names.join("; "),
+                row.billable.map(|b| b.to_string()).unwrap_or_default(),
+                row.start_utc.clone(),
+                optional(&row.end_utc),
+                row.start_local.clone(),
+                optional(&row.end_local),
+                row.timezone.clone(),
+                row.duration_seconds
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                optional(&row.note),
+            ],
+        );
+    }
+    out
+}
+
+fn push_csv_record(out: &mut String, fields: impl IntoIterator<Item = String>) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// One JSON object per line
+pub fn to_jsonl(rows: &[ExportRow]) -> Result<String> {
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(&serde_json::to_string(row).context("Failed to serialise export row")?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// An iCalendar with a VEVENT per closed session
+///
+/// Sessions still running are left out, as an event needs an end. Event
+/// UIDs are derived from the intent and start time, so re-importing an
+/// export updates events rather than duplicating them.
+pub fn to_ical(rows: &[ExportRow]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//faff//faff-core//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for row in rows {
+        let Some(end) = row.end else {
+            continue;
+        };
+        let start = ical_datetime(&row.start);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}-{}@faff", row.intent_id, start));
+        lines.push(format!("DTSTAMP:{start}"));
+        lines.push(format!("DTSTART:{start}"));
+        lines.push(format!("DTEND:{}", ical_datetime(&end)));
+        lines.push(format!("SUMMARY:{}", ical_text(row.label())));
+
+        let mut description = Vec::new();
+        for (field, value) in [
+            ("Role", &row.role),
+            ("Objective", &row.objective),
+            ("Action", &row.action),
+            ("Subject", &row.subject),
+        ] {
+            if let Some(value) = value {
+                description.push(format!("{field}: {value}"));
+            }
+        }
+        if !row.tracker_names.is_empty() {
+            description.push(format!("Trackers: {}", row.tracker_names.join(", ")));
+        }
+        if let Some(note) = &row.note {
+            description.push(note.clone());
+        }
+        if !description.is_empty() {
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                ical_text(&description.join("\n"))
+            ));
+        }
+        if let Some(role) = &row.role {
+            lines.push(format!("CATEGORIES:{}", ical_text(role)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        fold_ical_line(&mut out, &line);
+    }
+    out
+}
+
+fn ical_datetime(datetime: &DateTime<Utc>) -> String {
+    datetime.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value (RFC 5545 section 3.3.11)
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Append a content line, folded to 75 octets, with a CRLF
+fn fold_ical_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Intent;
+    use chrono::{Duration, TimeZone};
+
+    fn log() -> Log {
+        let tz: Tz = chrono_tz::Europe::London;
+        let date = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let start = tz.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap();
+        let intent = Intent::new(
+            Some("write, review".to_string()),
+            Some("engineer".to_string()),
+            None,
+            None,
+            None,
+            vec!["acme:2".to_string(), "acme:1".to_string()],
+        );
+        Log::new(
+            date,
+            tz,
+            vec![
+                Session::new(
+                    intent.clone(),
+                    start,
+                    Some(start + Duration::minutes(90)),
+                    Some("said \"hi\"".to_string()),
+                ),
+                Session::new(intent, start + Duration::hours(2), None, None),
+            ],
+        )
+    }
+
+    fn rows() -> Vec<ExportRow> {
+        let trackers = HashMap::from([("acme:1".to_string(), "Project One".to_string())]);
+        ExportRow::from_log(&log(), &trackers)
+    }
+
+    #[test]
+    fn test_rows() {
+        let rows = rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].trackers, vec!["acme:1", "acme:2"]);
+        assert_eq!(rows[0].tracker_names, vec!["Project One", "acme:2"]);
+        assert_eq!(rows[0].start_utc, "2025-06-02T08:00:00Z");
+        assert_eq!(rows[0].start_local, "2025-06-02T09:00:00+01:00");
+        assert_eq!(rows[0].duration_seconds, Some(5400));
+        assert_eq!(rows[1].end_utc, None);
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = to_csv(&rows());
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert!(lines[0].starts_with("date,intent_id,alias,role"));
+        assert!(lines[1].contains(",\"write, review\",engineer,"));
+        assert!(lines[1].contains("acme:1; acme:2,Project One; acme:2,"));
+        assert!(lines[1].ends_with(",5400,\"said \"\"hi\"\"\""));
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn test_jsonl() {
+        let jsonl = to_jsonl(&rows()).unwrap();
+        let values: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0]["role"], "engineer");
+        assert_eq!(values[1]["duration_seconds"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_ical() {
+        let ical = to_ical(&rows());
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        // The running session is left out
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ical.contains("DTSTART:20250602T080000Z\r\n"));
+        assert!(ical.contains("DTEND:20250602T093000Z\r\n"));
+        assert!(ical.contains("SUMMARY:write\\, review\r\n"));
+        assert!(ical.lines().all(|line| line.len() <= 75));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("ICS".parse::<ExportFormat>(), Ok(ExportFormat::Ical));
+        assert_eq!("jsonl".parse::<ExportFormat>(), Ok(ExportFormat::Jsonl));
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+    }
+}
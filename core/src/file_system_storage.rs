@@ -1,43 +1,155 @@
 use anyhow::{Context, Result};
+use std::fs::{File, Metadata, OpenOptions, TryLockError};
+use std::io::{ErrorKind, Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
-use crate::storage::Storage;
+use serde::Deserialize;
+
+use crate::models::config::Directories;
+use crate::storage::{Storage, StorageLock, VersionConflict, VersionToken};
 
 /// FileSystemStorage implements the Storage trait by finding and using
 /// a .faff directory in the filesystem.
 ///
-/// It searches upward from the current working directory (or a specified directory)
-/// to find a .faff directory, then provides access to the standard faff directory structure.
+/// The workspace is found from `$FAFF_DIR`, by searching upward from the
+/// current working directory, or at the XDG default (see `new`). Each data
+/// directory can be moved elsewhere with a `[directories]` table in
+/// `config.toml`; the config file and lock stay in the `.faff` directory.
+/// Since the layout is read before any decorator wraps this storage, the
+/// config file must stay plaintext on disk (`EncryptedStorage` never
+/// encrypts it).
+///
+/// Version tokens come from file metadata rather than contents (see
+/// `metadata_version`), so checking whether a file changed doesn't read it.
 #[derive(Clone)]
 pub struct FileSystemStorage {
     faff_root: PathBuf,
     faff_dir: PathBuf,
+    log_dir: PathBuf,
+    plan_dir: PathBuf,
+    identity_dir: PathBuf,
+    timesheet_dir: PathBuf,
+    lock_timeout: Duration,
+}
+
+/// The part of config.toml the storage layout depends on
+///
+/// Parsed on its own, ignoring the rest of the file, so that a workspace can
+/// still be opened (and fixed) when other settings are wrong. The file must
+/// still be valid TOML with a valid `[directories]` table.
+#[derive(Deserialize, Default)]
+pub(crate) struct LayoutConfig {
+    #[serde(default)]
+    pub(crate) directories: Directories,
+}
+
+impl LayoutConfig {
+    /// Read the layout from `config_file`; the default if there's no file
+    ///
+    /// Reads the file straight from disk, bypassing any storage decorator,
+    /// so a storage that changes the bytes it writes must leave the config
+    /// file as it is.
+    pub(crate) fn read(config_file: &Path) -> Result<Self> {
+        if !config_file.is_file() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(config_file)
+            .with_context(|| format!("Failed to read file: {}", config_file.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid [directories] in config: {}", config_file.display()))
+    }
 }
 
 impl FileSystemStorage {
-    /// Create a new FileSystemStorage by searching for .faff directory
+    /// Lock file inside `.faff`, held with an OS advisory lock
+    const LOCK_FILE: &'static str = ".lock";
+    /// How long `lock` waits for another process before giving up
+    pub(crate) const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+    const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+    /// Environment variable naming the workspace's faff directory
+    pub const FAFF_DIR_ENV: &'static str = "FAFF_DIR";
+
+    /// Create a new FileSystemStorage for the current workspace
     ///
-    /// Starts from the current working directory and searches upward.
+    /// In order of precedence, the faff directory is:
+    /// - `$FAFF_DIR`, if set
+    /// - the nearest `.faff` directory at or above the current working directory
+    /// - `$XDG_DATA_HOME/faff` (default `~/.local/share/faff`), if it exists
     pub fn new() -> Result<Self> {
         let cwd = std::env::current_dir().context("Failed to get current working directory")?;
-        Self::from_path(cwd)
+        let env_dir = std::env::var_os(Self::FAFF_DIR_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        Self::discover(&cwd, env_dir, xdg_data_dir())
+    }
+
+    fn discover(cwd: &Path, env_dir: Option<PathBuf>, xdg_dir: Option<PathBuf>) -> Result<Self> {
+        if let Some(faff_dir) = env_dir {
+            if !faff_dir.is_dir() {
+                anyhow::bail!(
+                    "{} is set to {}, which is not a directory",
+                    Self::FAFF_DIR_ENV,
+                    faff_dir.display()
+                );
+            }
+            return Self::open(faff_dir);
+        }
+        // Only a missing workspace falls back; one that fails to open is an error
+        if let Ok(faff_root) = Self::find_faff_root(cwd) {
+            return Self::open(faff_root.join(".faff"));
+        }
+        match xdg_dir {
+            Some(faff_dir) if faff_dir.is_dir() => Self::open(faff_dir),
+            _ => anyhow::bail!(
+                "No .faff directory found from {}, and {} is not set",
+                cwd.display(),
+                Self::FAFF_DIR_ENV
+            ),
+        }
     }
 
     /// Create a new FileSystemStorage by searching for .faff directory starting from a specific path
     pub fn from_path(start_path: PathBuf) -> Result<Self> {
         let faff_root = Self::find_faff_root(&start_path)?;
-        let faff_dir = faff_root.join(".faff");
+        Self::open(faff_root.join(".faff"))
+    }
+
+    /// Create a new FileSystemStorage for a specific faff directory
+    ///
+    /// The directory holds `config.toml` and, by default, the data
+    /// directories. It doesn't have to be named `.faff`: when it is, the
+    /// workspace root is its parent, otherwise the directory itself.
+    pub fn open(faff_dir: PathBuf) -> Result<Self> {
+        let faff_root = match faff_dir.parent() {
+            Some(parent) if faff_dir.file_name() == Some(".faff".as_ref()) => parent.to_path_buf(),
+            _ => faff_dir.clone(),
+        };
+
+        let dirs = LayoutConfig::read(&faff_dir.join("config.toml"))?.directories;
+
         Ok(Self {
+            log_dir: resolve_dir(&faff_dir, dirs.logs, "logs"),
+            plan_dir: resolve_dir(&faff_dir, dirs.plans, "plans"),
+            identity_dir: resolve_dir(&faff_dir, dirs.identities, "keys"),
+            timesheet_dir: resolve_dir(&faff_dir, dirs.timesheets, "timesheets"),
             faff_root,
             faff_dir,
+            lock_timeout: Self::DEFAULT_LOCK_TIMEOUT,
         })
     }
 
+    /// Set how long `lock` waits for another process to release the workspace
+    pub fn with_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
     /// Search upward from a given path for a `.faff` directory
     ///
     /// Returns the directory containing `.faff`, not the `.faff` directory itself.
-    fn find_faff_root(start_path: &PathBuf) -> Result<PathBuf> {
-        let mut current = start_path.clone();
+    fn find_faff_root(start_path: &Path) -> Result<PathBuf> {
+        let mut current = start_path.to_path_buf();
 
         loop {
             let faff_dir = current.join(".faff");
@@ -59,25 +171,45 @@ impl FileSystemStorage {
     }
 }
 
+/// Where a data directory lives, given its override from config.toml
+pub(crate) fn resolve_dir(faff_dir: &Path, configured: Option<PathBuf>, default: &str) -> PathBuf {
+    let Some(dir) = configured else {
+        return faff_dir.join(default);
+    };
+    match (dir.strip_prefix("~"), std::env::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => faff_dir.join(dir),
+    }
+}
+
+/// The XDG default faff directory, `$XDG_DATA_HOME/faff`
+fn xdg_data_dir() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::home_dir().map(|home| home.join(".local").join("share")))?;
+    Some(data_home.join("faff"))
+}
+
 impl Storage for FileSystemStorage {
     fn root_dir(&self) -> PathBuf {
         self.faff_root.clone()
     }
 
     fn log_dir(&self) -> PathBuf {
-        self.faff_dir.join("logs")
+        self.log_dir.clone()
     }
 
     fn plan_dir(&self) -> PathBuf {
-        self.faff_dir.join("plans")
+        self.plan_dir.clone()
     }
 
     fn identity_dir(&self) -> PathBuf {
-        self.faff_dir.join("keys")
+        self.identity_dir.clone()
     }
 
     fn timesheet_dir(&self) -> PathBuf {
-        self.faff_dir.join("timesheets")
+        self.timesheet_dir.clone()
     }
 
     fn config_file(&self) -> PathBuf {
@@ -94,23 +226,11 @@ impl Storage for FileSystemStorage {
     }
 
     fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
-        // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-        }
-        std::fs::write(path, data)
-            .with_context(|| format!("Failed to write file: {}", path.display()))
+        write_atomically(path, |file| file.write_all(data)).map(|_| ())
     }
 
     fn write_string(&self, path: &Path, data: &str) -> Result<()> {
-        // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-        }
-        std::fs::write(path, data)
-            .with_context(|| format!("Failed to write file: {}", path.display()))
+        write_atomically(path, |file| file.write_all(data.as_bytes())).map(|_| ())
     }
 
     fn delete(&self, path: &Path) -> Result<()> {
@@ -118,6 +238,46 @@ impl Storage for FileSystemStorage {
             .with_context(|| format!("Failed to delete file: {}", path.display()))
     }
 
+    fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+        match std::fs::metadata(path) {
+            Ok(metadata) => Ok(Some(metadata_version(&metadata))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read file: {}", path.display())),
+        }
+    }
+
+    fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+        // Version the open file rather than the path, so the token matches
+        // the contents read even if the file is replaced in the meantime
+        let read = || -> std::io::Result<(String, VersionToken)> {
+            let mut file = File::open(path)?;
+            let version = metadata_version(&file.metadata()?);
+            let mut data = String::new();
+            file.read_to_string(&mut data)?;
+            Ok((data, version))
+        };
+        read().with_context(|| format!("Failed to read file: {}", path.display()))
+    }
+
+    fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        let actual = self.version(path)?;
+        if actual.as_ref() != expected {
+            return Err(VersionConflict {
+                path: path.to_path_buf(),
+                expected: expected.cloned(),
+                actual,
+            }
+            .into());
+        }
+        let metadata = write_atomically(path, |file| file.write_all(data.as_bytes()))?;
+        Ok(metadata_version(&metadata))
+    }
+
     fn exists(&self, path: &Path) -> bool {
         path.exists()
     }
@@ -141,6 +301,128 @@ impl Storage for FileSystemStorage {
 
         paths.context("Failed to list files")
     }
+
+    fn lock(&self) -> Result<StorageLock> {
+        let lock_path = self.faff_dir.join(Self::LOCK_FILE);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open lock file: {}", lock_path.display()))?;
+
+        let deadline = Instant::now() + self.lock_timeout;
+        loop {
+            match file.try_lock() {
+                // Closing the file releases the lock
+                Ok(()) => return Ok(StorageLock::new(file)),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    std::thread::sleep(Self::LOCK_RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => anyhow::bail!(
+                    "Workspace {} is locked by another process (waited {:?})",
+                    self.faff_root.display(),
+                    self.lock_timeout
+                ),
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| {
+                        format!("Failed to lock workspace: {}", lock_path.display())
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// Replace the file at `path` without ever leaving it partially written
+///
+/// The contents are written to a hidden temporary file in the same directory,
+/// fsynced, and then renamed over the target. Rename is atomic on the same
+/// filesystem, so readers (and a process restarted after a crash) see either
+/// the old file or the complete new one. The directory is synced afterwards so
+/// the rename itself survives a power loss.
+///
+/// Returns the metadata of the file written, taken before the rename so it
+/// can't be another writer's.
+fn write_atomically<F>(path: &Path, write_contents: F) -> Result<Metadata>
+where
+    F: FnOnce(&mut File) -> std::io::Result<()>,
+{
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(parent)
+        .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+
+    let temp_path = temp_path_for(path)?;
+    let result = (|| -> std::io::Result<Metadata> {
+        let mut file = File::create(&temp_path)?;
+        write_contents(&mut file)?;
+        file.sync_all()?;
+        let metadata = file.metadata()?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(metadata)
+    })();
+
+    let metadata = match result {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            // Best effort: the target is untouched, so only the temp file needs cleaning up
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e).with_context(|| format!("Failed to write file: {}", path.display()));
+        }
+    };
+
+    sync_dir(parent).with_context(|| format!("Failed to sync directory: {}", parent.display()))?;
+    Ok(metadata)
+}
+
+/// Version token for a file from its size, modification time and inode
+///
+/// Every write through this storage stages a new file while the old one
+/// still exists, so its inode differs from the file it replaces even when
+/// the size and (coarse) modification time don't. Edits by other programs
+/// change the modification time.
+fn metadata_version(metadata: &Metadata) -> VersionToken {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_nanos());
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(metadata);
+    #[cfg(not(unix))]
+    let inode = 0;
+    VersionToken::new(format!("{}-{}-{}", metadata.len(), modified, inode))
+}
+
+/// Hidden sibling of `path` to stage a write in
+///
+/// Leading dot and `.tmp` suffix keep leftovers from a crash out of the
+/// `*.toml`, `*.json` and `id_*` patterns used to list data files.
+fn temp_path_for(path: &Path) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .with_context(|| format!("Invalid file path: {}", path.display()))?;
+    Ok(path.with_file_name(format!(
+        ".{}.{}.{:08x}.tmp",
+        file_name,
+        std::process::id(),
+        rand::random::<u32>()
+    )))
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    // Directories can't be opened for syncing on Windows; rename is durable there
+    Ok(())
 }
 
 #[cfg(test)]
@@ -179,6 +461,98 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_discover_prefers_env_dir() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        fs::create_dir_all(project.join(".faff")).unwrap();
+        let env_dir = temp.path().join("elsewhere");
+        fs::create_dir(&env_dir).unwrap();
+
+        let storage = FileSystemStorage::discover(&project, Some(env_dir.clone()), None).unwrap();
+        assert_eq!(storage.root_dir(), env_dir);
+        assert_eq!(storage.config_file(), env_dir.join("config.toml"));
+
+        let missing = temp.path().join("missing");
+        assert!(FileSystemStorage::discover(&project, Some(missing), None).is_err());
+    }
+
+    #[test]
+    fn test_discover_falls_back_to_xdg_dir() {
+        let temp = TempDir::new().unwrap();
+        let cwd = temp.path().join("cwd");
+        fs::create_dir(&cwd).unwrap();
+        let xdg_dir = temp.path().join("data").join("faff");
+
+        assert!(FileSystemStorage::discover(&cwd, None, Some(xdg_dir.clone())).is_err());
+
+        fs::create_dir_all(&xdg_dir).unwrap();
+        let storage = FileSystemStorage::discover(&cwd, None, Some(xdg_dir.clone())).unwrap();
+        assert_eq!(storage.log_dir(), xdg_dir.join("logs"));
+
+        // A workspace found from the working directory wins
+        fs::create_dir(cwd.join(".faff")).unwrap();
+        let storage = FileSystemStorage::discover(&cwd, None, Some(xdg_dir)).unwrap();
+        assert_eq!(storage.root_dir(), cwd);
+    }
+
+    #[test]
+    fn test_discover_reports_broken_workspace() {
+        let temp = TempDir::new().unwrap();
+        let cwd = temp.path().join("cwd");
+        fs::create_dir_all(cwd.join(".faff")).unwrap();
+        fs::write(cwd.join(".faff").join("config.toml"), "[directories\n").unwrap();
+        let xdg_dir = temp.path().join("data").join("faff");
+        fs::create_dir_all(&xdg_dir).unwrap();
+
+        // The config is at fault, rather than the XDG workspace being used instead
+        let err = FileSystemStorage::discover(&cwd, None, Some(xdg_dir))
+            .err()
+            .unwrap();
+        assert!(format!("{:#}", err).contains("config.toml"));
+    }
+
+    #[test]
+    fn test_directories_from_config() {
+        let temp = TempDir::new().unwrap();
+        let faff_dir = temp.path().join(".faff");
+        fs::create_dir(&faff_dir).unwrap();
+        let vault = temp.path().join("vault").join("Timesheets");
+        fs::write(
+            faff_dir.join("config.toml"),
+            format!(
+                "timezone = \"Europe/London\"\n\n[directories]\nlogs = {:?}\nplans = \"shared/plans\"\n",
+                vault.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+
+        let storage = FileSystemStorage::from_path(temp.path().to_path_buf()).unwrap();
+        assert_eq!(storage.log_dir(), vault);
+        assert_eq!(storage.plan_dir(), faff_dir.join("shared").join("plans"));
+        assert_eq!(storage.identity_dir(), faff_dir.join("keys"));
+
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        assert_eq!(storage.log_file_path(date), vault.join("2025-03-15.toml"));
+        storage
+            .write_string(&storage.log_file_path(date), "log")
+            .unwrap();
+        assert!(vault.join("2025-03-15.toml").is_file());
+    }
+
+    #[test]
+    fn test_home_relative_directory() {
+        let Some(home) = std::env::home_dir() else {
+            return;
+        };
+        let faff_dir = Path::new("/workspace/.faff");
+        assert_eq!(
+            resolve_dir(faff_dir, Some(PathBuf::from("~/.config/faff/keys")), "keys"),
+            home.join(".config/faff/keys")
+        );
+        assert_eq!(resolve_dir(faff_dir, None, "keys"), faff_dir.join("keys"));
+    }
+
     #[test]
     fn test_storage_trait_methods() {
         let temp = TempDir::new().unwrap();
@@ -386,4 +760,134 @@ mod tests {
         assert_eq!(storage.root_dir(), cloned.root_dir());
         assert_eq!(storage.log_dir(), cloned.log_dir());
     }
+
+    /// Files in `dir` other than `keep`, e.g. leftover temp files
+    fn stray_files(dir: &Path, keep: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path != keep)
+            .collect()
+    }
+
+    #[test]
+    fn test_write_replaces_existing_file() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join(".faff")).unwrap();
+        let storage = FileSystemStorage::from_path(temp.path().to_path_buf()).unwrap();
+
+        let log_file = storage.log_dir().join("2025-03-15.toml");
+        storage.write_string(&log_file, "old").unwrap();
+        storage.write_string(&log_file, "new").unwrap();
+
+        assert_eq!(storage.read_string(&log_file).unwrap(), "new");
+        assert!(stray_files(&storage.log_dir(), &log_file).is_empty());
+    }
+
+    #[test]
+    fn test_versions_follow_writes() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join(".faff")).unwrap();
+        let storage = FileSystemStorage::from_path(temp.path().to_path_buf()).unwrap();
+        let log_file = storage.log_dir().join("2025-03-15.toml");
+        assert_eq!(storage.version(&log_file).unwrap(), None);
+
+        let first = storage.write_string_if(&log_file, "same", None).unwrap();
+        assert_eq!(storage.version(&log_file).unwrap().as_ref(), Some(&first));
+        assert_eq!(
+            storage.read_string_versioned(&log_file).unwrap(),
+            ("same".to_string(), first.clone())
+        );
+
+        // Rewriting identical contents is still a new version
+        let second = storage
+            .write_string_if(&log_file, "same", Some(&first))
+            .unwrap();
+        assert_ne!(first, second);
+        let err = storage
+            .write_string_if(&log_file, "other", Some(&first))
+            .unwrap_err();
+        assert!(err.downcast_ref::<VersionConflict>().is_some());
+        assert_eq!(storage.read_string(&log_file).unwrap(), "same");
+    }
+
+    #[test]
+    fn test_interrupted_write_keeps_original_file() {
+        let temp = TempDir::new().unwrap();
+        let log_file = temp.path().join("2025-03-15.toml");
+        fs::write(&log_file, "complete log").unwrap();
+
+        // Simulate a full disk: half the data is written, then the write fails
+        let result = write_atomically(&log_file, |file| {
+            file.write_all(b"trunc")?;
+            Err(std::io::Error::other("No space left on device"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "complete log");
+        assert!(stray_files(temp.path(), &log_file).is_empty());
+    }
+
+    #[test]
+    fn test_interrupted_first_write_leaves_no_file() {
+        let temp = TempDir::new().unwrap();
+        let log_file = temp.path().join("2025-03-15.toml");
+
+        let result = write_atomically(&log_file, |file| {
+            file.write_all(b"trunc")?;
+            Err(std::io::Error::other("interrupted"))
+        });
+
+        assert!(result.is_err());
+        assert!(!log_file.exists());
+        assert!(stray_files(temp.path(), &log_file).is_empty());
+    }
+
+    #[test]
+    fn test_leftover_temp_file_is_not_listed() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join(".faff")).unwrap();
+        let storage = FileSystemStorage::from_path(temp.path().to_path_buf()).unwrap();
+
+        // Simulate a crash between writing the temp file and renaming it
+        let log_file = storage.log_dir().join("2025-03-15.toml");
+        storage.write_string(&log_file, "complete log").unwrap();
+        fs::write(temp_path_for(&log_file).unwrap(), "trunc").unwrap();
+
+        let logs = storage.list_files(&storage.log_dir(), "*.toml").unwrap();
+        assert_eq!(logs, vec![log_file.clone()]);
+        assert_eq!(storage.read_string(&log_file).unwrap(), "complete log");
+    }
+
+    #[test]
+    fn test_lock_excludes_other_holders_until_dropped() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join(".faff")).unwrap();
+        let storage = FileSystemStorage::from_path(temp.path().to_path_buf())
+            .unwrap()
+            .with_lock_timeout(Duration::from_millis(50));
+        let other = storage.clone();
+
+        let held = storage.lock().unwrap();
+        let err = other.lock().err().expect("lock should be held");
+        assert!(err.to_string().contains("locked by another process"));
+
+        drop(held);
+        assert!(other.lock().is_ok());
+    }
+
+    #[test]
+    fn test_lock_waits_for_holder() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join(".faff")).unwrap();
+        let storage = FileSystemStorage::from_path(temp.path().to_path_buf()).unwrap();
+
+        let held = storage.lock().unwrap();
+        let other = storage.clone();
+        let waiter = std::thread::spawn(move || other.lock().map(|_| ()));
+
+        std::thread::sleep(Duration::from_millis(100));
+        drop(held);
+        assert!(waiter.join().unwrap().is_ok());
+    }
 }
@@ -0,0 +1,538 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use git2::{Index, IndexEntry, IndexTime, Oid, Repository, Signature, Sort};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::storage::{Storage, StorageLock, VersionToken};
+
+/// One commit that changed a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Revision {
+    /// Full commit hash, accepted by `GitStorage::read_file_at`
+    pub id: String,
+    pub message: String,
+    pub author: String,
+    pub time: DateTime<Utc>,
+}
+
+/// GitStorage decorates a Storage whose files live in a git working tree,
+/// committing each manager operation.
+///
+/// Writes and deletes are collected until the managers call `commit` at the
+/// end of an operation, which commits exactly those paths on top of HEAD with
+/// the operation's message (e.g. "start session: Email"). Other changes in the
+/// working tree, and anything the user has staged, are left out. Files under the identity directory are never
+/// staged, so private keys stay out of history. An operation that fails
+/// `discard`s its paths, leaving its writes uncommitted in the working tree.
+pub struct GitStorage<S: Storage> {
+    inner: S,
+    repo: Mutex<Repository>,
+    pending: Mutex<BTreeSet<PathBuf>>,
+}
+
+impl<S: Storage> GitStorage<S> {
+    /// Author used when git has no `user.name`/`user.email` configured
+    const FALLBACK_AUTHOR: (&'static str, &'static str) = ("faff", "faff@localhost");
+
+    /// Wrap storage whose root directory is inside a git repository
+    pub fn open(inner: S) -> Result<Self> {
+        let root = inner.root_dir();
+        let repo = Repository::discover(&root)
+            .with_context(|| format!("No git repository found at {}", root.display()))?;
+        if repo.is_bare() {
+            anyhow::bail!("Git repository at {} has no working tree", root.display());
+        }
+        Ok(Self {
+            inner,
+            repo: Mutex::new(repo),
+            pending: Mutex::new(BTreeSet::new()),
+        })
+    }
+
+    /// The wrapped storage
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Commits that changed `path`, newest first
+    pub fn file_history(&self, path: &Path) -> Result<Vec<Revision>> {
+        let repo = self.repo.lock().unwrap();
+        let relative = relative_path(&repo, path)?;
+        if repo.head().is_err() {
+            // No commits yet
+            return Ok(vec![]);
+        }
+
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        walk.push_head()?;
+
+        let mut revisions = Vec::new();
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            let entry_id = |commit: &git2::Commit| -> Result<Option<Oid>> {
+                Ok(match commit.tree()?.get_path(&relative) {
+                    Ok(entry) => Some(entry.id()),
+                    Err(_) => None,
+                })
+            };
+
+            let current = entry_id(&commit)?;
+            let previous = match commit.parent(0) {
+                Ok(parent) => entry_id(&parent)?,
+                Err(_) => None,
+            };
+            if current != previous {
+                let author = commit.author();
+                revisions.push(Revision {
+                    id: commit.id().to_string(),
+                    message: commit.message().unwrap_or_default().trim_end().to_string(),
+                    author: author.name().unwrap_or_default().to_string(),
+                    time: Utc
+                        .timestamp_opt(commit.time().seconds(), 0)
+                        .single()
+                        .unwrap_or_default(),
+                });
+            }
+        }
+        Ok(revisions)
+    }
+
+    /// Contents of `path` as of a revision, or None if it didn't exist then
+    pub fn read_file_at(&self, path: &Path, revision: &str) -> Result<Option<Vec<u8>>> {
+        let repo = self.repo.lock().unwrap();
+        let relative = relative_path(&repo, path)?;
+        let commit = repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .with_context(|| format!("Unknown revision: {}", revision))?;
+
+        let entry = match commit.tree()?.get_path(&relative) {
+            Ok(entry) => entry,
+            Err(_) => return Ok(None),
+        };
+        let blob = repo
+            .find_blob(entry.id())
+            .with_context(|| format!("{} is not a file at {}", path.display(), revision))?;
+        Ok(Some(blob.content().to_vec()))
+    }
+
+    fn record(&self, path: &Path) {
+        if !path.starts_with(self.inner.identity_dir()) {
+            self.pending.lock().unwrap().insert(path.to_path_buf());
+        }
+    }
+}
+
+/// Path relative to the repository's working tree, as git wants it
+fn relative_path(repo: &Repository, path: &Path) -> Result<PathBuf> {
+    let workdir = repo
+        .workdir()
+        .context("Git repository has no working tree")?;
+    // Compare canonical forms, since the workdir may be reached via symlinks
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+    let absolute = match path.parent().and_then(|p| p.canonicalize().ok()) {
+        Some(parent) => parent.join(path.file_name().unwrap_or_default()),
+        None => path.to_path_buf(),
+    };
+    absolute
+        .strip_prefix(&workdir)
+        .map(Path::to_path_buf)
+        .with_context(|| format!("{} is outside the git repository", path.display()))
+}
+
+/// Index entry for a regular file at `path` with contents `id`
+fn index_entry(path: &Path, id: Oid, size: usize) -> IndexEntry {
+    let path = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+        .into_bytes();
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: 0o100644,
+        uid: 0,
+        gid: 0,
+        file_size: size as u32,
+        id,
+        flags: path.len().min(0xfff) as u16,
+        flags_extended: 0,
+        path,
+    }
+}
+
+impl<S: Storage> Storage for GitStorage<S> {
+    fn root_dir(&self) -> PathBuf {
+        self.inner.root_dir()
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        self.inner.log_dir()
+    }
+
+    fn plan_dir(&self) -> PathBuf {
+        self.inner.plan_dir()
+    }
+
+    fn identity_dir(&self) -> PathBuf {
+        self.inner.identity_dir()
+    }
+
+    fn timesheet_dir(&self) -> PathBuf {
+        self.inner.timesheet_dir()
+    }
+
+    fn config_file(&self) -> PathBuf {
+        self.inner.config_file()
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        self.inner.read_bytes(path)
+    }
+
+    fn read_string(&self, path: &Path) -> Result<String> {
+        self.inner.read_string(path)
+    }
+
+    fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+        self.inner.write_bytes(path, data)?;
+        self.record(path);
+        Ok(())
+    }
+
+    fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+        self.inner.write_string(path, data)?;
+        self.record(path);
+        Ok(())
+    }
+
+    fn delete(&self, path: &Path) -> Result<()> {
+        self.inner.delete(path)?;
+        self.record(path);
+        Ok(())
+    }
+
+    fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+        self.inner.version(path)
+    }
+
+    fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+        self.inner.read_string_versioned(path)
+    }
+
+    fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        let version = self.inner.write_string_if(path, data, expected)?;
+        self.record(path);
+        Ok(version)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        self.inner.create_dir_all(path)
+    }
+
+    fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        self.inner.list_files(dir, pattern)
+    }
+
+    fn lock(&self) -> Result<StorageLock> {
+        self.inner.lock()
+    }
+
+    fn commit(&self, message: &str) -> Result<()> {
+        let paths = std::mem::take(&mut *self.pending.lock().unwrap());
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let repo = self.repo.lock().unwrap();
+        let relative = paths
+            .iter()
+            .map(|path| relative_path(&repo, path))
+            .collect::<Result<Vec<_>>>()?;
+        let parent = match repo.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(_) => None,
+        };
+
+        // HEAD's tree plus this operation's paths, built in memory so that
+        // whatever the user has staged stays out of the commit
+        let mut index = Index::new()?;
+        if let Some(parent) = &parent {
+            index.read_tree(&parent.tree()?)?;
+        }
+        let workdir = repo
+            .workdir()
+            .context("Git repository has no working tree")?
+            .to_path_buf();
+        for path in &relative {
+            let tracked = index.get_path(path, 0).is_some();
+            if workdir.join(path).is_file() {
+                if !tracked && repo.is_path_ignored(path)? {
+                    continue;
+                }
+                let data = std::fs::read(workdir.join(path))
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                index
+                    .add(&index_entry(path, repo.blob(&data)?, data.len()))
+                    .context("Failed to stage changes")?;
+            } else if tracked {
+                index.remove_path(path)?;
+            }
+        }
+        let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+        if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+            // Written with identical contents
+            return Ok(());
+        }
+
+        let signature = repo.signature().or_else(|_| {
+            let (name, email) = Self::FALLBACK_AUTHOR;
+            Signature::now(name, email)
+        })?;
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .with_context(|| format!("Failed to commit \"{}\"", message))?;
+
+        // Bring the committed paths in the user's index up to date, as
+        // `git commit --only` does, so they don't show as staged reverts
+        let mut user_index = repo.index().context("Failed to open git index")?;
+        for path in &relative {
+            if tree.get_path(path).is_ok() {
+                user_index.add_path(path)?;
+            } else if user_index.get_path(path, 0).is_some() {
+                user_index.remove_path(path)?;
+            }
+        }
+        user_index.write().context("Failed to update git index")?;
+        Ok(())
+    }
+
+    fn discard(&self) {
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_storage::{block_on, operation};
+    use crate::encrypted_storage::{EncryptedStorage, WorkspaceKey};
+    use crate::file_system_storage::FileSystemStorage;
+    use crate::managers::{IdentityManager, LogManager, PlanManager};
+    use crate::models::Intent;
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn git_workspace() -> (tempfile::TempDir, Arc<GitStorage<FileSystemStorage>>) {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        std::fs::create_dir(dir.path().join(".faff")).unwrap();
+        let inner = FileSystemStorage::from_path(dir.path().to_path_buf()).unwrap();
+        (dir, Arc::new(GitStorage::open(inner).unwrap()))
+    }
+
+    fn messages(storage: &GitStorage<FileSystemStorage>) -> Vec<String> {
+        let repo = storage.repo.lock().unwrap();
+        let mut walk = repo.revwalk().unwrap();
+        walk.push_head().unwrap();
+        walk.map(|oid| {
+            let commit = repo.find_commit(oid.unwrap()).unwrap();
+            commit.message().unwrap().to_string()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_manager_operations_are_committed() {
+        let (_dir, storage) = git_workspace();
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let plans = PlanManager::new(storage.clone());
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+
+        let intent = Intent::new(Some("Email".to_string()), None, None, None, None, vec![]);
+        plans.add_intent(date, intent.clone()).unwrap();
+        logs.start_intent_now(intent, None, date, start, &HashMap::new())
+            .unwrap();
+        logs.stop_current_session(date, start + chrono::Duration::hours(1), &HashMap::new())
+            .unwrap();
+
+        assert_eq!(
+            messages(&storage),
+            vec![
+                "stop session: Email",
+                "start session: Email",
+                "add intent: Email"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_log_history() {
+        let (_dir, storage) = git_workspace();
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let path = logs.log_file_path(date);
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+
+        assert!(storage.file_history(&path).unwrap().is_empty());
+
+        let intent = Intent::new(Some("Email".to_string()), None, None, None, None, vec![]);
+        logs.start_intent_now(intent, None, date, start, &HashMap::new())
+            .unwrap();
+        let started = storage.read_string(&path).unwrap();
+        logs.stop_current_session(date, start + chrono::Duration::hours(1), &HashMap::new())
+            .unwrap();
+        // Unrelated changes don't show up in the log's history
+        PlanManager::new(storage.clone())
+            .add_tracker(date, "work:1", "Work")
+            .unwrap();
+
+        let history = storage.file_history(&path).unwrap();
+        let history_messages: Vec<_> = history.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            history_messages,
+            vec!["stop session: Email", "start session: Email"]
+        );
+        assert_eq!(
+            storage.read_file_at(&path, &history[1].id).unwrap(),
+            Some(started.into_bytes())
+        );
+
+        logs.delete_log(date).unwrap();
+        let head = &storage.file_history(&path).unwrap()[0];
+        assert_eq!(head.message, format!("delete log {}", date));
+        assert_eq!(storage.read_file_at(&path, "HEAD").unwrap(), None);
+    }
+
+    #[test]
+    fn test_identities_are_never_committed() {
+        let (_dir, storage) = git_workspace();
+        IdentityManager::new(storage.clone())
+            .create_identity("work", false)
+            .unwrap();
+        PlanManager::new(storage.clone())
+            .add_tracker(
+                NaiveDate::from_ymd_opt(2025, 3, 15).unwrap(),
+                "work:1",
+                "Work",
+            )
+            .unwrap();
+
+        let key_path = storage.identity_dir().join("id_work");
+        assert!(storage.exists(&key_path));
+        assert!(storage.file_history(&key_path).unwrap().is_empty());
+        assert_eq!(storage.read_file_at(&key_path, "HEAD").unwrap(), None);
+    }
+
+    #[test]
+    fn test_failed_operation_is_not_committed_later() {
+        let (_dir, storage) = git_workspace();
+        let plans = PlanManager::new(storage.clone());
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let stray = storage.log_file_path(date);
+
+        let failed: Result<()> = block_on(operation(&storage, async {
+            storage.create_dir_all(&storage.log_dir())?;
+            Storage::write_string(storage.as_ref(), &stray, "half done")?;
+            anyhow::bail!("operation failed after writing")
+        }));
+        assert!(failed.is_err());
+        plans.add_tracker(date, "work:1", "Work").unwrap();
+
+        assert_eq!(messages(&storage), vec!["add tracker: work:1"]);
+        assert!(storage.file_history(&stray).unwrap().is_empty());
+        assert!(storage.exists(&stray));
+    }
+
+    #[test]
+    fn test_user_staged_changes_are_not_committed() {
+        let (dir, storage) = git_workspace();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        std::fs::write(dir.path().join("notes.md"), "draft").unwrap();
+        {
+            let repo = storage.repo.lock().unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("notes.md")).unwrap();
+            index.write().unwrap();
+        }
+
+        PlanManager::new(storage.clone())
+            .add_tracker(date, "work:1", "Work")
+            .unwrap();
+
+        let notes = dir.path().join("notes.md");
+        assert_eq!(messages(&storage), vec!["add tracker: work:1"]);
+        assert_eq!(storage.read_file_at(&notes, "HEAD").unwrap(), None);
+        let repo = storage.repo.lock().unwrap();
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("notes.md"), 0).is_some());
+        // The committed plan isn't left looking staged
+        let statuses = repo.statuses(None).unwrap();
+        let changed: Vec<_> = statuses
+            .iter()
+            .filter(|entry| {
+                !entry
+                    .status()
+                    .intersects(git2::Status::WT_NEW | git2::Status::IGNORED)
+            })
+            .map(|entry| entry.path().unwrap().to_string())
+            .collect();
+        assert_eq!(changed, vec!["notes.md"]);
+    }
+
+    #[test]
+    fn test_encrypting_existing_files_is_committed() {
+        let (dir, storage) = git_workspace();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        PlanManager::new(storage.clone())
+            .add_tracker(date, "work:1", "Work")
+            .unwrap();
+
+        let inner = FileSystemStorage::from_path(dir.path().to_path_buf()).unwrap();
+        let encrypted =
+            EncryptedStorage::new(GitStorage::open(inner).unwrap(), &WorkspaceKey::generate());
+        assert_eq!(encrypted.encrypt_existing().unwrap(), 1);
+        assert_eq!(
+            messages(encrypted.inner()),
+            vec!["encrypt workspace: 1 files", "add tracker: work:1"]
+        );
+    }
+
+    #[test]
+    fn test_open_requires_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".faff")).unwrap();
+        let inner = FileSystemStorage::from_path(dir.path().to_path_buf()).unwrap();
+        assert!(GitStorage::open(inner).is_err());
+    }
+}
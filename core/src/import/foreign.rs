@@ -0,0 +1,607 @@
+//! Import of entries exported from other time trackers
+//!
+//! Toggl, Clockify and Harvest detailed reports are read as CSV, and
+//! Timewarrior as the JSON from `timew export`. Each entry's project, client
+//! and tags are turned into an intent by a [`Mapping`].
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{parse_csv_records, Skipped};
+use crate::models::intent::Intent;
+use crate::models::session::Session;
+
+/// A time tracker whose exports can be imported
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// Toggl Track detailed report, CSV
+    Toggl,
+    /// Clockify detailed report, CSV
+    Clockify,
+    /// Harvest detailed time report, CSV
+    ///
+    /// Harvest records hours but not times, so each day's entries are laid
+    /// end to end from the mapping's `day_start`.
+    Harvest,
+    /// Timewarrior, the JSON written by `timew export`
+    Timewarrior,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Source::Toggl => "toggl",
+            Source::Clockify => "clockify",
+            Source::Harvest => "harvest",
+            Source::Timewarrior => "timewarrior",
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "toggl" => Ok(Source::Toggl),
+            "clockify" => Ok(Source::Clockify),
+            "harvest" => Ok(Source::Harvest),
+            "timewarrior" | "timew" => Ok(Source::Timewarrior),
+            other => Err(format!(
+                "Unknown time tracker '{other}', expected toggl, clockify, harvest or timewarrior"
+            )),
+        }
+    }
+}
+
+/// Intent fields to use for a project, client or tag
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MappedIntent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trackers: Vec<String>,
+}
+
+/// How entries from another time tracker become intents
+///
+/// ```toml
+/// role = "engineer"
+/// day_start = "09:00"
+///
+/// [project."Website redesign"]
+/// subject = "website"
+/// trackers = ["jira:WEB-1"]
+///
+/// [client.Acme]
+/// role = "consultant"
+///
+/// [tag.meeting]
+/// action = "meeting"
+/// ```
+///
+/// The entry's project mapping is applied first, then its client's, then
+/// each tag's in order; a field set by an earlier mapping is kept, and
+/// trackers are combined. Unmapped entries get the project as their subject
+/// and alias (or the first tag, or the description), and `role` as their
+/// role.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mapping {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Time of day Harvest entries start from, "HH:MM"; 09:00 if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_start: Option<String>,
+    /// strftime format of dates in the CSV, if not "%Y-%m-%d" or "%m/%d/%Y"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub project: BTreeMap<String, MappedIntent>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub client: BTreeMap<String, MappedIntent>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tag: BTreeMap<String, MappedIntent>,
+}
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%m/%d/%Y"];
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+impl Mapping {
+    pub fn from_toml(toml_str: &str) -> Result<Self> {
+        toml::from_str(toml_str).context("Failed to parse import mapping")
+    }
+
+    fn day_start(&self) -> Result<NaiveTime> {
+        match &self.day_start {
+            Some(value) => parse_time(value)
+                .with_context(|| format!("Invalid day_start '{value}' in import mapping")),
+            None => Ok(NaiveTime::from_hms_opt(9, 0, 0).expect("09:00 is a valid time")),
+        }
+    }
+
+    fn parse_date(&self, value: &str) -> Option<NaiveDate> {
+        match &self.date_format {
+            Some(format) => NaiveDate::parse_from_str(value, format).ok(),
+            None => DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok()),
+        }
+    }
+
+    /// The intent an entry is logged against
+    pub fn intent_for(&self, entry: &ForeignEntry) -> Intent {
+        let mappings = entry
+            .project
+            .as_ref()
+            .and_then(|project| self.project.get(project))
+            .into_iter()
+            .chain(
+                entry
+                    .client
+                    .as_ref()
+                    .and_then(|client| self.client.get(client)),
+            )
+            .chain(entry.tags.iter().filter_map(|tag| self.tag.get(tag)));
+
+        let mut mapped = MappedIntent::default();
+        for mapping in mappings {
+            let fill = |field: &mut Option<String>, value: &Option<String>| {
+                if field.is_none() {
+                    field.clone_from(value);
+                }
+            };
+            fill(&mut mapped.alias, &mapping.alias);
+            fill(&mut mapped.role, &mapping.role);
+            fill(&mut mapped.objective, &mapping.objective);
+            fill(&mut mapped.action, &mapping.action);
+            fill(&mut mapped.subject, &mapping.subject);
+            mapped.trackers.extend(mapping.trackers.iter().cloned());
+        }
+        mapped.trackers.sort();
+        mapped.trackers.dedup();
+
+        let alias = mapped.alias.or_else(|| {
+            entry
+                .project
+                .clone()
+                .or_else(|| entry.tags.first().cloned())
+                .or_else(|| entry.description.clone())
+        });
+        Intent::new(
+            alias,
+            mapped.role.or_else(|| self.role.clone()),
+            mapped.objective,
+            mapped.action,
+            mapped.subject.or_else(|| entry.project.clone()),
+            mapped.trackers,
+        )
+    }
+}
+
+/// A time entry read from another tracker's export
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ForeignEntry {
+    pub start: DateTime<Tz>,
+    pub end: DateTime<Tz>,
+    pub project: Option<String>,
+    pub client: Option<String>,
+    pub task: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl ForeignEntry {
+    /// The date of the log the entry belongs in
+    pub fn date(&self) -> NaiveDate {
+        self.start.date_naive()
+    }
+
+    pub fn to_session(&self, mapping: &Mapping) -> Session {
+        Session::new(
+            mapping.intent_for(self),
+            self.start,
+            Some(self.end),
+            self.description.clone(),
+        )
+    }
+}
+
+/// Read the entries of an export from `source`, with local times in `timezone`
+pub fn parse(
+    content: &str,
+    source: Source,
+    mapping: &Mapping,
+    timezone: Tz,
+) -> Result<(Vec<ForeignEntry>, Vec<Skipped>)> {
+    match source {
+        Source::Toggl | Source::Clockify => parse_timed_csv(content, mapping, timezone),
+        Source::Harvest => parse_harvest(content, mapping, timezone),
+        Source::Timewarrior => parse_timewarrior(content, timezone),
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+}
+
+/// CSV records with their fields looked up by case-insensitive header
+struct Table {
+    header: Vec<String>,
+    records: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(content: &str) -> Result<Self> {
+        let mut records = parse_csv_records(content.trim_start_matches('\u{feff}')).into_iter();
+        let header = records
+            .next()
+            .context("CSV file is empty")?
+            .iter()
+            .map(|h| h.trim().to_ascii_lowercase())
+            .collect();
+        let records = records
+            .filter(|record| record.iter().any(|field| !field.trim().is_empty()))
+            .collect();
+        Ok(Self { header, records })
+    }
+
+    fn column(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|h| h == name)
+    }
+
+    fn require(&self, names: &[&str]) -> Result<()> {
+        let missing: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| self.column(name).is_none())
+            .collect();
+        if !missing.is_empty() {
+            bail!("CSV file is missing columns: {}", missing.join(", "));
+        }
+        Ok(())
+    }
+
+    fn field(&self, record: &[String], name: &str) -> Option<String> {
+        self.column(name)
+            .and_then(|i| record.get(i))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Project, client, task and tags, common to all three CSV exports
+    fn entry(
+        &self,
+        record: &[String],
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+        description: &str,
+    ) -> ForeignEntry {
+        ForeignEntry {
+            start,
+            end,
+            project: self.field(record, "project"),
+            client: self.field(record, "client"),
+            task: self.field(record, "task"),
+            description: self.field(record, description),
+            tags: self
+                .field(record, "tags")
+                .map(|tags| {
+                    tags.split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// The title an entry is reported under when skipped
+fn title_of(table: &Table, record: &[String], description: &str) -> String {
+    table
+        .field(record, description)
+        .or_else(|| table.field(record, "project"))
+        .unwrap_or_default()
+}
+
+/// Toggl and Clockify, which share start and end date and time columns
+fn parse_timed_csv(
+    content: &str,
+    mapping: &Mapping,
+    timezone: Tz,
+) -> Result<(Vec<ForeignEntry>, Vec<Skipped>)> {
+    let table = Table::new(content)?;
+    table.require(&["start date", "start time", "end date", "end time"])?;
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for record in &table.records {
+        let title = title_of(&table, record, "description");
+        let time = |which: &str| -> Result<DateTime<Tz>, Skipped> {
+            let date = table.field(record, &format!("{which} date"));
+            let time = table.field(record, &format!("{which} time"));
+            let (Some(date), Some(time)) = (date, time) else {
+                return Err(Skipped::new(&title, format!("no {which}")));
+            };
+            mapping
+                .parse_date(&date)
+                .zip(parse_time(&time))
+                .and_then(|(date, time)| {
+                    timezone.from_local_datetime(&date.and_time(time)).single()
+                })
+                .ok_or_else(|| Skipped::new(&title, format!("invalid {which} '{date} {time}'")))
+        };
+        let entry = time("start").and_then(|start| {
+            let end = time("end")?;
+            if end <= start {
+                return Err(Skipped::new(&title, "ends before it starts"));
+            }
+            Ok(table.entry(record, start, end, "description"))
+        });
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(skip) => skipped.push(skip),
+        }
+    }
+    Ok((entries, skipped))
+}
+
+/// Hours as "1.5" or "1:30"
+fn parse_hours(value: &str) -> Option<Duration> {
+    let seconds = match value.split_once(':') {
+        Some((hours, minutes)) => {
+            hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60
+        }
+        None => (value.parse::<f64>().ok()? * 3600.0).round() as i64,
+    };
+    Some(Duration::seconds(seconds))
+}
+
+/// Harvest, whose entries have a date and hours but no times
+fn parse_harvest(
+    content: &str,
+    mapping: &Mapping,
+    timezone: Tz,
+) -> Result<(Vec<ForeignEntry>, Vec<Skipped>)> {
+    let table = Table::new(content)?;
+    table.require(&["date", "hours"])?;
+    let day_start = mapping.day_start()?;
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    let mut next_start: BTreeMap<NaiveDate, DateTime<Tz>> = BTreeMap::new();
+    for record in &table.records {
+        let title = title_of(&table, record, "notes");
+        let entry = (|| -> Result<ForeignEntry, Skipped> {
+            let date = table
+                .field(record, "date")
+                .ok_or_else(|| Skipped::new(&title, "no date"))?;
+            let date = mapping
+                .parse_date(&date)
+                .ok_or_else(|| Skipped::new(&title, format!("invalid date '{date}'")))?;
+            let hours = table.field(record, "hours").unwrap_or_default();
+            let duration = parse_hours(&hours)
+                .ok_or_else(|| Skipped::new(&title, format!("invalid hours '{hours}'")))?;
+            if duration <= Duration::zero() {
+                return Err(Skipped::new(&title, "no time recorded"));
+            }
+            let start = match next_start.get(&date) {
+                Some(start) => *start,
+                None => timezone
+                    .from_local_datetime(&date.and_time(day_start))
+                    .earliest()
+                    .ok_or_else(|| Skipped::new(&title, "day start does not exist"))?,
+            };
+            let end = start + duration;
+            next_start.insert(date, end);
+            Ok(table.entry(record, start, end, "notes"))
+        })();
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(skip) => skipped.push(skip),
+        }
+    }
+    Ok((entries, skipped))
+}
+
+/// An interval in the JSON written by `timew export`
+#[derive(Deserialize)]
+struct TimewInterval {
+    start: String,
+    end: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    annotation: Option<String>,
+}
+
+/// Timewarrior, whose intervals carry tags but no project or client
+///
+/// Intervals still running are skipped.
+fn parse_timewarrior(content: &str, timezone: Tz) -> Result<(Vec<ForeignEntry>, Vec<Skipped>)> {
+    let intervals: Vec<TimewInterval> =
+        serde_json::from_str(content).context("Failed to parse Timewarrior export")?;
+    let parse = |value: &str| {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+            .ok()
+            .map(|naive| Utc.from_utc_datetime(&naive).with_timezone(&timezone))
+    };
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for interval in intervals {
+        let title = interval
+            .annotation
+            .clone()
+            .unwrap_or_else(|| interval.tags.join(" "));
+        let Some(end) = interval.end.as_deref() else {
+            skipped.push(Skipped::new(&title, "still running"));
+            continue;
+        };
+        let (Some(start), Some(end)) = (parse(&interval.start), parse(end)) else {
+            skipped.push(Skipped::new(&title, "invalid start or end"));
+            continue;
+        };
+        if end <= start {
+            skipped.push(Skipped::new(&title, "ends before it starts"));
+            continue;
+        }
+        entries.push(ForeignEntry {
+            start,
+            end,
+            project: None,
+            client: None,
+            task: None,
+            description: interval.annotation.filter(|a| !a.is_empty()),
+            tags: interval.tags,
+        });
+    }
+    Ok((entries, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TZ: Tz = chrono_tz::Europe::London;
+
+    fn mapping() -> Mapping {
+        Mapping::from_toml(
+            r#"
+            role = "engineer"
+            day_start = "08:30"
+
+            [project."Website redesign"]
+            subject = "website"
+            trackers = ["jira:WEB-1"]
+
+            [client.Acme]
+            role = "consultant"
+            trackers = ["harvest:acme"]
+
+            [tag.meeting]
+            action = "meeting"
+            subject = "ignored, project set it first"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_toggl() {
+        let csv = "\u{feff}User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags\n\
+            Ana,a@x,Acme,Website redesign,,Kickoff,Yes,2025-06-02,09:00:00,2025-06-02,10:00:00,01:00:00,\"meeting, planning\"\n\
+            Ana,a@x,,,,Late,No,2025-06-02,23:30:00,2025-06-03,00:30:00,01:00:00,\n\
+            Ana,a@x,,,,Broken,No,2025-06-02,11:00:00,,,00:00:00,\n";
+
+        let (entries, skipped) = parse(csv, Source::Toggl, &mapping(), TZ).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].tags, vec!["meeting", "planning"]);
+        assert_eq!(entries[1].end - entries[1].start, Duration::hours(1));
+        assert_eq!(
+            entries[1].date(),
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()
+        );
+        assert_eq!(skipped, vec![Skipped::new("Broken", "no end")]);
+
+        let intent = mapping().intent_for(&entries[0]);
+        assert_eq!(intent.alias.as_deref(), Some("Website redesign"));
+        assert_eq!(intent.role.as_deref(), Some("consultant"));
+        assert_eq!(intent.action.as_deref(), Some("meeting"));
+        assert_eq!(intent.subject.as_deref(), Some("website"));
+        assert_eq!(
+            intent.normalized().trackers,
+            vec!["harvest:acme", "jira:WEB-1"]
+        );
+
+        // Unmapped entries fall back to the description and default role
+        let intent = mapping().intent_for(&entries[1]);
+        assert_eq!(intent.alias.as_deref(), Some("Late"));
+        assert_eq!(intent.role.as_deref(), Some("engineer"));
+        assert_eq!(intent.subject, None);
+    }
+
+    #[test]
+    fn test_clockify_us_dates() {
+        let csv = "Project,Client,Description,Task,User,Tags,Billable,Start Date,Start Time,End Date,End Time\n\
+            Website redesign,,Fix nav,,Ana,,Yes,06/02/2025,01:15:00 PM,06/02/2025,02:45:00 PM\n";
+
+        let (entries, _) = parse(csv, Source::Clockify, &Mapping::default(), TZ).unwrap();
+        assert_eq!(
+            entries[0].start,
+            TZ.with_ymd_and_hms(2025, 6, 2, 13, 15, 0).unwrap()
+        );
+        assert_eq!(entries[0].end - entries[0].start, Duration::minutes(90));
+
+        assert!(parse(
+            "Project,Start Date\n",
+            Source::Clockify,
+            &Mapping::default(),
+            TZ
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_harvest_lays_out_entries() {
+        let csv = "Date,Client,Project,Project Code,Task,Notes,Hours,Billable?\n\
+            2025-06-02,Acme,Website redesign,WEB,Design,Mockups,1.5,Yes\n\
+            2025-06-02,Acme,Website redesign,WEB,Design,,0:45,Yes\n\
+            2025-06-03,,Internal,,Admin,,zero,No\n";
+
+        let (entries, skipped) = parse(csv, Source::Harvest, &mapping(), TZ).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].start,
+            TZ.with_ymd_and_hms(2025, 6, 2, 8, 30, 0).unwrap()
+        );
+        assert_eq!(entries[1].start, entries[0].end);
+        assert_eq!(entries[1].end - entries[1].start, Duration::minutes(45));
+        assert_eq!(entries[0].description.as_deref(), Some("Mockups"));
+        assert_eq!(
+            skipped,
+            vec![Skipped::new("Internal", "invalid hours 'zero'")]
+        );
+    }
+
+    #[test]
+    fn test_timewarrior() {
+        let json = r#"[
+            {"id":2,"start":"20250602T080000Z","end":"20250602T093000Z","tags":["meeting","standup"],"annotation":"Sprint planning"},
+            {"id":1,"start":"20250602T100000Z","tags":["coding"]}
+        ]"#;
+
+        let (entries, skipped) = parse(json, Source::Timewarrior, &mapping(), TZ).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].start,
+            TZ.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap()
+        );
+        assert_eq!(skipped, vec![Skipped::new("coding", "still running")]);
+
+        let intent = mapping().intent_for(&entries[0]);
+        assert_eq!(intent.alias.as_deref(), Some("meeting"));
+        assert_eq!(intent.action.as_deref(), Some("meeting"));
+        assert_eq!(intent.role.as_deref(), Some("engineer"));
+    }
+}
@@ -0,0 +1,704 @@
+//! Import of sessions from iCalendar and CSV files
+//!
+//! Events are read from the file, matched to an intent by the first
+//! `[[import_rule]]` whose pattern matches the event's title, and merged into
+//! the log for the day they start on. Events that overlap a session already
+//! logged (or another imported event) are reported rather than merged.
+//!
+//! Exports from other time trackers are read by [`foreign`].
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::intent::Intent;
+use crate::models::log::Log;
+use crate::models::report::serialize_seconds;
+use crate::models::session::Session;
+
+pub mod foreign;
+
+/// A file format sessions can be imported from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImportFormat {
+    Csv,
+    /// iCalendar; each VEVENT with a start and end becomes a session
+    Ical,
+}
+
+impl ImportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "csv",
+            ImportFormat::Ical => "ics",
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ImportFormat::Csv),
+            "ics" | "ical" | "icalendar" => Ok(ImportFormat::Ical),
+            other => Err(format!(
+                "Unknown import format '{other}', expected csv or ics"
+            )),
+        }
+    }
+}
+
+/// An `[[import_rule]]` entry in the config
+///
+/// ```toml
+/// [[import_rule]]
+/// pattern = "(?i)^stand-?up"
+/// alias = "standup"
+/// ```
+///
+/// Events whose title matches `pattern` are logged against the intent with
+/// `alias` in the day's plans, or a bare intent with that alias if no plan
+/// has one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportRule {
+    pub pattern: String,
+    pub alias: String,
+}
+
+/// Import rules with their patterns compiled
+pub struct Rules {
+    rules: Vec<(Regex, String)>,
+}
+
+impl Rules {
+    pub fn new(rules: &[ImportRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|regex| (regex, rule.alias.clone()))
+                    .with_context(|| format!("Invalid import rule pattern '{}'", rule.pattern))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// The alias of the first rule matching `title`
+    pub fn alias_for(&self, title: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(title))
+            .map(|(_, alias)| alias.as_str())
+    }
+}
+
+/// An event read from an import file
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImportedEvent {
+    pub title: String,
+    pub start: DateTime<Tz>,
+    pub end: DateTime<Tz>,
+    pub note: Option<String>,
+}
+
+impl ImportedEvent {
+    /// The date of the log the event belongs in
+    pub fn date(&self) -> NaiveDate {
+        self.start.date_naive()
+    }
+
+    pub fn to_session(&self, intent: Intent) -> Session {
+        Session::new(intent, self.start, Some(self.end), self.note.clone())
+    }
+}
+
+/// An event that was not imported, and why
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Skipped {
+    pub title: String,
+    pub reason: String,
+}
+
+impl Skipped {
+    pub fn new(title: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Read events from `content`, with times lacking a zone taken as `timezone`
+///
+/// Events are returned in the order they appear, converted to `timezone`.
+pub fn parse(
+    content: &str,
+    format: ImportFormat,
+    timezone: Tz,
+) -> Result<(Vec<ImportedEvent>, Vec<Skipped>)> {
+    match format {
+        ImportFormat::Ical => Ok(parse_ical(content, timezone)),
+        ImportFormat::Csv => parse_csv(content, timezone),
+    }
+}
+
+/// Read the VEVENTs of an iCalendar file
+///
+/// All-day events and events without an end are skipped. Recurrence rules
+/// are not expanded; only the first occurrence is read.
+pub fn parse_ical(content: &str, timezone: Tz) -> (Vec<ImportedEvent>, Vec<Skipped>) {
+    let mut events = Vec::new();
+    let mut skipped = Vec::new();
+    let mut current: Option<Vec<IcalProperty>> = None;
+
+    for line in unfold_ical(content) {
+        let Some(property) = parse_ical_line(&line) else {
+            continue;
+        };
+        match (property.name.as_str(), property.value.as_str()) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(properties) = current.take() {
+                    match ical_event(&properties, timezone) {
+                        Ok(event) => events.push(event),
+                        Err(skip) => skipped.push(skip),
+                    }
+                }
+            }
+            _ => {
+                if let Some(properties) = current.as_mut() {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+    (events, skipped)
+}
+
+fn ical_event(properties: &[IcalProperty], timezone: Tz) -> Result<ImportedEvent, Skipped> {
+    let property = |name: &str| properties.iter().find(|p| p.name == name);
+    let title = property("SUMMARY")
+        .map(|p| unescape_ical_text(&p.value))
+        .unwrap_or_default();
+
+    let time = |name: &str| -> Result<DateTime<Tz>, Skipped> {
+        let IcalProperty { params, value, .. } =
+            property(name).ok_or_else(|| Skipped::new(&title, format!("no {name}")))?;
+        let param = |key: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        if param("VALUE") == Some("DATE") || value.len() == 8 {
+            return Err(Skipped::new(&title, "all-day event"));
+        }
+        let zone = match param("TZID") {
+            Some(tzid) => tzid
+                .parse::<Tz>()
+                .map_err(|_| Skipped::new(&title, format!("unknown time zone '{tzid}'")))?,
+            None => timezone,
+        };
+        parse_ical_datetime(value, zone, timezone)
+            .ok_or_else(|| Skipped::new(&title, format!("invalid {name} '{value}'")))
+    };
+
+    let start = time("DTSTART")?;
+    let end = time("DTEND")?;
+    if end <= start {
+        return Err(Skipped::new(&title, "ends before it starts"));
+    }
+    let note = property("DESCRIPTION")
+        .map(|p| unescape_ical_text(&p.value))
+        .filter(|note| !note.is_empty());
+    Ok(ImportedEvent {
+        title,
+        start,
+        end,
+        note,
+    })
+}
+
+/// Parse "20250602T080000Z", or "20250602T090000" in `zone`, into `timezone`
+fn parse_ical_datetime(value: &str, zone: Tz, timezone: Tz) -> Option<DateTime<Tz>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive).with_timezone(&timezone));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    zone.from_local_datetime(&naive)
+        .single()
+        .map(|dt| dt.with_timezone(&timezone))
+}
+
+/// Join folded content lines (RFC 5545 section 3.1)
+fn unfold_ical(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// A content line, e.g. "DTSTART;TZID=Europe/London:20250602T090000"
+struct IcalProperty {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+/// Split "NAME;PARAM=value:VALUE" into its parts
+fn parse_ical_line(line: &str) -> Option<IcalProperty> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_ascii_uppercase();
+    let params = parts
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            Some((
+                key.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    Some(IcalProperty {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn unescape_ical_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Columns read from CSV files, by header; the first present is used
+const CSV_TITLE_COLUMNS: [&str; 5] = ["title", "summary", "description", "alias", "subject"];
+const CSV_START_COLUMNS: [&str; 3] = ["start", "start_utc", "start_local"];
+const CSV_END_COLUMNS: [&str; 3] = ["end", "end_utc", "end_local"];
+const CSV_NOTE_COLUMNS: [&str; 2] = ["note", "notes"];
+
+/// Read a CSV file with a header row
+///
+/// Headers are matched case-insensitively: the title comes from `title`,
+/// `summary`, `description`, `alias` or `subject`, and times from
+/// `start`/`end` (or the `_utc`/`_local` columns of a faff export). Times
+/// are RFC 3339, or "YYYY-MM-DD HH:MM[:SS]" in `timezone`.
+pub fn parse_csv(content: &str, timezone: Tz) -> Result<(Vec<ImportedEvent>, Vec<Skipped>)> {
+    let mut records = parse_csv_records(content).into_iter();
+    let header: Vec<String> = records
+        .next()
+        .context("CSV file is empty")?
+        .iter()
+        .map(|h| h.trim().to_ascii_lowercase())
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|h| h == name))
+    };
+    let title_column = column(&CSV_TITLE_COLUMNS);
+    let start_column = column(&CSV_START_COLUMNS).with_context(|| {
+        format!(
+            "CSV file needs a start column ({})",
+            CSV_START_COLUMNS.join(", ")
+        )
+    })?;
+    let end_column = column(&CSV_END_COLUMNS).with_context(|| {
+        format!(
+            "CSV file needs an end column ({})",
+            CSV_END_COLUMNS.join(", ")
+        )
+    })?;
+    let note_column = column(&CSV_NOTE_COLUMNS);
+
+    let mut events = Vec::new();
+    let mut skipped = Vec::new();
+    for record in records {
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let title = field(title_column).unwrap_or_default();
+        let time = |index: usize, what: &str| -> Result<DateTime<Tz>, Skipped> {
+            let value =
+                field(Some(index)).ok_or_else(|| Skipped::new(&title, format!("no {what}")))?;
+            parse_csv_datetime(&value, timezone)
+                .ok_or_else(|| Skipped::new(&title, format!("invalid {what} '{value}'")))
+        };
+        let event = time(start_column, "start").and_then(|start| {
+            let end = time(end_column, "end")?;
+            if end <= start {
+                return Err(Skipped::new(&title, "ends before it starts"));
+            }
+            Ok(ImportedEvent {
+                title: title.clone(),
+                start,
+                end,
+                note: field(note_column),
+            })
+        });
+        match event {
+            Ok(event) => events.push(event),
+            Err(skip) => skipped.push(skip),
+        }
+    }
+    Ok((events, skipped))
+}
+
+fn parse_csv_datetime(value: &str, timezone: Tz) -> Option<DateTime<Tz>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.with_timezone(&timezone));
+    }
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .and_then(|naive| timezone.from_local_datetime(&naive).single())
+}
+
+/// Split CSV into records of fields, handling quoted fields (RFC 4180)
+fn parse_csv_records(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// What merging imported sessions into a log did, or would do
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct MergeOutcome {
+    pub added: Vec<Session>,
+    /// Sessions already in the log with the same intent and times
+    pub duplicates: Vec<Session>,
+    /// Sessions left out, each with the session it overlaps
+    pub overlaps: Vec<(Session, Session)>,
+}
+
+fn overlaps(a: &Session, b: &Session) -> bool {
+    let starts_before_end =
+        |time: DateTime<Tz>, session: &Session| session.end.is_none_or(|end| time < end);
+    starts_before_end(a.start, b) && starts_before_end(b.start, a)
+}
+
+/// Add `sessions` to `log`, leaving out duplicates and overlapping sessions
+///
+/// A session still running in the log overlaps anything after its start.
+/// The merged timeline is sorted by start time.
+pub fn merge(log: &Log, sessions: Vec<Session>) -> (Log, MergeOutcome) {
+    let mut outcome = MergeOutcome::default();
+    let mut timeline = log.timeline.clone();
+
+    for session in sessions {
+        if timeline.iter().any(|existing| {
+            existing.start == session.start
+                && existing.end == session.end
+                && existing.intent.alias == session.intent.alias
+        }) {
+            outcome.duplicates.push(session);
+        } else if let Some(existing) = timeline
+            .iter()
+            .find(|existing| overlaps(existing, &session))
+        {
+            outcome.overlaps.push((session, existing.clone()));
+        } else {
+            timeline.push(session.clone());
+            outcome.added.push(session);
+        }
+    }
+
+    timeline.sort_by_key(|session| session.start);
+    (Log::new(log.date, log.timezone, timeline), outcome)
+}
+
+/// Events with the alias they matched, by the date of the log they belong in
+pub type MatchedEvents = BTreeMap<NaiveDate, Vec<(ImportedEvent, String)>>;
+
+/// Events matched to an alias by `rules`, grouped by the log they belong in
+///
+/// Events no rule matches are returned as skipped.
+pub fn match_events(events: Vec<ImportedEvent>, rules: &Rules) -> (MatchedEvents, Vec<Skipped>) {
+    let mut matched = MatchedEvents::new();
+    let mut skipped = Vec::new();
+    for event in events {
+        match rules.alias_for(&event.title) {
+            Some(alias) => {
+                let alias = alias.to_string();
+                matched
+                    .entry(event.date())
+                    .or_default()
+                    .push((event, alias));
+            }
+            None => skipped.push(Skipped::new(&event.title, "no import rule matches")),
+        }
+    }
+    (matched, skipped)
+}
+
+/// Sessions for a day's matched events
+///
+/// Each uses the intent with the event's alias from `intents` (the day's
+/// plan intents), or a bare intent with just the alias.
+pub fn sessions_for(matched: &[(ImportedEvent, String)], intents: &[Intent]) -> Vec<Session> {
+    matched
+        .iter()
+        .map(|(event, alias)| {
+            let intent = intents
+                .iter()
+                .find(|intent| intent.alias.as_deref() == Some(alias.as_str()))
+                .cloned()
+                .unwrap_or_else(|| {
+                    Intent::new(Some(alias.clone()), None, None, None, None, vec![])
+                });
+            event.to_session(intent)
+        })
+        .collect()
+}
+
+/// What an import added to one day's log
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImportDay {
+    pub date: NaiveDate,
+    pub added: usize,
+    pub duplicates: usize,
+    pub overlaps: usize,
+    /// Time in the sessions added
+    #[serde(rename = "added_seconds", serialize_with = "serialize_seconds")]
+    pub recorded: Duration,
+}
+
+/// What an import did, or would do with `dry_run`
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub added: Vec<Session>,
+    pub duplicates: Vec<Session>,
+    pub overlaps: Vec<(Session, Session)>,
+    pub skipped: Vec<Skipped>,
+    /// A summary for each day with imported sessions, in date order
+    pub days: Vec<ImportDay>,
+}
+
+impl ImportReport {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            ..Default::default()
+        }
+    }
+
+    /// Record what merging into the log for `date` did
+    pub fn add(&mut self, date: NaiveDate, outcome: MergeOutcome) {
+        self.days.push(ImportDay {
+            date,
+            added: outcome.added.len(),
+            duplicates: outcome.duplicates.len(),
+            overlaps: outcome.overlaps.len(),
+            recorded: outcome
+                .added
+                .iter()
+                .filter_map(|session| session.duration().ok())
+                .fold(Duration::zero(), |sum, d| sum + d),
+        });
+        self.added.extend(outcome.added);
+        self.duplicates.extend(outcome.duplicates);
+        self.overlaps.extend(outcome.overlaps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TZ: Tz = chrono_tz::Europe::London;
+
+    #[test]
+    fn test_parse_ical() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Daily standup\\, team\r\n\
+            DTSTART:20250602T080000Z\r\n\
+            DTEND:20250602T081500Z\r\n\
+            DESCRIPTION:Line one\\nline \r\n two\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Review\r\n\
+            DTSTART;TZID=America/New_York:20250602T090000\r\n\
+            DTEND;TZID=America/New_York:20250602T100000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Holiday\r\n\
+            DTSTART;VALUE=DATE:20250603\r\n\
+            DTEND;VALUE=DATE:20250604\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let (events, skipped) = parse_ical(ics, TZ);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].title, "Daily standup, team");
+        assert_eq!(
+            events[0].start,
+            TZ.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap()
+        );
+        assert_eq!(events[0].note.as_deref(), Some("Line one\nline two"));
+        assert_eq!(
+            events[1].start,
+            TZ.with_ymd_and_hms(2025, 6, 2, 14, 0, 0).unwrap()
+        );
+        assert_eq!(skipped, vec![Skipped::new("Holiday", "all-day event")]);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "Title,Start,End,Note\r\n\
+            \"Write, docs\",2025-06-02 09:00,2025-06-02 10:30,\"said \"\"hi\"\"\"\r\n\
+            Broken,2025-06-02 11:00,,\r\n\
+            Export,2025-06-02T12:00:00Z,2025-06-02T13:00:00Z,\r\n";
+
+        let (events, skipped) = parse_csv(csv, TZ).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].title, "Write, docs");
+        assert_eq!(events[0].end - events[0].start, Duration::minutes(90));
+        assert_eq!(events[0].note.as_deref(), Some("said \"hi\""));
+        assert_eq!(
+            events[1].start,
+            TZ.with_ymd_and_hms(2025, 6, 2, 13, 0, 0).unwrap()
+        );
+        assert_eq!(skipped, vec![Skipped::new("Broken", "no end")]);
+
+        assert!(parse_csv("title,end\nx,2025-06-02 10:00\n", TZ).is_err());
+    }
+
+    #[test]
+    fn test_rules() {
+        let rules = Rules::new(&[
+            ImportRule {
+                pattern: "(?i)^stand-?up".to_string(),
+                alias: "standup".to_string(),
+            },
+            ImportRule {
+                pattern: ".".to_string(),
+                alias: "meeting".to_string(),
+            },
+        ])
+        .unwrap();
+        assert_eq!(rules.alias_for("Standup with team"), Some("standup"));
+        assert_eq!(rules.alias_for("Planning"), Some("meeting"));
+        assert_eq!(rules.alias_for(""), None);
+
+        assert!(Rules::new(&[ImportRule {
+            pattern: "(".to_string(),
+            alias: "x".to_string(),
+        }])
+        .is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let at = |hour, minute| TZ.with_ymd_and_hms(2025, 6, 2, hour, minute, 0).unwrap();
+        let intent =
+            |alias: &str| Intent::new(Some(alias.to_string()), None, None, None, None, vec![]);
+        let session = |alias, start, end| Session::new(intent(alias), start, end, None);
+
+        let log = Log::new(
+            date,
+            TZ,
+            vec![
+                session("work", at(10, 0), Some(at(11, 0))),
+                session("work", at(15, 0), None),
+            ],
+        );
+        let (merged, outcome) = merge(
+            &log,
+            vec![
+                session("standup", at(9, 0), Some(at(9, 15))),
+                session("work", at(10, 0), Some(at(11, 0))),
+                session("review", at(10, 30), Some(at(11, 30))),
+                session("late", at(16, 0), Some(at(17, 0))),
+                session("clash", at(9, 10), Some(at(9, 20))),
+            ],
+        );
+
+        assert_eq!(outcome.added.len(), 1);
+        assert_eq!(outcome.duplicates.len(), 1);
+        assert_eq!(outcome.overlaps.len(), 3);
+        assert_eq!(
+            outcome.overlaps[2].1.intent.alias.as_deref(),
+            Some("standup")
+        );
+        assert_eq!(merged.timeline.len(), 3);
+        assert_eq!(merged.timeline[0].intent.alias.as_deref(), Some("standup"));
+    }
+}
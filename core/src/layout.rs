@@ -0,0 +1,363 @@
+//! Canonical names of the files in a workspace
+//!
+//! Every manager, storage helper and binding builds file names through this
+//! module, so there is exactly one layout:
+//!
+//! - logs: `<YYYY-MM-DD>.toml`
+//! - plans: `<source>.<YYYYMMDD>.toml`
+//! - timesheets: `<audience_id>.<YYYY-MM-DD>.json`, with metadata in
+//!   `<audience_id>.<YYYY-MM-DD>.json.meta`
+//! - identities: `id_<name>` and `id_<name>.pub`
+//!
+//! Earlier versions didn't agree on this everywhere; `repair` renames files
+//! written under the old names.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use crate::async_storage::{block_on, AsyncStorage};
+use crate::models::Plan;
+use crate::storage::Storage;
+
+/// Glob matching log files
+pub const LOG_FILES: &str = "*.toml";
+/// Glob matching plan files
+pub const PLAN_FILES: &str = "*.toml";
+/// Glob matching timesheet files (not their metadata)
+pub const TIMESHEET_FILES: &str = "*.json";
+/// Glob matching private and public identity key files
+pub const IDENTITY_FILES: &str = "id_*";
+
+static PLAN_FILENAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<source>.+?)\.(?P<datestr>\d{8})\.toml$")
+        .expect("PLAN_FILENAME_REGEX pattern is valid")
+});
+
+/// Filename of the log for a date: `<YYYY-MM-DD>.toml`
+pub fn log_file_name(date: NaiveDate) -> String {
+    format!("{}.toml", date.format("%Y-%m-%d"))
+}
+
+/// Date of a log file, if the name is a log filename
+pub fn parse_log_file_name(filename: &str) -> Option<NaiveDate> {
+    let stem = filename.strip_suffix(".toml")?;
+    NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()
+}
+
+/// Filename of the plan for a source and date: `<source>.<YYYYMMDD>.toml`
+pub fn plan_file_name(source: &str, date: NaiveDate) -> String {
+    format!("{}.{}.toml", source, date.format("%Y%m%d"))
+}
+
+/// Split a plan filename (`<source>.<YYYYMMDD>.toml`) into source and date
+pub fn parse_plan_file_name(filename: &str) -> Option<(String, NaiveDate)> {
+    let captures = PLAN_FILENAME_REGEX.captures(filename)?;
+    // These unwraps are safe because the regex guarantees named groups exist
+    let source = captures.name("source").unwrap().as_str().to_string();
+    let datestr = captures.name("datestr").unwrap().as_str();
+    let date = NaiveDate::parse_from_str(datestr, "%Y%m%d").ok()?;
+    Some((source, date))
+}
+
+/// Filename of a timesheet: `<audience_id>.<YYYY-MM-DD>.json`
+pub fn timesheet_file_name(audience_id: &str, date: NaiveDate) -> String {
+    format!("{}.{}.json", audience_id, date.format("%Y-%m-%d"))
+}
+
+/// Filename of a timesheet's metadata, stored next to it
+pub fn timesheet_meta_file_name(audience_id: &str, date: NaiveDate) -> String {
+    format!("{}.meta", timesheet_file_name(audience_id, date))
+}
+
+/// Glob matching the timesheets for one date
+pub fn timesheet_files_on(date: NaiveDate) -> String {
+    format!("*.{}.json", date.format("%Y-%m-%d"))
+}
+
+/// Split a timesheet file stem (`<audience_id>.<YYYY-MM-DD>`) into audience and date
+///
+/// Errors describe why the file should be skipped.
+pub fn parse_timesheet_stem(stem: &str) -> Result<(&str, NaiveDate), String> {
+    let parts: Vec<&str> = stem.split('.').collect();
+    if parts.len() != 2 {
+        return Err(format!(
+            "Skipping file with unexpected format: {} ({} parts)",
+            stem,
+            parts.len()
+        ));
+    }
+
+    let date = NaiveDate::parse_from_str(parts[1], "%Y-%m-%d").map_err(|e| {
+        format!(
+            "Skipping file with invalid date format '{}': {}",
+            parts[1], e
+        )
+    })?;
+    Ok((parts[0], date))
+}
+
+/// Filename of an identity's private key
+pub fn key_file_name(name: &str) -> String {
+    format!("id_{}", name)
+}
+
+/// Filename of an identity's public key
+pub fn pub_file_name(name: &str) -> String {
+    format!("id_{}.pub", name)
+}
+
+/// A file that `repair` moves to its canonical name
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Rename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Outcome of `repair`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RepairReport {
+    /// Files moved to their canonical names (or that would be, in a dry run)
+    pub renamed: Vec<Rename>,
+    /// Misnamed files left alone, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl RepairReport {
+    pub fn is_empty(&self) -> bool {
+        self.renamed.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// Rename plan and timesheet files written under inconsistent layouts
+///
+/// Handles:
+/// - plans stored under any other name in the plan directory, such as
+///   `<YYYY-MM-DD>.json` or `<id>.toml`: the file is parsed (as TOML or JSON)
+///   and moved to `<source>.<YYYYMMDD>.toml`, converted to TOML if needed
+/// - timesheet metadata in `<audience_id>.<YYYY-MM-DD>.meta.json`, moved to
+///   `<audience_id>.<YYYY-MM-DD>.json.meta`
+///
+/// Files whose canonical name is already taken, and plan-directory files that
+/// aren't plans, are skipped and reported. With `dry_run`, nothing is changed.
+/// The whole repair is committed as one change.
+pub fn repair<S: Storage + ?Sized>(storage: &Arc<S>, dry_run: bool) -> Result<RepairReport> {
+    block_on(repair_async(storage, dry_run))
+}
+
+/// `repair` over `AsyncStorage`
+pub async fn repair_async<S: AsyncStorage>(storage: &S, dry_run: bool) -> Result<RepairReport> {
+    let _lock = storage.lock().await?;
+    let mut report = RepairReport::default();
+    let mut moves = Vec::new();
+
+    let plan_dir = storage.plan_dir();
+    for path in list_sorted(storage, &plan_dir, "*").await? {
+        let Some(filename) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if filename.starts_with('.') || parse_plan_file_name(filename).is_some() {
+            continue;
+        }
+        let plan = match read_misnamed_plan(storage, &path).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                report
+                    .skipped
+                    .push((path, format!("Not a readable plan: {:#}", e)));
+                continue;
+            }
+        };
+        let to = storage.plan_file_path(&plan.source, plan.valid_from);
+        let contents = toml::to_string_pretty(&plan)
+            .context("Failed to serialize plan to TOML")?
+            .into_bytes();
+        moves.push((path, to, contents));
+    }
+
+    let timesheet_dir = storage.timesheet_dir();
+    for path in list_sorted(storage, &timesheet_dir, "*.meta.json").await? {
+        let Some(stem) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".meta.json"))
+        else {
+            continue;
+        };
+        let (audience_id, date) = match parse_timesheet_stem(stem) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                report.skipped.push((path.clone(), reason));
+                continue;
+            }
+        };
+        let to = storage.timesheet_meta_file_path(audience_id, date);
+        let contents = storage.read_bytes(&path).await?;
+        moves.push((path, to, contents));
+    }
+
+    for (from, to, contents) in moves {
+        if storage.exists(&to).await {
+            report
+                .skipped
+                .push((from, format!("{} already exists", to.display())));
+            continue;
+        }
+        if !dry_run {
+            let moved = match storage.write_bytes(&to, &contents).await {
+                Ok(()) => storage.delete(&from).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = moved {
+                storage.discard().await;
+                return Err(e);
+            }
+        }
+        report.renamed.push(Rename { from, to });
+    }
+
+    if !dry_run && !report.renamed.is_empty() {
+        storage
+            .commit(&format!(
+                "repair layout: rename {} files",
+                report.renamed.len()
+            ))
+            .await?;
+    }
+    Ok(report)
+}
+
+async fn list_sorted<S: AsyncStorage>(
+    storage: &S,
+    dir: &Path,
+    pattern: &str,
+) -> Result<Vec<PathBuf>> {
+    if !storage.exists(dir).await {
+        return Ok(vec![]);
+    }
+    let mut files = storage
+        .list_files(dir, pattern)
+        .await
+        .with_context(|| format!("Failed to list files in {}", dir.display()))?;
+    files.sort();
+    Ok(files)
+}
+
+async fn read_misnamed_plan<S: AsyncStorage>(storage: &S, path: &Path) -> Result<Plan> {
+    let content = storage.read_string(path).await?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(toml::from_str(&content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::{plan_helpers, PlanManager, TimesheetManager};
+    use crate::memory_storage::MemoryStorage;
+    use std::sync::Arc;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        assert_eq!(parse_log_file_name(&log_file_name(date())), Some(date()));
+        assert_eq!(
+            parse_plan_file_name(&plan_file_name("element.io", date())),
+            Some(("element.io".to_string(), date()))
+        );
+        assert_eq!(
+            parse_timesheet_stem("acme.2025-03-01"),
+            Ok(("acme", date()))
+        );
+        assert_eq!(
+            timesheet_meta_file_name("acme", date()),
+            "acme.2025-03-01.json.meta"
+        );
+        assert_eq!(parse_log_file_name("notes.toml"), None);
+    }
+
+    #[test]
+    fn test_storage_helpers_use_layout() {
+        let storage = MemoryStorage::new();
+        assert_eq!(
+            storage.plan_file_path("local", date()),
+            PathBuf::from("/faff/plans/local.20250301.toml")
+        );
+        assert_eq!(
+            storage.timesheet_meta_file_path("acme", date()),
+            PathBuf::from("/faff/timesheets/acme.2025-03-01.json.meta")
+        );
+    }
+
+    #[test]
+    fn test_repair_renames_legacy_files() {
+        let storage = Arc::new(MemoryStorage::new());
+        let plans = PlanManager::new(storage.clone());
+        let plan = plan_helpers::empty_local_plan(date());
+        let plan_dir = storage.plan_dir();
+        let timesheet_dir = storage.timesheet_dir();
+
+        storage.add_file(
+            plan_dir.join("2025-03-01.json"),
+            serde_json::to_string(&plan).unwrap(),
+        );
+        storage.add_file(plan_dir.join("notes.txt"), "not a plan");
+        storage.add_file(timesheet_dir.join("acme.2025-03-01.meta.json"), "{}");
+
+        let report = repair(&storage, true).unwrap();
+        assert_eq!(report.renamed.len(), 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert!(plans.get_plan("local", date()).unwrap().is_none());
+
+        let report = repair(&storage, false).unwrap();
+        assert_eq!(
+            report.renamed,
+            vec![
+                Rename {
+                    from: plan_dir.join("2025-03-01.json"),
+                    to: plan_dir.join("local.20250301.toml"),
+                },
+                Rename {
+                    from: timesheet_dir.join("acme.2025-03-01.meta.json"),
+                    to: timesheet_dir.join("acme.2025-03-01.json.meta"),
+                },
+            ]
+        );
+        assert_eq!(plans.get_plan("local", date()).unwrap(), Some(plan));
+        let files = storage.files();
+        assert!(!files.contains_key(&plan_dir.join("2025-03-01.json")));
+        assert!(files.contains_key(&timesheet_dir.join("acme.2025-03-01.json.meta")));
+        assert!(TimesheetManager::new(storage.clone())
+            .list_timesheets(None)
+            .unwrap()
+            .is_empty());
+
+        // Nothing left to do except the file that isn't a plan
+        let report = repair(&storage, false).unwrap();
+        assert!(report.renamed.is_empty());
+        assert_eq!(report.skipped[0].0, plan_dir.join("notes.txt"));
+    }
+
+    #[test]
+    fn test_repair_keeps_existing_canonical_file() {
+        let storage = Arc::new(MemoryStorage::new());
+        let plans = PlanManager::new(storage.clone());
+        let plan = plan_helpers::empty_local_plan(date());
+        plans.write_plan(&plan).unwrap();
+        let legacy = storage.plan_dir().join("local.toml");
+        storage.add_file(legacy.clone(), toml::to_string(&plan).unwrap());
+
+        let report = repair(&storage, false).unwrap();
+        assert!(report.renamed.is_empty());
+        assert_eq!(report.skipped[0].0, legacy);
+        assert!(storage.files().contains_key(&legacy));
+    }
+}
@@ -1,8 +1,19 @@
+pub mod async_storage;
+pub mod async_workspace;
+pub mod encrypted_storage;
+pub mod export;
 pub mod file_system_storage;
+#[cfg(feature = "git")]
+pub mod git_storage;
+pub mod import;
+pub mod layout;
 pub mod managers;
+pub mod memory_storage;
 pub mod models;
 #[cfg(feature = "python")]
 pub mod py_models;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_index;
 pub mod storage;
 #[cfg(test)]
 pub mod test_utils;
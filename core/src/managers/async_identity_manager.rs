@@ -0,0 +1,224 @@
+use anyhow::{Context, Result};
+use ed25519_dalek::SigningKey;
+use rand::rngs::OsRng;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::async_storage::AsyncStorage;
+use crate::layout;
+
+/// Manages Ed25519 identity keypairs over `AsyncStorage`
+///
+/// This is the implementation behind `IdentityManager`, which drives it
+/// over synchronous storage.
+#[derive(Clone)]
+pub struct AsyncIdentityManager<S: AsyncStorage> {
+    storage: S,
+}
+
+impl<S: AsyncStorage> AsyncIdentityManager<S> {
+    pub fn new(storage: S) -> Self {
+        Self { storage }
+    }
+
+    /// Get the path for a private key file
+    fn get_key_path(&self, name: &str) -> PathBuf {
+        self.storage
+            .identity_dir()
+            .join(layout::key_file_name(name))
+    }
+
+    /// Get the path for a public key file
+    fn get_pub_path(&self, name: &str) -> PathBuf {
+        self.storage
+            .identity_dir()
+            .join(layout::pub_file_name(name))
+    }
+
+    /// Create a new Ed25519 identity keypair
+    ///
+    /// Keys are stored as base64-encoded strings:
+    /// - Private key: ~/.faff/identities/id_{name}
+    /// - Public key: ~/.faff/identities/id_{name}.pub
+    pub async fn create_identity(&self, name: &str, overwrite: bool) -> Result<SigningKey> {
+        let private_path = self.get_key_path(name);
+        let public_path = self.get_pub_path(name);
+
+        if !overwrite && self.storage.exists(&private_path).await {
+            anyhow::bail!("Identity '{}' already exists", name);
+        }
+
+        // Ensure identity directory exists
+        let identity_dir = self.storage.identity_dir();
+        self.storage
+            .create_dir_all(&identity_dir)
+            .await
+            .context("Failed to create identity directory")?;
+
+        let (signing_key, b64_private, b64_public) = generate_keypair();
+
+        // Write keys to files
+        self.storage
+            .write_string(&private_path, &b64_private)
+            .await
+            .with_context(|| format!("Failed to write private key for identity '{}'", name))?;
+        self.storage
+            .write_string(&public_path, &b64_public)
+            .await
+            .with_context(|| format!("Failed to write public key for identity '{}'", name))?;
+
+        // Note: File permissions (chmod 0o600) should be handled by the Storage implementation
+        // if it's a real filesystem. For testing with mock storage, this is skipped.
+
+        Ok(signing_key)
+    }
+
+    /// Check if an identity exists
+    pub async fn identity_exists(&self, name: &str) -> bool {
+        self.storage.exists(&self.get_key_path(name)).await
+    }
+
+    /// Delete an identity
+    ///
+    /// Removes both the private and public key files
+    pub async fn delete_identity(&self, name: &str) -> Result<()> {
+        let private_path = self.get_key_path(name);
+        let public_path = self.get_pub_path(name);
+
+        if !self.storage.exists(&private_path).await {
+            anyhow::bail!("Identity '{}' does not exist", name);
+        }
+
+        // Delete private key
+        self.storage
+            .delete(&private_path)
+            .await
+            .with_context(|| format!("Failed to delete private key for identity '{}'", name))?;
+
+        // Delete public key if it exists
+        if self.storage.exists(&public_path).await {
+            self.storage
+                .delete(&public_path)
+                .await
+                .with_context(|| format!("Failed to delete public key for identity '{}'", name))?;
+        }
+
+        Ok(())
+    }
+
+    /// Get a specific identity by name
+    pub async fn get_identity(&self, name: &str) -> Result<Option<SigningKey>> {
+        let identities = self.list_identities().await?;
+        Ok(identities.get(name).cloned())
+    }
+
+    /// List all identities
+    ///
+    /// Returns a HashMap where keys are identity names and values are SigningKeys
+    pub async fn list_identities(&self) -> Result<HashMap<String, SigningKey>> {
+        let identity_dir = self.storage.identity_dir();
+        let mut identities = HashMap::new();
+
+        // List all files matching layout::IDENTITY_FILES pattern
+        let files = self
+            .storage
+            .list_files(&identity_dir, layout::IDENTITY_FILES)
+            .await
+            .context("Failed to list identity files")?;
+
+        for file in files {
+            // Skip public key files
+            if file.extension().and_then(|s| s.to_str()) == Some("pub") {
+                continue;
+            }
+
+            // Extract identity name (remove "id_" prefix)
+            let filename = file
+                .file_name()
+                .and_then(|s| s.to_str())
+                .with_context(|| format!("Invalid filename in identity directory: {:?}", file))?;
+
+            let Some(name) = filename.strip_prefix("id_") else {
+                continue;
+            };
+
+            // Read and decode the private key
+            let b64_private = self
+                .storage
+                .read_string(&file)
+                .await
+                .with_context(|| format!("Failed to read identity file '{}'", name))?;
+            let signing_key = decode_signing_key(name, &b64_private)?;
+
+            identities.insert(name.to_string(), signing_key);
+        }
+
+        Ok(identities)
+    }
+}
+
+/// Generate a new keypair, returning it with its base64-encoded private and public keys
+fn generate_keypair() -> (SigningKey, String, String) {
+    let mut csprng = OsRng;
+    let mut secret_bytes = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut csprng, &mut secret_bytes);
+    let signing_key = SigningKey::from_bytes(&secret_bytes);
+    let verifying_key = signing_key.verifying_key();
+
+    // Encode keys as base64
+    let b64_private = base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        signing_key.to_bytes(),
+    );
+    let b64_public = base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        verifying_key.to_bytes(),
+    );
+
+    (signing_key, b64_private, b64_public)
+}
+
+/// Decode a base64-encoded private key file
+fn decode_signing_key(name: &str, b64_private: &str) -> Result<SigningKey> {
+    let key_bytes = base64::Engine::decode(
+        &base64::engine::general_purpose::STANDARD,
+        b64_private.trim(),
+    )
+    .with_context(|| format!("Failed to decode base64 key for identity '{}'", name))?;
+
+    if key_bytes.len() != 32 {
+        anyhow::bail!(
+            "Invalid key length for identity '{}': expected 32 bytes, got {}",
+            name,
+            key_bytes.len()
+        );
+    }
+
+    let mut key_array = [0u8; 32];
+    key_array.copy_from_slice(&key_bytes);
+    Ok(SigningKey::from_bytes(&key_array))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::IdentityManager;
+    use crate::memory_storage::MemoryStorage;
+    use crate::test_utils::block_on;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_created_identity_is_readable_by_sync_manager() {
+        let storage = Arc::new(MemoryStorage::new());
+        let sync = IdentityManager::new(storage.clone());
+        let manager = AsyncIdentityManager::new(storage);
+
+        let key = block_on(manager.create_identity("work", false)).unwrap();
+        assert!(block_on(manager.create_identity("work", false)).is_err());
+
+        assert_eq!(sync.get_identity("work").unwrap(), Some(key.clone()));
+        assert_eq!(block_on(manager.get_identity("work")).unwrap(), Some(key));
+        assert_eq!(block_on(manager.list_identities()).unwrap().len(), 1);
+        assert!(block_on(manager.get_identity("home")).unwrap().is_none());
+    }
+}
@@ -0,0 +1,724 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+#[cfg(feature = "sqlite")]
+use std::sync::Arc;
+
+use crate::async_storage::{operation, AsyncStorage};
+use crate::import::{self, MergeOutcome};
+use crate::layout;
+use crate::models::allocation::Allocation;
+use crate::models::budget::{Budget, BudgetStatus};
+use crate::models::intent_resolver::IntentResolver;
+use crate::models::intent_stats::{self, IntentStats, IntentSuggestions};
+use crate::models::report::{Dimension, Report};
+use crate::models::rounding::{RoundedTime, Rounding};
+use crate::models::working_hours::{Schedule, WorkingHours};
+use crate::models::{Intent, Log, Session};
+#[cfg(feature = "sqlite")]
+use crate::sqlite_index::SqliteIndex;
+use crate::storage::{VersionConflict, VersionToken};
+
+/// Manages log file operations over `AsyncStorage`.
+///
+/// This is the implementation behind `LogManager`, which drives it over
+/// synchronous storage.
+#[derive(Clone)]
+pub struct AsyncLogManager<S: AsyncStorage> {
+    storage: S,
+    timezone: Tz,
+    #[cfg(feature = "sqlite")]
+    index: Option<Arc<SqliteIndex>>,
+}
+
+impl<S: AsyncStorage> AsyncLogManager<S> {
+    /// How far back in the logs intent statistics look
+    const STATS_LOOKBACK_DAYS: i64 = 90;
+    /// How many times a read-modify-write is retried after a version conflict
+    const MAX_WRITE_ATTEMPTS: usize = 3;
+
+    pub fn new(storage: S, timezone: Tz) -> Self {
+        Self {
+            storage,
+            timezone,
+            #[cfg(feature = "sqlite")]
+            index: None,
+        }
+    }
+
+    /// Keep `index` up to date with every log written or deleted
+    #[cfg(feature = "sqlite")]
+    pub fn with_index(mut self, index: Arc<SqliteIndex>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Apply a change to the index, if there is one
+    #[cfg(feature = "sqlite")]
+    fn update_index(&self, update: impl FnOnce(&SqliteIndex) -> Result<()>) -> Result<()> {
+        match &self.index {
+            Some(index) => {
+                update(index).context("Written to storage, but failed to update the index")
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Index `log` at the version of its file, if there is an index
+    #[cfg(feature = "sqlite")]
+    async fn index_written_log(&self, log: &Log, version: Option<VersionToken>) -> Result<()> {
+        if self.index.is_none() {
+            return Ok(());
+        }
+        let version = match version {
+            Some(version) => Some(version),
+            None => self
+                .storage
+                .version(&self.storage.log_file_path(log.date))
+                .await
+                .context("Written to storage, but failed to update the index")?,
+        };
+        self.update_index(|index| index.index_log(log, version.as_ref()))
+    }
+
+    /// Re-index the logs dated `start..=end` whose files changed outside this manager
+    ///
+    /// Files are compared with the versions they were indexed at; logs that
+    /// are new, changed or unparseable are re-read, so a bad file fails the
+    /// read as it would without an index. Logs whose files are gone are
+    /// dropped from the index.
+    #[cfg(feature = "sqlite")]
+    async fn refresh_index(
+        &self,
+        index: &SqliteIndex,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<()> {
+        let mut indexed = index.log_versions(start, end)?;
+        for date in self.list_logs().await? {
+            if date < start || date > end {
+                continue;
+            }
+            let stored = indexed.remove(&date);
+            let current = self
+                .storage
+                .version(&self.storage.log_file_path(date))
+                .await?;
+            if current.is_some() && current == stored {
+                continue;
+            }
+            let (log, version) = self.get_log_versioned(date).await?;
+            index
+                .index_log(&log, version.as_ref())
+                .context("Failed to update the index")?;
+        }
+        for date in indexed.into_keys() {
+            index
+                .remove_log(date)
+                .context("Failed to update the index")?;
+        }
+        Ok(())
+    }
+
+    /// Get the path for a log file
+    pub fn log_file_path(&self, date: NaiveDate) -> PathBuf {
+        self.storage.log_file_path(date)
+    }
+
+    /// Check if a log file exists
+    pub async fn log_exists(&self, date: NaiveDate) -> bool {
+        self.storage.exists(&self.storage.log_file_path(date)).await
+    }
+
+    /// Read the raw log file contents
+    pub async fn read_log_raw(&self, date: NaiveDate) -> Result<String> {
+        self.storage
+            .read_string(&self.storage.log_file_path(date))
+            .await
+            .context(format!("Failed to read log file for {}", date))
+    }
+
+    /// Write raw log file contents
+    pub async fn write_log_raw(&self, date: NaiveDate, contents: &str) -> Result<()> {
+        operation(&self.storage, async {
+            self.create_log_dir().await?;
+            self.storage
+                .write_string(&self.storage.log_file_path(date), contents)
+                .await
+                .context(format!("Failed to write log for {}", date))?;
+
+            // Raw contents may not parse; such a log leaves the index, and
+            // reads covering it re-read the file and report why
+            #[cfg(feature = "sqlite")]
+            match Log::from_log_file(contents) {
+                Ok(log) => self.index_written_log(&log, None).await?,
+                Err(_) => self.update_index(|index| index.remove_log(date))?,
+            }
+            self.storage.commit(&format!("edit log {}", date)).await
+        })
+        .await
+    }
+
+    /// Get timezone for creating empty logs
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Get a log for a given date
+    ///
+    /// Returns None if the log file doesn't exist
+    pub async fn get_log(&self, date: NaiveDate) -> Result<Option<Log>> {
+        let log_path = self.storage.log_file_path(date);
+        if !self.storage.exists(&log_path).await {
+            return Ok(None);
+        }
+
+        let toml_str = self
+            .storage
+            .read_string(&log_path)
+            .await
+            .with_context(|| format!("Failed to read log file for {}", date))?;
+        let log = Log::from_log_file(&toml_str)
+            .with_context(|| format!("Failed to parse log file for {}", date))?;
+        Ok(Some(log))
+    }
+
+    /// Get a log for a given date, creating an empty one if it doesn't exist
+    pub async fn get_log_or_create(&self, date: NaiveDate) -> Result<Log> {
+        match self.get_log(date).await? {
+            Some(log) => Ok(log),
+            None => Ok(Log::new(date, self.timezone, vec![])),
+        }
+    }
+
+    /// Make sure the log directory exists, for storage that needs it
+    async fn create_log_dir(&self) -> Result<()> {
+        self.storage
+            .create_dir_all(&self.storage.log_dir())
+            .await
+            .context("Failed to create log directory")
+    }
+
+    /// Write a log to storage
+    ///
+    /// trackers: map of tracker IDs to human-readable names for comments
+    pub async fn write_log(&self, log: &Log, trackers: &HashMap<String, String>) -> Result<()> {
+        operation(&self.storage, async {
+            self.create_log_dir().await?;
+            self.storage
+                .write_string(
+                    &self.storage.log_file_path(log.date),
+                    &log.to_log_file(trackers),
+                )
+                .await
+                .context(format!("Failed to write log for {}", log.date))?;
+
+            #[cfg(feature = "sqlite")]
+            self.index_written_log(log, None).await?;
+            self.storage
+                .commit(&format!("update log {}", log.date))
+                .await
+        })
+        .await
+    }
+
+    /// Get a log for a given date along with its version token
+    ///
+    /// Returns an empty log and no version if the log file doesn't exist.
+    /// Pass the version to `write_log_if` to detect concurrent changes.
+    pub async fn get_log_versioned(&self, date: NaiveDate) -> Result<(Log, Option<VersionToken>)> {
+        let log_path = self.storage.log_file_path(date);
+        if !self.storage.exists(&log_path).await {
+            return Ok((Log::new(date, self.timezone, vec![]), None));
+        }
+
+        let (toml_str, version) = self
+            .storage
+            .read_string_versioned(&log_path)
+            .await
+            .with_context(|| format!("Failed to read log file for {}", date))?;
+        let log = Log::from_log_file(&toml_str)
+            .with_context(|| format!("Failed to parse log file for {}", date))?;
+        Ok((log, Some(version)))
+    }
+
+    /// Write a log only if it hasn't changed since it was read at `expected`
+    ///
+    /// Fails with a `VersionConflict` if it has. Returns the new version.
+    pub async fn write_log_if(
+        &self,
+        log: &Log,
+        trackers: &HashMap<String, String>,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        operation(&self.storage, async {
+            let version = self.store_log_if(log, trackers, expected).await?;
+            self.storage
+                .commit(&format!("update log {}", log.date))
+                .await?;
+            Ok(version)
+        })
+        .await
+    }
+
+    /// `write_log_if` without committing, for operations that describe themselves
+    async fn store_log_if(
+        &self,
+        log: &Log,
+        trackers: &HashMap<String, String>,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        self.create_log_dir().await?;
+        let version = self
+            .storage
+            .write_string_if(
+                &self.storage.log_file_path(log.date),
+                &log.to_log_file(trackers),
+                expected,
+            )
+            .await
+            .context(format!("Failed to write log for {}", log.date))?;
+
+        #[cfg(feature = "sqlite")]
+        self.index_written_log(log, Some(version.clone())).await?;
+        Ok(version)
+    }
+
+    /// Apply an edit to the log for `date`, retrying if it changes underneath us
+    ///
+    /// `edit` returns the updated log and a description of the change for
+    /// storage history. Holds the workspace lock where storage supports one;
+    /// version checks cover storage that doesn't (or writers that ignore it).
+    async fn update_log(
+        &self,
+        date: NaiveDate,
+        trackers: &HashMap<String, String>,
+        edit: impl Fn(Log) -> Result<(Log, String)>,
+    ) -> Result<()> {
+        operation(&self.storage, async {
+            let _lock = self.storage.lock().await?;
+
+            let mut attempt = 1;
+            loop {
+                let (log, version) = self.get_log_versioned(date).await?;
+                let (updated, message) = edit(log)?;
+                match self
+                    .store_log_if(&updated, trackers, version.as_ref())
+                    .await
+                {
+                    Err(e)
+                        if attempt < Self::MAX_WRITE_ATTEMPTS
+                            && e.downcast_ref::<VersionConflict>().is_some() =>
+                    {
+                        attempt += 1;
+                    }
+                    Err(e) => return Err(e),
+                    Ok(_) => return self.storage.commit(&message).await,
+                }
+            }
+        })
+        .await
+    }
+
+    /// List all log dates in storage
+    pub async fn list_logs(&self) -> Result<Vec<NaiveDate>> {
+        let files = self
+            .storage
+            .list_files(&self.storage.log_dir(), layout::LOG_FILES)
+            .await
+            .context("Failed to list log files")?;
+        let mut dates: Vec<NaiveDate> = files
+            .iter()
+            .filter_map(|file| layout::parse_log_file_name(file.file_name()?.to_str()?))
+            .collect();
+        dates.sort();
+        Ok(dates)
+    }
+
+    /// Get all logs with dates in the inclusive range `start..=end`
+    ///
+    /// Returns logs sorted by date; days without a log file are skipped.
+    /// With an index, only logs whose files changed since they were indexed
+    /// are read and parsed.
+    pub async fn get_logs_between(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Log>> {
+        #[cfg(feature = "sqlite")]
+        if let Some(index) = &self.index {
+            self.refresh_index(index, start, end).await?;
+            return index.logs_between(start, end);
+        }
+
+        let mut logs = Vec::new();
+        for date in self.list_logs().await? {
+            if date < start || date > end {
+                continue;
+            }
+            if let Some(log) = self.get_log(date).await? {
+                logs.push(log);
+            }
+        }
+        Ok(logs)
+    }
+
+    /// Compute usage statistics for the given intents relative to `now`
+    ///
+    /// Scans logs from the last `STATS_LOOKBACK_DAYS` days. Callers typically
+    /// pass today's plan intents so that only currently valid intents are reported.
+    pub async fn intent_stats(
+        &self,
+        intents: &[Intent],
+        now: DateTime<Tz>,
+    ) -> Result<Vec<IntentStats>> {
+        let today = now.date_naive();
+        let since = today - chrono::Duration::days(Self::STATS_LOOKBACK_DAYS);
+        let logs = self
+            .get_logs_between(since, today)
+            .await
+            .context("Failed to load logs for intent statistics")?;
+
+        Ok(intent_stats::compute_intent_stats(intents, &logs, now))
+    }
+
+    /// Suggest intents for quick-pick lists, at most `limit` per list
+    pub async fn suggest_intents(
+        &self,
+        intents: &[Intent],
+        now: DateTime<Tz>,
+        limit: usize,
+    ) -> Result<IntentSuggestions> {
+        let stats = self.intent_stats(intents, now).await?;
+        Ok(IntentSuggestions::from_stats(&stats, limit))
+    }
+
+    /// Merge imported sessions into the log for `date`
+    ///
+    /// Sessions duplicating or overlapping ones already logged are left out;
+    /// see `import::merge`. With `dry_run` nothing is written. Like
+    /// `update_log`, holds the workspace lock and retries if the log changes
+    /// underneath us.
+    pub async fn merge_sessions(
+        &self,
+        date: NaiveDate,
+        sessions: Vec<Session>,
+        trackers: &HashMap<String, String>,
+        dry_run: bool,
+    ) -> Result<MergeOutcome> {
+        if dry_run {
+            let (log, _) = self.get_log_versioned(date).await?;
+            return Ok(import::merge(&log, sessions).1);
+        }
+
+        operation(&self.storage, async {
+            let _lock = self.storage.lock().await?;
+
+            let mut attempt = 1;
+            loop {
+                let (log, version) = self.get_log_versioned(date).await?;
+                let (merged, outcome) = import::merge(&log, sessions.clone());
+                if outcome.added.is_empty() {
+                    return Ok(outcome);
+                }
+                match self.store_log_if(&merged, trackers, version.as_ref()).await {
+                    Err(e)
+                        if attempt < Self::MAX_WRITE_ATTEMPTS
+                            && e.downcast_ref::<VersionConflict>().is_some() =>
+                    {
+                        attempt += 1;
+                    }
+                    Err(e) => {
+                        return Err(e.context(format!(
+                            "Failed to merge imported sessions into log for {date}"
+                        )))
+                    }
+                    Ok(_) => {
+                        let message =
+                            format!("import {} sessions into log {}", outcome.added.len(), date);
+                        self.storage.commit(&message).await?;
+                        return Ok(outcome);
+                    }
+                }
+            }
+        })
+        .await
+    }
+
+    /// Recorded time in logs dated `start..=end`, grouped by `group_by`
+    ///
+    /// Time is credited to trackers per `allocation`, normally the config's.
+    pub async fn report(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &[Dimension],
+        allocation: &Allocation,
+    ) -> Result<Report> {
+        let logs = self
+            .get_logs_between(start, end)
+            .await
+            .context("Failed to load logs for report")?;
+        Ok(Report::build(&logs, start, end, group_by, allocation))
+    }
+
+    /// Time recorded in logs dated `start..=end`, raw and rounded per `rounding`
+    pub async fn rounded_time(
+        &self,
+        rounding: &Rounding,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<RoundedTime> {
+        let logs = self
+            .get_logs_between(start, end)
+            .await
+            .context("Failed to load logs for rounding")?;
+        Ok(rounding.apply(&logs))
+    }
+
+    /// Expected versus recorded hours in `start..=end` under `schedule`
+    pub async fn working_hours(
+        &self,
+        schedule: &Schedule,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<WorkingHours> {
+        let logs = self
+            .get_logs_between(start, end)
+            .await
+            .context("Failed to load logs for working hours")?;
+        WorkingHours::calculate(schedule, &logs, start, end)
+    }
+
+    /// Progress of each budget in its period containing `today`
+    ///
+    /// Time is credited to trackers per `allocation`, normally the config's.
+    pub async fn budget_status(
+        &self,
+        budgets: &[Budget],
+        today: NaiveDate,
+        allocation: &Allocation,
+    ) -> Result<Vec<BudgetStatus>> {
+        let periods: Vec<(NaiveDate, NaiveDate)> =
+            budgets.iter().map(|b| b.period.bounds(today)).collect();
+        let (Some(start), Some(end)) = (
+            periods.iter().map(|(start, _)| *start).min(),
+            periods.iter().map(|(_, end)| *end).max(),
+        ) else {
+            return Ok(Vec::new());
+        };
+        let logs = self
+            .get_logs_between(start, end)
+            .await
+            .context("Failed to load logs for budgets")?;
+        budgets
+            .iter()
+            .map(|budget| {
+                budget
+                    .status(&logs, today, allocation)
+                    .with_context(|| format!("Invalid budget '{}'", budget.label()))
+            })
+            .collect()
+    }
+
+    /// Warnings for budgets that time on `intent` would count towards
+    ///
+    /// Front ends show these before starting a session, e.g. when the
+    /// budget is already exceeded or on course to be.
+    pub async fn budget_warnings(
+        &self,
+        budgets: &[Budget],
+        intent: &Intent,
+        today: NaiveDate,
+        allocation: &Allocation,
+    ) -> Result<Vec<String>> {
+        let relevant: Vec<Budget> = budgets
+            .iter()
+            .filter(|b| b.applies_to(intent))
+            .cloned()
+            .collect();
+        Ok(self
+            .budget_status(&relevant, today, allocation)
+            .await?
+            .iter()
+            .filter_map(BudgetStatus::warning)
+            .collect())
+    }
+
+    /// Rewrite historical logs to use current intent definitions
+    ///
+    /// Only logs with at least one changed session are written back.
+    /// Returns the dates of the rewritten logs.
+    pub async fn rewrite_intents(
+        &self,
+        resolver: &IntentResolver,
+        trackers: &HashMap<String, String>,
+    ) -> Result<Vec<NaiveDate>> {
+        operation(&self.storage, async {
+            let _lock = self.storage.lock().await?;
+            let mut rewritten = Vec::new();
+            for date in self.list_logs().await? {
+                let (log, version) = self.get_log_versioned(date).await?;
+                let (resolved, changed) = resolver.resolve_log(&log);
+                if changed > 0 {
+                    self.store_log_if(&resolved, trackers, version.as_ref())
+                        .await?;
+                    rewritten.push(date);
+                }
+            }
+            if !rewritten.is_empty() {
+                self.storage
+                    .commit(&format!("rewrite intents in {} logs", rewritten.len()))
+                    .await?;
+            }
+            Ok(rewritten)
+        })
+        .await
+    }
+
+    /// Delete a log for a given date
+    pub async fn delete_log(&self, date: NaiveDate) -> Result<()> {
+        operation(&self.storage, async {
+            let log_path = self.storage.log_file_path(date);
+
+            if !self.storage.exists(&log_path).await {
+                anyhow::bail!("Log for {} does not exist", date);
+            }
+
+            self.storage
+                .delete(&log_path)
+                .await
+                .with_context(|| format!("Failed to delete log for {}", date))?;
+
+            #[cfg(feature = "sqlite")]
+            self.update_index(|index| index.remove_log(date))?;
+            self.storage.commit(&format!("delete log {}", date)).await
+        })
+        .await
+    }
+
+    /// Fail if the intent references trackers that aren't in today's plan
+    fn check_trackers(intent: &Intent, trackers: &HashMap<String, String>) -> Result<()> {
+        if intent.trackers.is_empty() {
+            return Ok(());
+        }
+
+        let tracker_ids: HashSet<_> = trackers.keys().collect();
+        let intent_tracker_set: HashSet<_> = intent.trackers.iter().collect();
+
+        if !intent_tracker_set.is_subset(&tracker_ids) {
+            let missing: Vec<_> = intent_tracker_set
+                .difference(&tracker_ids)
+                .map(|s| s.as_str())
+                .collect();
+            anyhow::bail!("Tracker {} not found in today's plan", missing.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Start a new session with the given intent at the current time
+    pub async fn start_intent_now(
+        &self,
+        intent: Intent,
+        note: Option<String>,
+        current_date: NaiveDate,
+        current_time: DateTime<Tz>,
+        trackers: &HashMap<String, String>,
+    ) -> Result<()> {
+        Self::check_trackers(&intent, trackers)?;
+
+        let message = format!("start session: {}", intent.label());
+
+        // Create new session
+        let session = Session::new(intent, current_time, None, note);
+
+        // Append to today's log (or a new one), without losing concurrent changes
+        self.update_log(current_date, trackers, |log| {
+            Ok((log.append_session(session.clone())?, message.clone()))
+        })
+        .await
+    }
+
+    /// Stop the currently active session
+    ///
+    /// Returns Ok(()) if a session was stopped, or an error if no active session exists
+    pub async fn stop_current_session(
+        &self,
+        current_date: NaiveDate,
+        current_time: DateTime<Tz>,
+        trackers: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.update_log(current_date, trackers, |log| {
+            let Some(active) = log.active_session() else {
+                anyhow::bail!("No active session to stop");
+            };
+            let message = format!("stop session: {}", active.intent.label());
+            Ok((log.stop_active_session(current_time)?, message))
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::LogManager;
+    use crate::memory_storage::MemoryStorage;
+    use crate::test_utils::block_on;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn intent() -> Intent {
+        Intent::new(Some("Work".to_string()), None, None, None, None, vec![])
+    }
+
+    #[test]
+    fn test_start_and_stop_session() {
+        let manager = AsyncLogManager::new(Arc::new(MemoryStorage::new()), chrono_tz::UTC);
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+        let stop = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 10, 0, 0)
+            .unwrap();
+        let trackers = HashMap::new();
+
+        block_on(manager.start_intent_now(intent(), None, date, start, &trackers)).unwrap();
+        block_on(manager.stop_current_session(date, stop, &trackers)).unwrap();
+
+        let log = block_on(manager.get_log(date)).unwrap().unwrap();
+        assert_eq!(log.timeline.len(), 1);
+        assert_eq!(log.timeline[0].end, Some(stop));
+        assert_eq!(block_on(manager.list_logs()).unwrap(), vec![date]);
+    }
+
+    #[test]
+    fn test_matches_sync_manager() {
+        let storage = Arc::new(MemoryStorage::new());
+        let sync = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let manager = AsyncLogManager::new(storage, chrono_tz::UTC);
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+
+        sync.start_intent_now(intent(), None, date, start, &HashMap::new())
+            .unwrap();
+
+        let (log, version) = block_on(manager.get_log_versioned(date)).unwrap();
+        assert_eq!(Some(log), sync.get_log(date).unwrap());
+        assert_eq!(version, sync.get_log_versioned(date).unwrap().1);
+    }
+
+    #[test]
+    fn test_stop_without_active_session_fails() {
+        let manager = AsyncLogManager::new(Arc::new(MemoryStorage::new()), chrono_tz::UTC);
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let now = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+
+        let result = block_on(manager.stop_current_session(date, now, &HashMap::new()));
+        assert!(result.is_err());
+        assert!(!block_on(manager.log_exists(date)));
+    }
+}
@@ -0,0 +1,764 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "sqlite")]
+use std::sync::Arc;
+
+use crate::async_storage::{operation, AsyncStorage};
+use crate::layout;
+use crate::managers::plan_helpers::{self, LOCAL_PLAN_SOURCE};
+use crate::managers::AsyncLogManager;
+use crate::models::intent::Intent;
+use crate::models::intent_resolver::IntentResolver;
+use crate::models::intent_search::{self, IntentMatch};
+use crate::models::plan::{Plan, Vocabulary};
+use crate::models::plan_lint::{self, PlanFileReport};
+#[cfg(feature = "sqlite")]
+use crate::sqlite_index::SqliteIndex;
+use crate::storage::VersionToken;
+
+/// Manages plan loading and querying over `AsyncStorage`
+///
+/// This is the implementation behind `PlanManager`, which drives it over
+/// synchronous storage. Plan files follow the `<source>.<YYYYMMDD>.toml`
+/// layout.
+#[derive(Clone)]
+pub struct AsyncPlanManager<S: AsyncStorage> {
+    storage: S,
+    #[cfg(feature = "sqlite")]
+    index: Option<Arc<SqliteIndex>>,
+}
+
+impl<S: AsyncStorage> AsyncPlanManager<S> {
+    /// How far back to look in the logs when boosting recently used intents
+    const RECENT_USAGE_DAYS: i64 = 28;
+
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            #[cfg(feature = "sqlite")]
+            index: None,
+        }
+    }
+
+    /// Keep `index` up to date with every plan written or deleted
+    #[cfg(feature = "sqlite")]
+    pub fn with_index(mut self, index: Arc<SqliteIndex>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Apply a change to the index, if there is one
+    #[cfg(feature = "sqlite")]
+    fn update_index(&self, update: impl FnOnce(&SqliteIndex) -> Result<()>) -> Result<()> {
+        match &self.index {
+            Some(index) => {
+                update(index).context("Written to storage, but failed to update the index")
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Get all plans valid for a given date
+    ///
+    /// A plan is valid if:
+    /// - valid_from <= target_date
+    /// - and (valid_until >= target_date or valid_until is None)
+    ///
+    /// Plan files that can't be read or parsed are skipped, so one bad file
+    /// doesn't hide every other plan; `get_plans_with_diagnostics` reports
+    /// them.
+    pub async fn get_plans(&self, date: NaiveDate) -> Result<HashMap<String, Plan>> {
+        let (plans, _) = self.get_plans_with_diagnostics(date).await?;
+        Ok(plans)
+    }
+
+    /// Get all plans valid for a given date, as `get_plans` does
+    ///
+    /// Returns the plans that loaded, plus a report for each file skipped
+    /// because it failed to load. With an index, only plan files that
+    /// changed since they were indexed are read and parsed.
+    pub async fn get_plans_with_diagnostics(
+        &self,
+        date: NaiveDate,
+    ) -> Result<(HashMap<String, Plan>, Vec<PlanFileReport>)> {
+        #[cfg(feature = "sqlite")]
+        if let Some(index) = &self.index {
+            let files = self.list_plan_files().await?;
+            let failed = self.refresh_index(index, &files).await?;
+            let latest = plan_helpers::latest_plan_files(files, date)?;
+
+            let mut plans = index.plans_on(date)?;
+            let mut skipped = Vec::new();
+            for (file_path, source, error) in failed {
+                if latest.contains(&file_path) {
+                    // Left out of the index, which would fall back to an
+                    // older file; without an index the source is skipped
+                    plans.remove(&source);
+                    skipped.push(plan_helpers::load_failure(file_path, &error));
+                }
+            }
+            return Ok((plans, skipped));
+        }
+
+        self.load_plans_for_date(date).await
+    }
+
+    /// Index `plan` at the version of its file, if there is an index
+    #[cfg(feature = "sqlite")]
+    async fn index_written_plan(&self, plan: &Plan, version: Option<VersionToken>) -> Result<()> {
+        if self.index.is_none() {
+            return Ok(());
+        }
+        let version = match version {
+            Some(version) => Some(version),
+            None => self
+                .plan_version(&plan.source, plan.valid_from)
+                .await
+                .context("Written to storage, but failed to update the index")?,
+        };
+        self.update_index(|index| {
+            index.index_plan(&plan.source, plan.valid_from, plan, version.as_ref())
+        })
+    }
+
+    /// Re-index the plan files among `files` that changed outside this manager
+    ///
+    /// Files are compared with the versions they were indexed at, as
+    /// `AsyncLogManager` does for logs: new and changed files are re-read and
+    /// files that are gone are dropped. Files that fail to load are dropped
+    /// too, and returned with their source and error, so they're re-read
+    /// next time.
+    #[cfg(feature = "sqlite")]
+    async fn refresh_index(
+        &self,
+        index: &SqliteIndex,
+        files: &[PathBuf],
+    ) -> Result<Vec<(PathBuf, String, anyhow::Error)>> {
+        let mut indexed = index.plan_versions()?;
+        let mut failed = Vec::new();
+        for file_path in files {
+            let Some((source, file_date)) = file_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(layout::parse_plan_file_name)
+            else {
+                continue;
+            };
+            let stored = indexed.remove(&(source.clone(), file_date));
+            let current = self.storage.version(file_path).await?;
+            if current.is_some() && current == stored {
+                continue;
+            }
+            let updated = match self.read_plan_file_versioned(file_path).await {
+                Ok((plan, version)) => index.index_plan(&source, file_date, &plan, Some(&version)),
+                Err(error) => {
+                    failed.push((file_path.clone(), source.clone(), error));
+                    index.remove_plan(&source, file_date)
+                }
+            };
+            updated.context("Failed to update the index")?;
+        }
+        for (source, file_date) in indexed.into_keys() {
+            index
+                .remove_plan(&source, file_date)
+                .context("Failed to update the index")?;
+        }
+        Ok(failed)
+    }
+
+    /// Lint every plan file in storage
+    ///
+    /// Returns one report per file that has at least one diagnostic.
+    pub async fn lint_plans(&self) -> Result<Vec<PlanFileReport>> {
+        let mut files = self.list_plan_files().await?;
+        files.sort();
+
+        let mut reports = Vec::new();
+        for file_path in files {
+            let plan = match self.read_plan_file(&file_path).await {
+                Ok(plan) => plan,
+                Err(e) => {
+                    reports.push(plan_helpers::load_failure(file_path, &e));
+                    continue;
+                }
+            };
+
+            let mut diagnostics = plan_lint::lint_plan(&plan);
+            diagnostics.extend(plan_helpers::lint_filename(&file_path, &plan));
+            if !diagnostics.is_empty() {
+                reports.push(PlanFileReport::new(file_path, diagnostics));
+            }
+        }
+
+        Ok(reports)
+    }
+
+    /// Read and parse a single plan file
+    async fn read_plan_file(&self, file_path: &Path) -> Result<Plan> {
+        let content = self
+            .storage
+            .read_string(file_path)
+            .await
+            .with_context(|| format!("Failed to read plan file: {}", file_path.display()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse plan file: {}", file_path.display()))
+    }
+
+    /// Read and parse a single plan file, with the version it was read at
+    #[cfg(feature = "sqlite")]
+    async fn read_plan_file_versioned(&self, file_path: &Path) -> Result<(Plan, VersionToken)> {
+        let (content, version) = self
+            .storage
+            .read_string_versioned(file_path)
+            .await
+            .with_context(|| format!("Failed to read plan file: {}", file_path.display()))?;
+
+        let plan = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse plan file: {}", file_path.display()))?;
+        Ok((plan, version))
+    }
+
+    /// Every plan file in the plan directory
+    async fn list_plan_files(&self) -> Result<Vec<PathBuf>> {
+        self.storage
+            .list_files(&self.storage.plan_dir(), layout::PLAN_FILES)
+            .await
+            .context("Failed to list plan files")
+    }
+
+    /// Load plans from storage for a given date
+    ///
+    /// Reads the most recent file dated on or before `date` for each source.
+    /// Files that fail to load are skipped and reported instead of failing
+    /// the whole call.
+    async fn load_plans_for_date(
+        &self,
+        date: NaiveDate,
+    ) -> Result<(HashMap<String, Plan>, Vec<PlanFileReport>)> {
+        let plan_files = plan_helpers::latest_plan_files(self.list_plan_files().await?, date)?;
+
+        let mut plans: HashMap<String, Plan> = HashMap::new();
+        let mut skipped = Vec::new();
+
+        for file_path in plan_files {
+            let plan = match self.read_plan_file(&file_path).await {
+                Ok(plan) => plan,
+                Err(e) => {
+                    skipped.push(plan_helpers::load_failure(file_path, &e));
+                    continue;
+                }
+            };
+
+            plan_helpers::insert_if_valid(&mut plans, plan, date);
+        }
+
+        Ok((plans, skipped))
+    }
+
+    /// Get all intents from plans valid for a given date
+    pub async fn get_intents(&self, date: NaiveDate) -> Result<Vec<Intent>> {
+        Ok(plan_helpers::intents_of(&self.get_plans(date).await?))
+    }
+
+    /// Search intents from plans valid for a given date
+    ///
+    /// Ranks intents against the query across alias, role, objective, action,
+    /// subject and tracker names. Intents used in logs within the last
+    /// `RECENT_USAGE_DAYS` days are boosted.
+    pub async fn search_intents<L: AsyncStorage>(
+        &self,
+        query: &str,
+        date: NaiveDate,
+        log_manager: &AsyncLogManager<L>,
+    ) -> Result<Vec<IntentMatch>> {
+        let plans = self.get_plans(date).await?;
+        let intents = plan_helpers::intents_of(&plans);
+        let trackers = plan_helpers::trackers_of(&plans);
+
+        let since = date - chrono::Duration::days(Self::RECENT_USAGE_DAYS);
+        let logs = log_manager
+            .get_logs_between(since, date)
+            .await
+            .context("Failed to load recent logs for intent search")?;
+        let last_used = intent_search::last_used_from_logs(&logs);
+
+        Ok(intent_search::rank_intents(
+            query, &intents, &trackers, &last_used, date,
+        ))
+    }
+
+    /// Build a resolver mapping logged intents to the intents valid on a given date
+    pub async fn intent_resolver(&self, date: NaiveDate) -> Result<IntentResolver> {
+        Ok(IntentResolver::new(&self.get_intents(date).await?))
+    }
+
+    /// Get all roles from plans valid for a given date
+    ///
+    /// Returns roles prefixed with their source (e.g., "element:engineer")
+    /// plus any roles from intents
+    pub async fn get_roles(&self, date: NaiveDate) -> Result<Vec<String>> {
+        Ok(plan_helpers::roles_of(&self.get_plans(date).await?))
+    }
+
+    /// Get all objectives from plans valid for a given date
+    pub async fn get_objectives(&self, date: NaiveDate) -> Result<Vec<String>> {
+        Ok(plan_helpers::objectives_of(&self.get_plans(date).await?))
+    }
+
+    /// Get all actions from plans valid for a given date
+    pub async fn get_actions(&self, date: NaiveDate) -> Result<Vec<String>> {
+        Ok(plan_helpers::actions_of(&self.get_plans(date).await?))
+    }
+
+    /// Get all subjects from plans valid for a given date
+    pub async fn get_subjects(&self, date: NaiveDate) -> Result<Vec<String>> {
+        Ok(plan_helpers::subjects_of(&self.get_plans(date).await?))
+    }
+
+    /// Get all trackers from plans valid for a given date
+    ///
+    /// Returns a map of tracker IDs (prefixed with source) to human-readable names
+    /// Example: "element:12345" -> "Fix critical bug"
+    pub async fn get_trackers(&self, date: NaiveDate) -> Result<HashMap<String, String>> {
+        Ok(plan_helpers::trackers_of(&self.get_plans(date).await?))
+    }
+
+    /// Whether each tracker is billable, per the plans valid on a date
+    pub async fn get_billable(&self, date: NaiveDate) -> Result<HashMap<String, bool>> {
+        Ok(plan_helpers::billable_of(&self.get_plans(date).await?))
+    }
+
+    /// Get the plan containing a specific tracker ID
+    ///
+    /// Returns None if the tracker is not found in any plan for the given date
+    pub async fn get_plan_by_tracker_id(
+        &self,
+        tracker_id: &str,
+        date: NaiveDate,
+    ) -> Result<Option<Plan>> {
+        let plans = self.get_plans(date).await?;
+        Ok(plans
+            .into_values()
+            .find(|plan| plan.trackers.contains_key(tracker_id)))
+    }
+
+    /// Get the local plan for a given date
+    ///
+    /// Returns None if the local plan doesn't exist
+    pub async fn get_local_plan(&self, date: NaiveDate) -> Result<Option<Plan>> {
+        let mut plans = self.get_plans(date).await?;
+        Ok(plans.remove(LOCAL_PLAN_SOURCE))
+    }
+
+    /// Get the local plan for a given date, creating an empty one if it doesn't exist
+    pub async fn get_local_plan_or_create(&self, date: NaiveDate) -> Result<Plan> {
+        Ok(self
+            .get_local_plan(date)
+            .await?
+            .unwrap_or_else(|| plan_helpers::empty_local_plan(date)))
+    }
+
+    /// Get the plan stored for a specific source and date
+    ///
+    /// Returns None if there's no such plan file, regardless of validity dates.
+    pub async fn get_plan(&self, source: &str, date: NaiveDate) -> Result<Option<Plan>> {
+        let file_path = self.storage.plan_file_path(source, date);
+        if !self.storage.exists(&file_path).await {
+            return Ok(None);
+        }
+        self.read_plan_file(&file_path).await.map(Some)
+    }
+
+    /// Ensure the plan directory exists; return the plan's file path and contents
+    async fn prepare_plan_write(&self, plan: &Plan) -> Result<(PathBuf, String)> {
+        self.storage
+            .create_dir_all(&self.storage.plan_dir())
+            .await
+            .context("Failed to create plan directory")?;
+
+        let file_path = self.storage.plan_file_path(&plan.source, plan.valid_from);
+        let toml_content =
+            toml::to_string_pretty(plan).context("Failed to serialize plan to TOML")?;
+        Ok((file_path, toml_content))
+    }
+
+    /// Write a plan to storage as `<source>.<YYYYMMDD>.toml`
+    pub async fn write_plan(&self, plan: &Plan) -> Result<()> {
+        operation(&self.storage, async {
+            let (file_path, toml_content) = self.prepare_plan_write(plan).await?;
+            self.storage
+                .write_string(&file_path, &toml_content)
+                .await
+                .context("Failed to write plan file")?;
+
+            #[cfg(feature = "sqlite")]
+            self.index_written_plan(plan, None).await?;
+            self.storage
+                .commit(&plan_helpers::update_message(plan))
+                .await
+        })
+        .await
+    }
+
+    /// Version token of the plan file for a source and date
+    ///
+    /// Returns None if the file doesn't exist.
+    pub async fn plan_version(
+        &self,
+        source: &str,
+        date: NaiveDate,
+    ) -> Result<Option<VersionToken>> {
+        self.storage
+            .version(&self.storage.plan_file_path(source, date))
+            .await
+    }
+
+    /// Write a plan only if its file is still at `expected` (None: must not exist)
+    ///
+    /// Fails with a `VersionConflict` otherwise. Returns the new version.
+    pub async fn write_plan_if(
+        &self,
+        plan: &Plan,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        operation(&self.storage, async {
+            let version = self.store_plan_if(plan, expected).await?;
+            self.storage
+                .commit(&plan_helpers::update_message(plan))
+                .await?;
+            Ok(version)
+        })
+        .await
+    }
+
+    /// `write_plan_if` without committing, for operations made of several writes
+    async fn store_plan_if(
+        &self,
+        plan: &Plan,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        let (file_path, toml_content) = self.prepare_plan_write(plan).await?;
+        let version = self
+            .storage
+            .write_string_if(&file_path, &toml_content, expected)
+            .await
+            .context("Failed to write plan file")?;
+
+        #[cfg(feature = "sqlite")]
+        self.index_written_plan(plan, Some(version.clone())).await?;
+        Ok(version)
+    }
+
+    /// Apply an edit to the local plan in effect on `date`
+    ///
+    /// The edited plan is written as a new version valid from `date`, so other
+    /// versions are left untouched: earlier ones, and later ones, which keep
+    /// taking effect from their own dates without the edit. An existing
+    /// version dated `date` is replaced.
+    ///
+    /// Holds the workspace lock, so concurrent edits are applied one after another.
+    ///
+    /// `message` describes the edit in storage history.
+    async fn edit_local_plan(
+        &self,
+        date: NaiveDate,
+        message: String,
+        edit: impl FnOnce(&Plan) -> Result<Plan>,
+    ) -> Result<Plan> {
+        operation(&self.storage, async {
+            let _lock = self.storage.lock().await?;
+            let edited = self.edit_local_plan_locked(date, edit).await?;
+            self.storage.commit(&message).await?;
+            Ok(edited)
+        })
+        .await
+    }
+
+    /// `edit_local_plan` for callers already holding the workspace lock
+    ///
+    /// Doesn't commit; the caller does once its operation is complete.
+    async fn edit_local_plan_locked(
+        &self,
+        date: NaiveDate,
+        edit: impl FnOnce(&Plan) -> Result<Plan>,
+    ) -> Result<Plan> {
+        // Taken before reading, so a concurrent write in between is reported
+        let expected = self.plan_version(LOCAL_PLAN_SOURCE, date).await?;
+        let current = self.local_plan_to_edit(date).await?;
+        let edited = edit(&current)?.with_valid_from(date);
+        self.store_plan_if(&edited, expected.as_ref()).await?;
+        Ok(edited)
+    }
+
+    /// The local plan in effect on `date`, or an empty one, for an edit to build on
+    ///
+    /// Unlike `get_local_plan`, a local plan file that fails to load is an
+    /// error rather than skipped: the edit would replace it, losing whatever
+    /// the file held.
+    async fn local_plan_to_edit(&self, date: NaiveDate) -> Result<Plan> {
+        let files = plan_helpers::latest_plan_files(self.list_plan_files().await?, date)?;
+        let local = files.into_iter().find(|file_path| {
+            file_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(layout::parse_plan_file_name)
+                .is_some_and(|(source, _)| source == LOCAL_PLAN_SOURCE)
+        });
+
+        let mut plans = HashMap::new();
+        if let Some(file_path) = local {
+            let plan = self.read_plan_file(&file_path).await?;
+            plan_helpers::insert_if_valid(&mut plans, plan, date);
+        }
+        Ok(plans
+            .remove(LOCAL_PLAN_SOURCE)
+            .unwrap_or_else(|| plan_helpers::empty_local_plan(date)))
+    }
+
+    /// Add an intent to the local plan version dated `date`
+    pub async fn add_intent(&self, date: NaiveDate, intent: Intent) -> Result<Plan> {
+        let message = format!("add intent: {}", intent.label());
+        self.edit_local_plan(date, message, |plan| Ok(plan.add_intent(intent)))
+            .await
+    }
+
+    /// Remove an intent from the local plan version dated `date`
+    pub async fn remove_intent(&self, date: NaiveDate, intent: &Intent) -> Result<Plan> {
+        let message = format!("remove intent: {}", intent.label());
+        self.edit_local_plan(date, message, |plan| {
+            plan_helpers::without_intent(plan, intent)
+        })
+        .await
+    }
+
+    /// Give an intent a new alias in the local plan version dated `date`
+    pub async fn rename_intent(
+        &self,
+        date: NaiveDate,
+        intent: &Intent,
+        alias: &str,
+    ) -> Result<Plan> {
+        let message = format!("rename intent: {} -> {}", intent.label(), alias);
+        self.edit_local_plan(date, message, |plan| {
+            if !plan.has_intent(intent) {
+                anyhow::bail!("Intent {:?} is not in the local plan", intent.alias);
+            }
+            Ok(plan.rename_intent(intent, alias))
+        })
+        .await
+    }
+
+    /// Retire an intent from the local plan from `date` onward
+    ///
+    /// Unlike `remove_intent`, this also removes the intent from any local
+    /// plan versions dated after `date`. Versions before `date` keep it, so
+    /// existing logs still resolve.
+    pub async fn retire_intent(&self, date: NaiveDate, intent: &Intent) -> Result<()> {
+        operation(&self.storage, async {
+            let _lock = self.storage.lock().await?;
+            self.edit_local_plan_locked(date, |plan| plan_helpers::without_intent(plan, intent))
+                .await?;
+
+            for (source, version_date) in self.list_plans().await? {
+                if source != LOCAL_PLAN_SOURCE || version_date <= date {
+                    continue;
+                }
+                let expected = self.plan_version(&source, version_date).await?;
+                let plan = self
+                    .read_plan_file(&self.storage.plan_file_path(&source, version_date))
+                    .await?;
+                if plan.has_intent(intent) {
+                    self.store_plan_if(&plan.remove_intent(intent), expected.as_ref())
+                        .await?;
+                }
+            }
+
+            self.storage
+                .commit(&format!("retire intent: {}", intent.label()))
+                .await
+        })
+        .await
+    }
+
+    /// Add or rename a tracker in the local plan version dated `date`
+    pub async fn add_tracker(&self, date: NaiveDate, id: &str, name: &str) -> Result<Plan> {
+        let message = format!("add tracker: {}", id);
+        self.edit_local_plan(date, message, |plan| Ok(plan.add_tracker(id, name)))
+            .await
+    }
+
+    /// Remove a tracker from the local plan version dated `date`
+    ///
+    /// Fails if the tracker doesn't exist or an intent still references it.
+    pub async fn remove_tracker(&self, date: NaiveDate, id: &str) -> Result<Plan> {
+        let message = format!("remove tracker: {}", id);
+        self.edit_local_plan(date, message, |plan| {
+            if !plan.trackers.contains_key(id) {
+                anyhow::bail!("Tracker '{}' is not in the local plan", id);
+            }
+            if plan.tracker_in_use(id) {
+                anyhow::bail!("Tracker '{}' is still referenced by an intent", id);
+            }
+            Ok(plan.remove_tracker(id))
+        })
+        .await
+    }
+
+    /// Add a role, objective, action or subject to the local plan version dated `date`
+    pub async fn add_vocabulary(
+        &self,
+        date: NaiveDate,
+        kind: Vocabulary,
+        value: &str,
+    ) -> Result<Plan> {
+        let message = format!("add {}: {}", kind, value);
+        self.edit_local_plan(date, message, |plan| Ok(plan.add_vocabulary(kind, value)))
+            .await
+    }
+
+    /// Rename a role, objective, action or subject in the local plan version dated `date`
+    ///
+    /// Intents using the old name are updated too, with their ids pinned so
+    /// existing logs still resolve to them.
+    pub async fn rename_vocabulary(
+        &self,
+        date: NaiveDate,
+        kind: Vocabulary,
+        old: &str,
+        new: &str,
+    ) -> Result<Plan> {
+        let message = format!("rename {}: {} -> {}", kind, old, new);
+        self.edit_local_plan(date, message, |plan| {
+            if !plan.vocabulary(kind).iter().any(|v| v == old) {
+                anyhow::bail!("{} '{}' is not in the local plan", kind, old);
+            }
+            Ok(plan.rename_vocabulary(kind, old, new))
+        })
+        .await
+    }
+
+    /// Remove a role, objective, action or subject from the local plan version dated `date`
+    pub async fn remove_vocabulary(
+        &self,
+        date: NaiveDate,
+        kind: Vocabulary,
+        value: &str,
+    ) -> Result<Plan> {
+        let message = format!("remove {}: {}", kind, value);
+        self.edit_local_plan(date, message, |plan| {
+            if !plan.vocabulary(kind).iter().any(|v| v == value) {
+                anyhow::bail!("{} '{}' is not in the local plan", kind, value);
+            }
+            Ok(plan.remove_vocabulary(kind, value))
+        })
+        .await
+    }
+
+    /// List all plan files
+    ///
+    /// Returns a vector of (source, valid_from_date) tuples
+    pub async fn list_plans(&self) -> Result<Vec<(String, NaiveDate)>> {
+        let mut plan_info = Vec::new();
+
+        for file_path in self.list_plan_files().await? {
+            let filename = file_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .with_context(|| format!("Invalid filename in plan directory: {:?}", file_path))?;
+
+            if let Some(info) = layout::parse_plan_file_name(filename) {
+                plan_info.push(info);
+            }
+        }
+
+        plan_info.sort();
+        Ok(plan_info)
+    }
+
+    /// Check if a plan exists for a specific source and date
+    pub async fn plan_exists(&self, source: &str, date: NaiveDate) -> bool {
+        self.storage
+            .exists(&self.storage.plan_file_path(source, date))
+            .await
+    }
+
+    /// Delete a plan
+    pub async fn delete_plan(&self, source: &str, date: NaiveDate) -> Result<()> {
+        operation(&self.storage, async {
+            let file_path = self.storage.plan_file_path(source, date);
+
+            if !self.storage.exists(&file_path).await {
+                anyhow::bail!(
+                    "Plan for source '{}' and date {} does not exist",
+                    source,
+                    date
+                );
+            }
+
+            self.storage.delete(&file_path).await.with_context(|| {
+                format!(
+                    "Failed to delete plan for source '{}' and date {}",
+                    source, date
+                )
+            })?;
+
+            #[cfg(feature = "sqlite")]
+            self.update_index(|index| index.remove_plan(source, date))?;
+            self.storage
+                .commit(&format!("delete plan {} {}", source, date))
+                .await
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::PlanManager;
+    use crate::memory_storage::MemoryStorage;
+    use crate::test_utils::block_on;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_reads_plans_written_by_sync_manager() {
+        let storage = Arc::new(MemoryStorage::new());
+        let sync = PlanManager::new(storage.clone());
+        let manager = AsyncPlanManager::new(storage);
+
+        let jan = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let feb = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+        sync.add_tracker(jan, "PROJ-1", "One").unwrap();
+        sync.add_tracker(feb, "PROJ-2", "Two").unwrap();
+
+        assert_eq!(
+            block_on(manager.get_plans(feb)).unwrap(),
+            sync.get_plans(feb).unwrap()
+        );
+        assert_eq!(block_on(manager.get_trackers(jan)).unwrap().len(), 1);
+        assert_eq!(
+            block_on(manager.list_plans()).unwrap(),
+            sync.list_plans().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_plan_uses_dated_filename() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = AsyncPlanManager::new(storage.clone());
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+        let plan = block_on(manager.get_local_plan_or_create(date))
+            .unwrap()
+            .add_tracker("PROJ-1", "One");
+        block_on(manager.write_plan(&plan)).unwrap();
+
+        assert!(storage
+            .files()
+            .contains_key(&PathBuf::from("/faff/plans/local.20250301.toml")));
+        assert!(block_on(manager.plan_exists("local", date)));
+    }
+}
@@ -0,0 +1,393 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+#[cfg(feature = "sqlite")]
+use std::sync::Arc;
+
+use crate::async_storage::{operation, AsyncStorage};
+use crate::layout;
+use crate::models::{Timesheet, TimesheetMeta};
+#[cfg(feature = "sqlite")]
+use crate::sqlite_index::SqliteIndex;
+use crate::storage::VersionToken;
+
+/// Manages timesheet storage and retrieval over `AsyncStorage`
+///
+/// This is the implementation behind `TimesheetManager`, which drives it
+/// over synchronous storage.
+#[derive(Clone)]
+pub struct AsyncTimesheetManager<S: AsyncStorage> {
+    storage: S,
+    #[cfg(feature = "sqlite")]
+    index: Option<Arc<SqliteIndex>>,
+}
+
+impl<S: AsyncStorage> AsyncTimesheetManager<S> {
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            #[cfg(feature = "sqlite")]
+            index: None,
+        }
+    }
+
+    /// Keep `index` up to date with every timesheet written or deleted
+    #[cfg(feature = "sqlite")]
+    pub fn with_index(mut self, index: Arc<SqliteIndex>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Apply a change to the index, if there is one
+    #[cfg(feature = "sqlite")]
+    fn update_index(&self, update: impl FnOnce(&SqliteIndex) -> Result<()>) -> Result<()> {
+        match &self.index {
+            Some(index) => {
+                update(index).context("Written to storage, but failed to update the index")
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Write a timesheet to storage
+    pub async fn write_timesheet(&self, timesheet: &Timesheet) -> Result<()> {
+        operation(&self.storage, async {
+            let message = format!(
+                "compile timesheet {} {}",
+                timesheet.meta.audience_id, timesheet.date
+            );
+            self.write_timesheet_as(timesheet, &message).await
+        })
+        .await
+    }
+
+    /// Write a timesheet to storage, described by `message` in storage history
+    pub(crate) async fn write_timesheet_as(
+        &self,
+        timesheet: &Timesheet,
+        message: &str,
+    ) -> Result<()> {
+        let timesheet_dir = self.storage.timesheet_dir();
+        self.storage
+            .create_dir_all(&timesheet_dir)
+            .await
+            .context("Failed to create timesheet directory")?;
+
+        // Write the canonical timesheet
+        let audience_id = &timesheet.meta.audience_id;
+        let timesheet_path = self
+            .storage
+            .timesheet_file_path(audience_id, timesheet.date);
+        let canonical = timesheet
+            .submittable_timesheet()
+            .canonical_form()
+            .context("Failed to create canonical form")?;
+        self.storage
+            .write_bytes(&timesheet_path, &canonical)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to write timesheet for {} on {}",
+                    timesheet.meta.audience_id, timesheet.date
+                )
+            })?;
+
+        // Write the metadata separately
+        let meta_path = self
+            .storage
+            .timesheet_meta_file_path(audience_id, timesheet.date);
+        let meta_json = serde_json::to_vec(&timesheet.meta)
+            .context("Failed to serialize timesheet metadata")?;
+        self.storage
+            .write_bytes(&meta_path, &meta_json)
+            .await
+            .context("Failed to write timesheet metadata")?;
+
+        #[cfg(feature = "sqlite")]
+        if self.index.is_some() {
+            let version = self
+                .timesheet_version(audience_id, timesheet.date)
+                .await
+                .context("Written to storage, but failed to update the index")?;
+            self.update_index(|index| index.index_timesheet(timesheet, version.as_ref()))?;
+        }
+        self.storage.commit(message).await
+    }
+
+    /// Version token of a timesheet's files
+    ///
+    /// Covers the timesheet and its metadata, so it changes when either
+    /// does. Returns None if the timesheet doesn't exist.
+    pub async fn timesheet_version(
+        &self,
+        audience_id: &str,
+        date: NaiveDate,
+    ) -> Result<Option<VersionToken>> {
+        let timesheet_path = self.storage.timesheet_file_path(audience_id, date);
+        let Some(version) = self.storage.version(&timesheet_path).await? else {
+            return Ok(None);
+        };
+        let meta_path = self.storage.timesheet_meta_file_path(audience_id, date);
+        Ok(Some(match self.storage.version(&meta_path).await? {
+            Some(meta_version) => VersionToken::new(format!("{}+{}", version, meta_version)),
+            None => version,
+        }))
+    }
+
+    /// Re-index the timesheets, optionally for one date, whose files changed
+    /// outside this manager
+    ///
+    /// Works like `AsyncLogManager`'s refresh: new and changed timesheets are
+    /// re-read, so a bad file fails the listing as it would without an
+    /// index, and timesheets whose files are gone are dropped.
+    #[cfg(feature = "sqlite")]
+    async fn refresh_index(&self, index: &SqliteIndex, date: Option<NaiveDate>) -> Result<()> {
+        let mut indexed = index.timesheet_versions(date)?;
+        for (audience_id, ts_date) in self.list_timesheet_keys(date).await? {
+            let stored = indexed.remove(&(audience_id.clone(), ts_date));
+            // Taken before reading, so a file changed in between is re-read
+            // next time rather than kept with stale contents
+            let current = self.timesheet_version(&audience_id, ts_date).await?;
+            if current.is_some() && current == stored {
+                continue;
+            }
+            if let Some(timesheet) = self.get_timesheet(&audience_id, ts_date).await? {
+                index
+                    .index_timesheet(&timesheet, current.as_ref())
+                    .context("Failed to update the index")?;
+            }
+        }
+        for (audience_id, ts_date) in indexed.into_keys() {
+            index
+                .remove_timesheet(&audience_id, ts_date)
+                .context("Failed to update the index")?;
+        }
+        Ok(())
+    }
+
+    /// Get a timesheet for a specific audience and date
+    ///
+    /// Returns None if the timesheet doesn't exist
+    pub async fn get_timesheet(
+        &self,
+        audience_id: &str,
+        date: NaiveDate,
+    ) -> Result<Option<Timesheet>> {
+        let timesheet_path = self.storage.timesheet_file_path(audience_id, date);
+
+        if !self.storage.exists(&timesheet_path).await {
+            return Ok(None);
+        }
+
+        // Read the timesheet
+        let timesheet_data = self
+            .storage
+            .read_string(&timesheet_path)
+            .await
+            .with_context(|| format!("Failed to read timesheet for {} on {}", audience_id, date))?;
+        let mut timesheet: Timesheet =
+            serde_json::from_str(&timesheet_data).with_context(|| {
+                format!("Failed to parse timesheet for {} on {}", audience_id, date)
+            })?;
+
+        // Try to load metadata if it exists
+        let meta_path = self.storage.timesheet_meta_file_path(audience_id, date);
+
+        if self.storage.exists(&meta_path).await {
+            let meta_data = self
+                .storage
+                .read_string(&meta_path)
+                .await
+                .context("Failed to read timesheet metadata")?;
+            let meta: TimesheetMeta =
+                serde_json::from_str(&meta_data).context("Failed to parse timesheet metadata")?;
+            timesheet.meta = meta;
+        }
+
+        Ok(Some(timesheet))
+    }
+
+    /// List all timesheets, optionally filtered by date
+    ///
+    /// With an index, only timesheets whose files changed since they were
+    /// indexed are read and parsed.
+    pub async fn list_timesheets(&self, date: Option<NaiveDate>) -> Result<Vec<Timesheet>> {
+        #[cfg(feature = "sqlite")]
+        if let Some(index) = &self.index {
+            self.refresh_index(index, date).await?;
+            return match date {
+                Some(d) => index.timesheets_between(None, d, d),
+                None => index.timesheets(),
+            };
+        }
+
+        let mut timesheets = Vec::new();
+        for (audience_id, ts_date) in self.list_timesheet_keys(date).await? {
+            match self.get_timesheet(&audience_id, ts_date).await {
+                Ok(Some(timesheet)) => timesheets.push(timesheet),
+                Ok(None) => {
+                    eprintln!(
+                        "[WARN] Timesheet file exists but couldn't be loaded: {}",
+                        layout::timesheet_file_name(&audience_id, ts_date)
+                    );
+                }
+                Err(e) => {
+                    eprintln!(
+                        "[ERROR] Failed to load timesheet {}: {}",
+                        layout::timesheet_file_name(&audience_id, ts_date),
+                        e
+                    );
+                    return Err(e);
+                }
+            }
+        }
+
+        timesheets.sort_by_key(|t| t.date);
+        Ok(timesheets)
+    }
+
+    /// The audience and date of every timesheet in storage, optionally
+    /// filtered by date
+    ///
+    /// Files whose names don't follow the timesheet layout are skipped with
+    /// a warning.
+    pub async fn list_timesheet_keys(
+        &self,
+        date: Option<NaiveDate>,
+    ) -> Result<Vec<(String, NaiveDate)>> {
+        let timesheet_dir = self.storage.timesheet_dir();
+
+        let pattern = match date {
+            Some(d) => layout::timesheet_files_on(d),
+            None => layout::TIMESHEET_FILES.to_string(),
+        };
+
+        let files = self
+            .storage
+            .list_files(&timesheet_dir, &pattern)
+            .await
+            .context("Failed to list timesheet files")?;
+        let mut keys = Vec::new();
+
+        for file in files {
+            let filename = file
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .ok_or_else(|| anyhow::anyhow!("Invalid filename"))?;
+
+            // Skip meta files
+            if filename.ends_with(".meta") {
+                continue;
+            }
+
+            // Parse audience_id and date from filename: audience.YYYY-MM-DD
+            let (audience_id, ts_date) = match layout::parse_timesheet_stem(filename) {
+                Ok(parsed) => parsed,
+                Err(warning) => {
+                    eprintln!("[WARN] {}", warning);
+                    continue;
+                }
+            };
+
+            // Filter by date if specified
+            if let Some(filter_date) = date {
+                if ts_date != filter_date {
+                    continue;
+                }
+            }
+
+            keys.push((audience_id.to_string(), ts_date));
+        }
+
+        Ok(keys)
+    }
+
+    /// Check if a timesheet exists for a specific audience and date
+    pub async fn timesheet_exists(&self, audience_id: &str, date: NaiveDate) -> bool {
+        self.storage
+            .exists(&self.storage.timesheet_file_path(audience_id, date))
+            .await
+    }
+
+    /// Delete a timesheet
+    pub async fn delete_timesheet(&self, audience_id: &str, date: NaiveDate) -> Result<()> {
+        operation(&self.storage, async {
+            let timesheet_path = self.storage.timesheet_file_path(audience_id, date);
+
+            if !self.storage.exists(&timesheet_path).await {
+                anyhow::bail!(
+                    "Timesheet for audience '{}' on {} does not exist",
+                    audience_id,
+                    date
+                );
+            }
+
+            // Delete the timesheet file
+            self.storage
+                .delete(&timesheet_path)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to delete timesheet for audience '{}' on {}",
+                        audience_id, date
+                    )
+                })?;
+
+            // Delete the metadata file if it exists
+            let meta_path = self.storage.timesheet_meta_file_path(audience_id, date);
+
+            if self.storage.exists(&meta_path).await {
+                self.storage
+                    .delete(&meta_path)
+                    .await
+                    .context("Failed to delete timesheet metadata")?;
+            }
+
+            #[cfg(feature = "sqlite")]
+            self.update_index(|index| index.remove_timesheet(audience_id, date))?;
+            self.storage
+                .commit(&format!("delete timesheet {} {}", audience_id, date))
+                .await
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::TimesheetManager;
+    use crate::memory_storage::MemoryStorage;
+    use crate::test_utils::block_on;
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_round_trips_with_sync_manager() {
+        let storage = Arc::new(MemoryStorage::new());
+        let sync = TimesheetManager::new(storage.clone());
+        let manager = AsyncTimesheetManager::new(storage);
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let timesheet = Timesheet::new(
+            HashMap::new(),
+            date,
+            Utc::now().with_timezone(&chrono_tz::UTC),
+            chrono_tz::UTC,
+            vec![],
+            HashMap::new(),
+            TimesheetMeta::new("acme".to_string(), None, None),
+        );
+        block_on(manager.write_timesheet(&timesheet)).unwrap();
+
+        assert!(block_on(manager.timesheet_exists("acme", date)));
+        assert_eq!(
+            sync.get_timesheet("acme", date).unwrap(),
+            block_on(manager.get_timesheet("acme", date)).unwrap()
+        );
+        assert_eq!(
+            block_on(manager.list_timesheets(Some(date))).unwrap().len(),
+            1
+        );
+    }
+}
@@ -1,30 +1,25 @@
+use crate::async_storage::block_on;
+use crate::managers::AsyncIdentityManager;
 use crate::storage::Storage;
-use anyhow::{Context, Result};
+use anyhow::Result;
 use ed25519_dalek::SigningKey;
-use rand::rngs::OsRng;
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Manages Ed25519 identity keypairs for signing timesheets
+///
+/// Drives an `AsyncIdentityManager` over synchronous storage, which is
+/// where the operations are implemented.
 #[derive(Clone)]
 pub struct IdentityManager {
-    storage: Arc<dyn Storage>,
+    inner: AsyncIdentityManager<Arc<dyn Storage>>,
 }
 
 impl IdentityManager {
     pub fn new(storage: Arc<dyn Storage>) -> Self {
-        Self { storage }
-    }
-
-    /// Get the path for a private key file
-    fn get_key_path(&self, name: &str) -> PathBuf {
-        self.storage.identity_dir().join(format!("id_{}", name))
-    }
-
-    /// Get the path for a public key file
-    fn get_pub_path(&self, name: &str) -> PathBuf {
-        self.storage.identity_dir().join(format!("id_{}.pub", name))
+        Self {
+            inner: AsyncIdentityManager::new(storage),
+        }
     }
 
     /// Create a new Ed25519 identity keypair
@@ -33,157 +28,43 @@ impl IdentityManager {
     /// - Private key: ~/.faff/identities/id_{name}
     /// - Public key: ~/.faff/identities/id_{name}.pub
     pub fn create_identity(&self, name: &str, overwrite: bool) -> Result<SigningKey> {
-        let private_path = self.get_key_path(name);
-        let public_path = self.get_pub_path(name);
-
-        if !overwrite && self.storage.exists(&private_path) {
-            anyhow::bail!("Identity '{}' already exists", name);
-        }
-
-        // Ensure identity directory exists
-        let identity_dir = self.storage.identity_dir();
-        self.storage
-            .create_dir_all(&identity_dir)
-            .context("Failed to create identity directory")?;
-
-        // Generate new keypair
-        let mut csprng = OsRng;
-        let mut secret_bytes = [0u8; 32];
-        rand::RngCore::fill_bytes(&mut csprng, &mut secret_bytes);
-        let signing_key = SigningKey::from_bytes(&secret_bytes);
-        let verifying_key = signing_key.verifying_key();
-
-        // Encode keys as base64
-        let b64_private = base64::Engine::encode(
-            &base64::engine::general_purpose::STANDARD,
-            signing_key.to_bytes(),
-        );
-        let b64_public = base64::Engine::encode(
-            &base64::engine::general_purpose::STANDARD,
-            verifying_key.to_bytes(),
-        );
-
-        // Write keys to files
-        self.storage
-            .write_string(&private_path, &b64_private)
-            .with_context(|| format!("Failed to write private key for identity '{}'", name))?;
-        self.storage
-            .write_string(&public_path, &b64_public)
-            .with_context(|| format!("Failed to write public key for identity '{}'", name))?;
-
-        // Note: File permissions (chmod 0o600) should be handled by the Storage implementation
-        // if it's a real filesystem. For testing with mock storage, this is skipped.
-
-        Ok(signing_key)
+        block_on(self.inner.create_identity(name, overwrite))
     }
 
     /// Check if an identity exists
     pub fn identity_exists(&self, name: &str) -> bool {
-        self.storage.exists(&self.get_key_path(name))
+        block_on(self.inner.identity_exists(name))
     }
 
     /// Delete an identity
     ///
     /// Removes both the private and public key files
     pub fn delete_identity(&self, name: &str) -> Result<()> {
-        let private_path = self.get_key_path(name);
-        let public_path = self.get_pub_path(name);
-
-        if !self.storage.exists(&private_path) {
-            anyhow::bail!("Identity '{}' does not exist", name);
-        }
-
-        // Delete private key
-        self.storage
-            .delete(&private_path)
-            .with_context(|| format!("Failed to delete private key for identity '{}'", name))?;
-
-        // Delete public key if it exists
-        if self.storage.exists(&public_path) {
-            self.storage
-                .delete(&public_path)
-                .with_context(|| format!("Failed to delete public key for identity '{}'", name))?;
-        }
-
-        Ok(())
+        block_on(self.inner.delete_identity(name))
     }
 
     /// Get a specific identity by name
     pub fn get_identity(&self, name: &str) -> Result<Option<SigningKey>> {
-        let identities = self.list_identities()?;
-        Ok(identities.get(name).cloned())
+        block_on(self.inner.get_identity(name))
     }
 
     /// List all identities
     ///
     /// Returns a HashMap where keys are identity names and values are SigningKeys
     pub fn list_identities(&self) -> Result<HashMap<String, SigningKey>> {
-        let identity_dir = self.storage.identity_dir();
-        let mut identities = HashMap::new();
-
-        // List all files matching "id_*" pattern
-        let files = self
-            .storage
-            .list_files(&identity_dir, "id_*")
-            .context("Failed to list identity files")?;
-
-        for file in files {
-            // Skip public key files
-            if file.extension().and_then(|s| s.to_str()) == Some("pub") {
-                continue;
-            }
-
-            // Extract identity name (remove "id_" prefix)
-            let filename = file
-                .file_name()
-                .and_then(|s| s.to_str())
-                .with_context(|| format!("Invalid filename in identity directory: {:?}", file))?;
-
-            if !filename.starts_with("id_") {
-                continue;
-            }
-
-            let name = &filename[3..]; // Remove "id_" prefix
-
-            // Read and decode the private key
-            let b64_private = self
-                .storage
-                .read_string(&file)
-                .with_context(|| format!("Failed to read identity file '{}'", name))?;
-
-            let key_bytes = base64::Engine::decode(
-                &base64::engine::general_purpose::STANDARD,
-                b64_private.trim(),
-            )
-            .with_context(|| format!("Failed to decode base64 key for identity '{}'", name))?;
-
-            if key_bytes.len() != 32 {
-                anyhow::bail!(
-                    "Invalid key length for identity '{}': expected 32 bytes, got {}",
-                    name,
-                    key_bytes.len()
-                );
-            }
-
-            let mut key_array = [0u8; 32];
-            key_array.copy_from_slice(&key_bytes);
-            let signing_key = SigningKey::from_bytes(&key_array);
-
-            identities.insert(name.to_string(), signing_key);
-        }
-
-        Ok(identities)
+        block_on(self.inner.list_identities())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::mock_storage::MockStorage;
+    use crate::memory_storage::MemoryStorage;
+    use std::path::PathBuf;
 
     #[test]
     fn test_create_identity() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = IdentityManager::new(storage.clone());
 
         let key = manager.create_identity("test", false).unwrap();
@@ -203,7 +84,7 @@ mod tests {
 
     #[test]
     fn test_create_identity_no_overwrite() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = IdentityManager::new(storage.clone());
 
         manager.create_identity("test", false).unwrap();
@@ -216,7 +97,7 @@ mod tests {
 
     #[test]
     fn test_create_identity_with_overwrite() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = IdentityManager::new(storage.clone());
 
         let key1 = manager.create_identity("test", false).unwrap();
@@ -228,7 +109,7 @@ mod tests {
 
     #[test]
     fn test_get_identity() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = IdentityManager::new(storage.clone());
 
         let key = manager.create_identity("alice", false).unwrap();
@@ -243,7 +124,7 @@ mod tests {
 
     #[test]
     fn test_list_identities() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = IdentityManager::new(storage.clone());
 
         let key1 = manager.create_identity("alice", false).unwrap();
@@ -257,7 +138,7 @@ mod tests {
 
     #[test]
     fn test_identity_exists() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = IdentityManager::new(storage.clone());
 
         assert!(!manager.identity_exists("test"));
@@ -269,7 +150,7 @@ mod tests {
 
     #[test]
     fn test_delete_identity() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = IdentityManager::new(storage.clone());
 
         manager.create_identity("test", false).unwrap();
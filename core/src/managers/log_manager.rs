@@ -1,175 +1,261 @@
-use anyhow::{Context, Result};
-use chrono::NaiveDate;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate};
 use chrono_tz::Tz;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::models::Log;
-use crate::storage::Storage;
+use crate::async_storage::block_on;
+use crate::import::MergeOutcome;
+use crate::managers::AsyncLogManager;
+use crate::models::allocation::Allocation;
+use crate::models::budget::{Budget, BudgetStatus};
+use crate::models::intent_resolver::IntentResolver;
+use crate::models::intent_stats::{IntentStats, IntentSuggestions};
+use crate::models::report::{Dimension, Report};
+use crate::models::rounding::{RoundedTime, Rounding};
+use crate::models::working_hours::{Schedule, WorkingHours};
+use crate::models::{Intent, Log, Session};
+#[cfg(feature = "sqlite")]
+use crate::sqlite_index::SqliteIndex;
+use crate::storage::{Storage, VersionToken};
 
 /// Manages log file operations.
 ///
 /// Handles reading, writing, listing, and deleting daily logs.
-/// Note: TOML parsing/formatting is currently handled on the Python side.
-/// This manager provides the storage abstraction layer.
+/// Drives an `AsyncLogManager` over synchronous storage, which is where
+/// the operations are implemented.
 #[derive(Clone)]
 pub struct LogManager {
-    storage: Arc<dyn Storage>,
-    timezone: Tz,
+    inner: AsyncLogManager<Arc<dyn Storage>>,
 }
 
 impl LogManager {
     pub fn new(storage: Arc<dyn Storage>, timezone: Tz) -> Self {
-        Self { storage, timezone }
+        Self {
+            inner: AsyncLogManager::new(storage, timezone),
+        }
+    }
+
+    /// Drive an existing `AsyncLogManager` over synchronous storage
+    pub(crate) fn from_async(inner: AsyncLogManager<Arc<dyn Storage>>) -> Self {
+        Self { inner }
+    }
+
+    /// Keep `index` up to date with every log written or deleted
+    #[cfg(feature = "sqlite")]
+    pub fn with_index(self, index: Arc<SqliteIndex>) -> Self {
+        Self {
+            inner: self.inner.with_index(index),
+        }
+    }
+
+    /// The async manager this one drives
+    pub(crate) fn as_async(&self) -> &AsyncLogManager<Arc<dyn Storage>> {
+        &self.inner
     }
 
     /// Get the path for a log file
     pub fn log_file_path(&self, date: NaiveDate) -> std::path::PathBuf {
-        self.storage.log_file_path(date)
+        self.inner.log_file_path(date)
     }
 
     /// Check if a log file exists
     pub fn log_exists(&self, date: NaiveDate) -> bool {
-        let log_path = self.storage.log_file_path(date);
-        self.storage.exists(&log_path)
+        block_on(self.inner.log_exists(date))
     }
 
     /// Read the raw log file contents
     pub fn read_log_raw(&self, date: NaiveDate) -> Result<String> {
-        let log_path = self.storage.log_file_path(date);
-        self.storage
-            .read_string(&log_path)
-            .context(format!("Failed to read log file for {}", date))
+        block_on(self.inner.read_log_raw(date))
     }
 
     /// Write raw log file contents
     pub fn write_log_raw(&self, date: NaiveDate, contents: &str) -> Result<()> {
-        let log_path = self.storage.log_file_path(date);
-        self.storage
-            .write_string(&log_path, contents)
-            .context(format!("Failed to write log for {}", date))
+        block_on(self.inner.write_log_raw(date, contents))
     }
 
     /// Get timezone for creating empty logs
     pub fn timezone(&self) -> Tz {
-        self.timezone
+        self.inner.timezone()
     }
 
     /// Get a log for a given date
     ///
     /// Returns None if the log file doesn't exist
     pub fn get_log(&self, date: NaiveDate) -> Result<Option<Log>> {
-        let log_path = self.storage.log_file_path(date);
-
-        if self.storage.exists(&log_path) {
-            let toml_str = self
-                .storage
-                .read_string(&log_path)
-                .with_context(|| format!("Failed to read log file for {}", date))?;
-
-            let log = Log::from_log_file(&toml_str)
-                .with_context(|| format!("Failed to parse log file for {}", date))?;
-            Ok(Some(log))
-        } else {
-            Ok(None)
-        }
+        block_on(self.inner.get_log(date))
     }
 
     /// Get a log for a given date, creating an empty one if it doesn't exist
     ///
     /// This is a convenience method for callers who always want a log to work with
     pub fn get_log_or_create(&self, date: NaiveDate) -> Result<Log> {
-        if let Some(log) = self.get_log(date)? {
-            Ok(log)
-        } else {
-            Ok(Log::new(date, self.timezone, vec![]))
-        }
+        block_on(self.inner.get_log_or_create(date))
     }
 
     /// Write a log to storage
     ///
     /// trackers: map of tracker IDs to human-readable names for comments
-    pub fn write_log(
+    pub fn write_log(&self, log: &Log, trackers: &HashMap<String, String>) -> Result<()> {
+        block_on(self.inner.write_log(log, trackers))
+    }
+
+    /// Get a log for a given date along with its version token
+    ///
+    /// Returns an empty log and no version if the log file doesn't exist.
+    /// Pass the version to `write_log_if` to detect concurrent changes.
+    pub fn get_log_versioned(&self, date: NaiveDate) -> Result<(Log, Option<VersionToken>)> {
+        block_on(self.inner.get_log_versioned(date))
+    }
+
+    /// Write a log only if it hasn't changed since it was read at `expected`
+    ///
+    /// Fails with a `VersionConflict` if it has. Returns the new version.
+    pub fn write_log_if(
         &self,
         log: &Log,
-        trackers: &std::collections::HashMap<String, String>,
-    ) -> Result<()> {
-        let log_contents = log.to_log_file(trackers);
-        let log_path = self.storage.log_file_path(log.date);
-
-        self.storage
-            .write_string(&log_path, &log_contents)
-            .context(format!("Failed to write log for {}", log.date))
+        trackers: &HashMap<String, String>,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        block_on(self.inner.write_log_if(log, trackers, expected))
     }
 
     /// List all log dates in storage
     pub fn list_logs(&self) -> Result<Vec<NaiveDate>> {
-        let log_dir = self.storage.log_dir();
-        let files = self
-            .storage
-            .list_files(&log_dir, "*.toml")
-            .context("Failed to list log files")?;
-
-        let mut dates = Vec::new();
-        for file in files {
-            // Extract date from filename (YYYY-MM-DD.toml)
-            if let Some(stem) = file.file_stem().and_then(|s| s.to_str()) {
-                if let Ok(date) = NaiveDate::parse_from_str(stem, "%Y-%m-%d") {
-                    dates.push(date);
-                }
-            }
-        }
+        block_on(self.inner.list_logs())
+    }
 
-        dates.sort();
-        Ok(dates)
+    /// Get all logs with dates in the inclusive range `start..=end`
+    ///
+    /// Returns logs sorted by date; days without a log file are skipped
+    pub fn get_logs_between(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Log>> {
+        block_on(self.inner.get_logs_between(start, end))
     }
 
-    /// Delete a log for a given date
-    pub fn delete_log(&self, date: NaiveDate) -> Result<()> {
-        let log_path = self.storage.log_file_path(date);
+    /// Compute usage statistics for the given intents relative to `now`
+    ///
+    /// Callers typically pass today's plan intents so that only currently
+    /// valid intents are reported.
+    pub fn intent_stats(&self, intents: &[Intent], now: DateTime<Tz>) -> Result<Vec<IntentStats>> {
+        block_on(self.inner.intent_stats(intents, now))
+    }
 
-        if !self.storage.exists(&log_path) {
-            anyhow::bail!("Log for {} does not exist", date);
-        }
+    /// Suggest intents for quick-pick lists, at most `limit` per list
+    pub fn suggest_intents(
+        &self,
+        intents: &[Intent],
+        now: DateTime<Tz>,
+        limit: usize,
+    ) -> Result<IntentSuggestions> {
+        block_on(self.inner.suggest_intents(intents, now, limit))
+    }
 
-        self.storage
-            .delete(&log_path)
-            .with_context(|| format!("Failed to delete log for {}", date))
+    /// Merge imported sessions into the log for `date`
+    ///
+    /// Sessions duplicating or overlapping ones already logged are left out;
+    /// see `import::merge`. With `dry_run` nothing is written.
+    pub fn merge_sessions(
+        &self,
+        date: NaiveDate,
+        sessions: Vec<Session>,
+        trackers: &HashMap<String, String>,
+        dry_run: bool,
+    ) -> Result<MergeOutcome> {
+        block_on(self.inner.merge_sessions(date, sessions, trackers, dry_run))
+    }
+
+    /// Recorded time in logs dated `start..=end`, grouped by `group_by`
+    ///
+    /// Time is credited to trackers per `allocation`, normally the config's.
+    pub fn report(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &[Dimension],
+        allocation: &Allocation,
+    ) -> Result<Report> {
+        block_on(self.inner.report(start, end, group_by, allocation))
+    }
+
+    /// Time recorded in logs dated `start..=end`, raw and rounded per `rounding`
+    pub fn rounded_time(
+        &self,
+        rounding: &Rounding,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<RoundedTime> {
+        block_on(self.inner.rounded_time(rounding, start, end))
+    }
+
+    /// Expected versus recorded hours in `start..=end` under `schedule`
+    pub fn working_hours(
+        &self,
+        schedule: &Schedule,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<WorkingHours> {
+        block_on(self.inner.working_hours(schedule, start, end))
+    }
+
+    /// Progress of each budget in its period containing `today`
+    ///
+    /// Time is credited to trackers per `allocation`, normally the config's.
+    pub fn budget_status(
+        &self,
+        budgets: &[Budget],
+        today: NaiveDate,
+        allocation: &Allocation,
+    ) -> Result<Vec<BudgetStatus>> {
+        block_on(self.inner.budget_status(budgets, today, allocation))
+    }
+
+    /// Warnings for budgets that time on `intent` would count towards
+    ///
+    /// Front ends show these before starting a session, e.g. when the
+    /// budget is already exceeded or on course to be.
+    pub fn budget_warnings(
+        &self,
+        budgets: &[Budget],
+        intent: &Intent,
+        today: NaiveDate,
+        allocation: &Allocation,
+    ) -> Result<Vec<String>> {
+        block_on(
+            self.inner
+                .budget_warnings(budgets, intent, today, allocation),
+        )
+    }
+
+    /// Rewrite historical logs to use current intent definitions
+    ///
+    /// Only logs with at least one changed session are written back.
+    /// Returns the dates of the rewritten logs.
+    pub fn rewrite_intents(
+        &self,
+        resolver: &IntentResolver,
+        trackers: &HashMap<String, String>,
+    ) -> Result<Vec<NaiveDate>> {
+        block_on(self.inner.rewrite_intents(resolver, trackers))
+    }
+
+    /// Delete a log for a given date
+    pub fn delete_log(&self, date: NaiveDate) -> Result<()> {
+        block_on(self.inner.delete_log(date))
     }
 
     /// Start a new session with the given intent at the current time
     pub fn start_intent_now(
         &self,
-        intent: crate::models::Intent,
+        intent: Intent,
         note: Option<String>,
         current_date: NaiveDate,
-        current_time: chrono::DateTime<Tz>,
-        trackers: &std::collections::HashMap<String, String>,
+        current_time: DateTime<Tz>,
+        trackers: &HashMap<String, String>,
     ) -> Result<()> {
-        // Get today's log or create empty one
-        let log = self.get_log_or_create(current_date)?;
-
-        // Validate trackers if any are specified
-        if !intent.trackers.is_empty() {
-            let tracker_ids: std::collections::HashSet<_> = trackers.keys().collect();
-            let intent_tracker_set: std::collections::HashSet<_> = intent.trackers.iter().collect();
-
-            if !intent_tracker_set.is_subset(&tracker_ids) {
-                let missing: Vec<_> = intent_tracker_set
-                    .difference(&tracker_ids)
-                    .map(|s| s.as_str())
-                    .collect();
-                anyhow::bail!("Tracker {} not found in today's plan", missing.join(", "));
-            }
-        }
-
-        // Create new session
-        let session = crate::models::Session::new(intent, current_time, None, note);
-
-        // Append to log and write
-        let updated_log = log.append_session(session)?;
-        self.write_log(&updated_log, trackers)?;
-
-        Ok(())
+        block_on(
+            self.inner
+                .start_intent_now(intent, note, current_date, current_time, trackers),
+        )
     }
 
     /// Stop the currently active session
@@ -178,29 +264,26 @@ impl LogManager {
     pub fn stop_current_session(
         &self,
         current_date: NaiveDate,
-        current_time: chrono::DateTime<Tz>,
-        trackers: &std::collections::HashMap<String, String>,
+        current_time: DateTime<Tz>,
+        trackers: &HashMap<String, String>,
     ) -> Result<()> {
-        let log = self.get_log_or_create(current_date)?;
-
-        if log.active_session().is_some() {
-            let updated_log = log.stop_active_session(current_time)?;
-            self.write_log(&updated_log, trackers)?;
-            Ok(())
-        } else {
-            anyhow::bail!("No active session to stop")
-        }
+        block_on(
+            self.inner
+                .stop_current_session(current_date, current_time, trackers),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::mock_storage::MockStorage;
+    use crate::memory_storage::MemoryStorage;
+    use crate::storage::VersionConflict;
+    use chrono::Datelike;
 
     #[test]
     fn test_log_exists() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = LogManager::new(storage.clone(), chrono_tz::UTC);
 
         let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
@@ -215,7 +298,7 @@ mod tests {
 
     #[test]
     fn test_write_and_read_raw() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = LogManager::new(storage, chrono_tz::UTC);
 
         let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
@@ -229,7 +312,7 @@ mod tests {
 
     #[test]
     fn test_list_logs() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = LogManager::new(storage, chrono_tz::UTC);
 
         let date1 = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
@@ -244,9 +327,110 @@ mod tests {
         assert_eq!(dates[1], date2);
     }
 
+    #[test]
+    fn test_get_logs_between() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = LogManager::new(storage, chrono_tz::UTC);
+
+        for day in [10, 15, 20] {
+            let date = NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+            manager
+                .write_log(&Log::new(date, chrono_tz::UTC, vec![]), &Default::default())
+                .unwrap();
+        }
+
+        let logs = manager
+            .get_logs_between(
+                NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 3, 20).unwrap(),
+            )
+            .unwrap();
+        let dates: Vec<_> = logs.iter().map(|l| l.date.day()).collect();
+        assert_eq!(dates, vec![15, 20]);
+    }
+
+    #[test]
+    fn test_intent_stats_ignores_logs_outside_lookback() {
+        use chrono::TimeZone;
+
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = LogManager::new(storage, chrono_tz::UTC);
+        let intent = Intent::new(Some("work".to_string()), None, None, None, None, vec![]);
+
+        for (year, month) in [(2024, 1), (2025, 3)] {
+            let start = chrono_tz::UTC
+                .with_ymd_and_hms(year, month, 10, 9, 0, 0)
+                .unwrap();
+            let session = crate::models::Session::new(
+                intent.clone(),
+                start,
+                Some(start + chrono::Duration::hours(1)),
+                None,
+            );
+            let log = Log::new(start.date_naive(), chrono_tz::UTC, vec![session]);
+            manager.write_log(&log, &Default::default()).unwrap();
+        }
+
+        let now = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 12, 9, 0, 0)
+            .unwrap();
+        let stats = manager.intent_stats(&[intent], now).unwrap();
+        assert_eq!(stats[0].session_count, 1);
+        assert_eq!(stats[0].total_duration, chrono::Duration::hours(1));
+
+        let suggestions = manager.suggest_intents(&[], now, 5).unwrap();
+        assert!(suggestions.most_used_this_week.is_empty());
+    }
+
+    #[test]
+    fn test_budget_warnings() {
+        use chrono::TimeZone;
+
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = LogManager::new(storage, chrono_tz::UTC);
+        let billable = Intent::new(
+            Some("abc".to_string()),
+            None,
+            None,
+            None,
+            None,
+            vec!["ABC-123".to_string()],
+        );
+        let other = Intent::new(Some("other".to_string()), None, None, None, None, vec![]);
+
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 10, 9, 0, 0)
+            .unwrap();
+        let session = crate::models::Session::new(
+            billable.clone(),
+            start,
+            Some(start + chrono::Duration::hours(5)),
+            None,
+        );
+        let log = Log::new(start.date_naive(), chrono_tz::UTC, vec![session]);
+        manager.write_log(&log, &Default::default()).unwrap();
+
+        let budgets: Vec<Budget> =
+            vec![toml::from_str("tracker = \"ABC-123\"\nhours = 4\nperiod = \"week\"").unwrap()];
+        let today = start.date_naive();
+        let allocation = Allocation::default();
+
+        let warnings = manager
+            .budget_warnings(&budgets, &billable, today, &allocation)
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec!["tracker ABC-123 is 1.0h over its 4.0h week budget"]
+        );
+        assert!(manager
+            .budget_warnings(&budgets, &other, today, &allocation)
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_get_log_parses_toml() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = LogManager::new(storage, chrono_tz::UTC);
 
         let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
@@ -282,7 +466,7 @@ note = "Morning session"
 
     #[test]
     fn test_get_log_returns_none_when_missing() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = LogManager::new(storage, chrono_tz::UTC);
 
         let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
@@ -293,7 +477,7 @@ note = "Morning session"
 
     #[test]
     fn test_get_log_or_create() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = LogManager::new(storage, chrono_tz::UTC);
 
         let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
@@ -302,4 +486,90 @@ note = "Morning session"
         assert_eq!(log.date, date);
         assert_eq!(log.timeline.len(), 0);
     }
+
+    #[test]
+    fn test_write_log_if_detects_concurrent_change() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = LogManager::new(storage, chrono_tz::UTC);
+        let trackers = std::collections::HashMap::new();
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let (log, version) = manager.get_log_versioned(date).unwrap();
+        assert!(version.is_none());
+        let version = manager
+            .write_log_if(&log, &trackers, version.as_ref())
+            .unwrap();
+
+        // Someone else changes the log after we read it
+        manager
+            .write_log_raw(
+                date,
+                "date = \"2025-03-15\"\ntimezone = \"UTC\"\n# edited\n",
+            )
+            .unwrap();
+
+        let err = manager
+            .write_log_if(&log, &trackers, Some(&version))
+            .unwrap_err();
+        let conflict = err.downcast_ref::<VersionConflict>().unwrap();
+        assert_eq!(conflict.expected, Some(version));
+        assert_ne!(conflict.actual, conflict.expected);
+    }
+
+    #[test]
+    fn test_versioned_read_matches_current_version() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let trackers = std::collections::HashMap::new();
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let written = manager
+            .write_log_if(&Log::new(date, chrono_tz::UTC, vec![]), &trackers, None)
+            .unwrap();
+
+        let (_, read) = manager.get_log_versioned(date).unwrap();
+        assert_eq!(read, Some(written.clone()));
+        assert_eq!(
+            storage.version(&manager.log_file_path(date)).unwrap(),
+            Some(written)
+        );
+    }
+
+    #[test]
+    fn test_merge_sessions_waits_for_lock() {
+        use chrono::TimeZone;
+
+        let storage =
+            Arc::new(MemoryStorage::new().with_lock_timeout(std::time::Duration::from_millis(50)));
+        let manager = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 10, 9, 0, 0)
+            .unwrap();
+        let intent = Intent::new(Some("Email".to_string()), None, None, None, None, vec![]);
+        let sessions = vec![crate::models::Session::new(
+            intent,
+            start,
+            Some(start + chrono::Duration::hours(1)),
+            None,
+        )];
+        let date = start.date_naive();
+
+        let held = storage.lock().unwrap();
+        assert!(manager
+            .merge_sessions(date, sessions.clone(), &HashMap::new(), false)
+            .is_err());
+        assert!(!manager.log_exists(date));
+        // A dry run only reads, so doesn't need the lock
+        let preview = manager
+            .merge_sessions(date, sessions.clone(), &HashMap::new(), true)
+            .unwrap();
+        assert_eq!(preview.added.len(), 1);
+
+        drop(held);
+        let outcome = manager
+            .merge_sessions(date, sessions, &HashMap::new(), false)
+            .unwrap();
+        assert_eq!(outcome.added.len(), 1);
+        assert_eq!(manager.get_log(date).unwrap().unwrap().timeline.len(), 1);
+    }
 }
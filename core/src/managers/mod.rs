@@ -1,11 +1,20 @@
+pub mod async_identity_manager;
+pub mod async_log_manager;
+pub mod async_plan_manager;
+pub mod async_timesheet_manager;
 pub mod identity_manager;
 pub mod log_manager;
+pub(crate) mod plan_helpers;
 pub mod plan_manager;
 pub mod timesheet_manager;
 
 #[cfg(feature = "python")]
 pub mod plugin_manager;
 
+pub use async_identity_manager::AsyncIdentityManager;
+pub use async_log_manager::AsyncLogManager;
+pub use async_plan_manager::AsyncPlanManager;
+pub use async_timesheet_manager::AsyncTimesheetManager;
 pub use identity_manager::IdentityManager;
 pub use log_manager::LogManager;
 pub use plan_manager::PlanManager;
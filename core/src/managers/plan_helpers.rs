@@ -0,0 +1,260 @@
+//! Plan logic shared by the plan managers and the index
+//!
+//! Picking plan files, checking validity and collecting vocabulary don't
+//! touch storage, so they live here rather than on either manager.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::layout;
+use crate::models::intent::Intent;
+use crate::models::plan::Plan;
+use crate::models::plan_lint::{PlanDiagnostic, PlanFileReport};
+
+/// Source of the plan edited through the managers
+pub(crate) const LOCAL_PLAN_SOURCE: &str = "local";
+
+/// Check that a plan file is named `<source>.<YYYYMMDD>.toml` after its contents
+pub(crate) fn lint_filename(file_path: &Path, plan: &Plan) -> Option<PlanDiagnostic> {
+    let filename = file_path.file_name()?.to_str()?;
+    let expected = layout::plan_file_name(&plan.source, plan.valid_from);
+    if filename == expected {
+        None
+    } else {
+        Some(PlanDiagnostic::warning(
+            "filename-mismatch",
+            format!(
+                "File is named '{}' but its source and valid_from suggest '{}'",
+                filename, expected
+            ),
+        ))
+    }
+}
+
+/// Report for a plan file skipped because it failed to load
+pub(crate) fn load_failure(file_path: PathBuf, error: &anyhow::Error) -> PlanFileReport {
+    let diagnostic = PlanDiagnostic::error("parse-error", format!("{:#}", error));
+    PlanFileReport::new(file_path, vec![diagnostic])
+}
+
+/// Add a plan to `plans` if it's valid on `date` and the most recent for its source
+pub(crate) fn insert_if_valid(plans: &mut HashMap<String, Plan>, plan: Plan, date: NaiveDate) {
+    // Validate date range
+    if plan.valid_from > date {
+        return;
+    }
+    if let Some(valid_until) = plan.valid_until {
+        if valid_until < date {
+            return;
+        }
+    }
+
+    // Keep the most recent plan for each source
+    if let Some(existing) = plans.get(&plan.source) {
+        if plan.valid_from > existing.valid_from {
+            plans.insert(plan.source.clone(), plan);
+        }
+    } else {
+        plans.insert(plan.source.clone(), plan);
+    }
+}
+
+/// Pick the most recent file dated on or before `date` for each source
+pub(crate) fn latest_plan_files(files: Vec<PathBuf>, date: NaiveDate) -> Result<Vec<PathBuf>> {
+    // Map of source -> (most recent date, file path)
+    let mut candidates: HashMap<String, (NaiveDate, PathBuf)> = HashMap::new();
+
+    for file_path in files {
+        let filename = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .context("Invalid filename")?;
+
+        if let Some((source, file_date)) = layout::parse_plan_file_name(filename) {
+            // Skip files with dates after our target date
+            if file_date > date {
+                continue;
+            }
+
+            // Keep the most recent file for this source
+            if let Some((existing_date, _)) = candidates.get(&source) {
+                if file_date > *existing_date {
+                    candidates.insert(source, (file_date, file_path));
+                }
+            } else {
+                candidates.insert(source, (file_date, file_path));
+            }
+        }
+    }
+
+    Ok(candidates.into_values().map(|(_, path)| path).collect())
+}
+
+/// Distinct intents across a set of plans
+pub(crate) fn intents_of(plans: &HashMap<String, Plan>) -> Vec<Intent> {
+    let mut intents = std::collections::HashSet::new();
+
+    for plan in plans.values() {
+        for intent in &plan.intents {
+            intents.insert(intent.clone());
+        }
+    }
+
+    intents.into_iter().collect()
+}
+
+/// Roles across a set of plans, source-prefixed, plus any from intents
+pub(crate) fn roles_of(plans: &HashMap<String, Plan>) -> Vec<String> {
+    let mut roles = Vec::new();
+
+    for plan in plans.values() {
+        // Roles from plan (prefixed with source)
+        for role in &plan.roles {
+            roles.push(format!("{}:{}", plan.source, role));
+        }
+
+        // Roles from intents
+        for intent in &plan.intents {
+            if let Some(role) = &intent.role {
+                roles.push(role.clone());
+            }
+        }
+    }
+
+    // Deduplicate and sort
+    roles.sort();
+    roles.dedup();
+
+    roles
+}
+
+/// Objectives across a set of plans, source-prefixed, plus any from intents
+pub(crate) fn objectives_of(plans: &HashMap<String, Plan>) -> Vec<String> {
+    let mut objectives = Vec::new();
+
+    for plan in plans.values() {
+        // Objectives from plan (prefixed with source)
+        for objective in &plan.objectives {
+            objectives.push(format!("{}:{}", plan.source, objective));
+        }
+
+        // Objectives from intents
+        for intent in &plan.intents {
+            if let Some(objective) = &intent.objective {
+                objectives.push(objective.clone());
+            }
+        }
+    }
+
+    // Deduplicate and sort
+    objectives.sort();
+    objectives.dedup();
+
+    objectives
+}
+
+/// Actions across a set of plans, source-prefixed, plus any from intents
+pub(crate) fn actions_of(plans: &HashMap<String, Plan>) -> Vec<String> {
+    let mut actions = Vec::new();
+
+    for plan in plans.values() {
+        // Actions from plan (prefixed with source)
+        for action in &plan.actions {
+            actions.push(format!("{}:{}", plan.source, action));
+        }
+
+        // Actions from intents
+        for intent in &plan.intents {
+            if let Some(action) = &intent.action {
+                actions.push(action.clone());
+            }
+        }
+    }
+
+    // Deduplicate and sort
+    actions.sort();
+    actions.dedup();
+
+    actions
+}
+
+/// Subjects across a set of plans, source-prefixed, plus any from intents
+pub(crate) fn subjects_of(plans: &HashMap<String, Plan>) -> Vec<String> {
+    let mut subjects = Vec::new();
+
+    for plan in plans.values() {
+        // Subjects from plan (prefixed with source)
+        for subject in &plan.subjects {
+            subjects.push(format!("{}:{}", plan.source, subject));
+        }
+
+        // Subjects from intents
+        for intent in &plan.intents {
+            if let Some(subject) = &intent.subject {
+                subjects.push(subject.clone());
+            }
+        }
+    }
+
+    // Deduplicate and sort
+    subjects.sort();
+    subjects.dedup();
+
+    subjects
+}
+
+/// Trackers across a set of plans, keyed by source-prefixed ID
+pub(crate) fn trackers_of(plans: &HashMap<String, Plan>) -> HashMap<String, String> {
+    let mut trackers = HashMap::new();
+
+    for plan in plans.values() {
+        for (tracker_key, tracker_value) in &plan.trackers {
+            let prefixed_key = format!("{}:{}", plan.source, tracker_key);
+            trackers.insert(prefixed_key, tracker_value.clone());
+        }
+    }
+
+    trackers
+}
+
+/// Billable marks across a set of plans, keyed by source-prefixed ID
+pub(crate) fn billable_of(plans: &HashMap<String, Plan>) -> HashMap<String, bool> {
+    plans
+        .values()
+        .flat_map(|plan| {
+            plan.billable
+                .iter()
+                .map(|(id, billable)| (format!("{}:{}", plan.source, id), *billable))
+        })
+        .collect()
+}
+
+/// A local plan with nothing in it, valid from `date`
+pub(crate) fn empty_local_plan(date: NaiveDate) -> Plan {
+    Plan::new(
+        LOCAL_PLAN_SOURCE.to_string(),
+        date,
+        None,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        HashMap::new(),
+        vec![],
+    )
+}
+
+/// How writing `plan` is described in storage history
+pub(crate) fn update_message(plan: &Plan) -> String {
+    format!("update plan {} {}", plan.source, plan.valid_from)
+}
+
+/// `plan` without `intent`, failing if it isn't there
+pub(crate) fn without_intent(plan: &Plan, intent: &Intent) -> Result<Plan> {
+    if !plan.has_intent(intent) {
+        anyhow::bail!("Intent {:?} is not in the local plan", intent.alias);
+    }
+    Ok(plan.remove_intent(intent))
+}
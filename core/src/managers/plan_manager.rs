@@ -1,33 +1,46 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::NaiveDate;
-use regex::Regex;
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::{Arc, LazyLock};
+use std::sync::Arc;
 
+use crate::async_storage::block_on;
+use crate::managers::{AsyncPlanManager, LogManager};
 use crate::models::intent::Intent;
-use crate::models::plan::Plan;
-use crate::storage::Storage;
+use crate::models::intent_resolver::IntentResolver;
+use crate::models::intent_search::IntentMatch;
+use crate::models::plan::{Plan, Vocabulary};
+use crate::models::plan_lint::PlanFileReport;
+#[cfg(feature = "sqlite")]
+use crate::sqlite_index::SqliteIndex;
+use crate::storage::{Storage, VersionToken};
 
-// Regex for parsing plan filenames - validated at compile time
-static PLAN_FILENAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^(?P<source>.+?)\.(?P<datestr>\d{8})\.toml$")
-        .expect("PLAN_FILENAME_REGEX pattern is valid")
-});
-
-/// Manages Plan loading, caching, and querying
-///
 /// Manages plan loading and querying
+///
+/// Drives an `AsyncPlanManager` over synchronous storage, which is where
+/// the operations are implemented.
 #[derive(Clone)]
 pub struct PlanManager {
-    storage: Arc<dyn Storage>,
+    inner: AsyncPlanManager<Arc<dyn Storage>>,
 }
 
 impl PlanManager {
-    const LOCAL_PLAN_SOURCE: &'static str = "local";
-
     pub fn new(storage: Arc<dyn Storage>) -> Self {
-        Self { storage }
+        Self {
+            inner: AsyncPlanManager::new(storage),
+        }
+    }
+
+    /// Drive an existing `AsyncPlanManager` over synchronous storage
+    pub(crate) fn from_async(inner: AsyncPlanManager<Arc<dyn Storage>>) -> Self {
+        Self { inner }
+    }
+
+    /// Keep `index` up to date with every plan written or deleted
+    #[cfg(feature = "sqlite")]
+    pub fn with_index(self, index: Arc<SqliteIndex>) -> Self {
+        Self {
+            inner: self.inner.with_index(index),
+        }
     }
 
     /// Get all plans valid for a given date
@@ -35,110 +48,56 @@ impl PlanManager {
     /// A plan is valid if:
     /// - valid_from <= target_date
     /// - and (valid_until >= target_date or valid_until is None)
+    ///
+    /// Plan files that can't be read or parsed are skipped, so one bad file
+    /// doesn't hide every other plan; `get_plans_with_diagnostics` reports
+    /// them.
     pub fn get_plans(&self, date: NaiveDate) -> Result<HashMap<String, Plan>> {
-        self.load_plans_for_date(date)
-    }
-
-    /// Load plans from storage for a given date
-    fn load_plans_for_date(&self, date: NaiveDate) -> Result<HashMap<String, Plan>> {
-        let plan_dir = self.storage.plan_dir();
-        let plan_files = self.find_plan_files_for_date(&plan_dir, date)?;
-
-        let mut plans: HashMap<String, Plan> = HashMap::new();
-
-        for file_path in plan_files {
-            let content = self
-                .storage
-                .read_string(&file_path)
-                .with_context(|| format!("Failed to read plan file: {}", file_path.display()))?;
-
-            let plan: Plan = toml::from_str(&content)
-                .with_context(|| format!("Failed to parse plan file: {}", file_path.display()))?;
-
-            // Validate date range
-            if plan.valid_from > date {
-                continue;
-            }
-            if let Some(valid_until) = plan.valid_until {
-                if valid_until < date {
-                    continue;
-                }
-            }
-
-            // Keep the most recent plan for each source
-            if let Some(existing) = plans.get(&plan.source) {
-                if plan.valid_from > existing.valid_from {
-                    plans.insert(plan.source.clone(), plan);
-                }
-            } else {
-                plans.insert(plan.source.clone(), plan);
-            }
-        }
-
-        Ok(plans)
+        block_on(self.inner.get_plans(date))
     }
 
-    /// Find plan files relevant for a given date
+    /// Get all plans valid for a given date, as `get_plans` does
     ///
-    /// Plan files follow the pattern: `<source>.<YYYYMMDD>.toml`
-    /// For each source, we find the most recent file where file_date <= target_date
-    fn find_plan_files_for_date(
+    /// Returns the plans that loaded, plus a report for each file skipped
+    /// because it failed to load.
+    pub fn get_plans_with_diagnostics(
         &self,
-        plan_dir: &PathBuf,
         date: NaiveDate,
-    ) -> Result<Vec<PathBuf>> {
-        let files = self
-            .storage
-            .list_files(plan_dir, "*.toml")
-            .context("Failed to list plan files")?;
-
-        // Map of source -> (most recent date, file path)
-        let mut candidates: HashMap<String, (NaiveDate, PathBuf)> = HashMap::new();
-
-        for file_path in files {
-            let filename = file_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .context("Invalid filename")?;
-
-            if let Some(captures) = PLAN_FILENAME_REGEX.captures(filename) {
-                // These unwraps are safe because the regex guarantees named groups exist
-                let source = captures.name("source").unwrap().as_str().to_string();
-                let datestr = captures.name("datestr").unwrap().as_str();
-
-                if let Ok(file_date) = NaiveDate::parse_from_str(datestr, "%Y%m%d") {
-                    // Skip files with dates after our target date
-                    if file_date > date {
-                        continue;
-                    }
-
-                    // Keep the most recent file for this source
-                    if let Some((existing_date, _)) = candidates.get(&source) {
-                        if file_date > *existing_date {
-                            candidates.insert(source, (file_date, file_path));
-                        }
-                    } else {
-                        candidates.insert(source, (file_date, file_path));
-                    }
-                }
-            }
-        }
+    ) -> Result<(HashMap<String, Plan>, Vec<PlanFileReport>)> {
+        block_on(self.inner.get_plans_with_diagnostics(date))
+    }
 
-        Ok(candidates.into_values().map(|(_, path)| path).collect())
+    /// Lint every plan file in storage
+    ///
+    /// Returns one report per file that has at least one diagnostic.
+    pub fn lint_plans(&self) -> Result<Vec<PlanFileReport>> {
+        block_on(self.inner.lint_plans())
     }
 
     /// Get all intents from plans valid for a given date
     pub fn get_intents(&self, date: NaiveDate) -> Result<Vec<Intent>> {
-        let plans = self.get_plans(date)?;
-        let mut intents = std::collections::HashSet::new();
+        block_on(self.inner.get_intents(date))
+    }
 
-        for plan in plans.values() {
-            for intent in &plan.intents {
-                intents.insert(intent.clone());
-            }
-        }
+    /// Search intents from plans valid for a given date
+    ///
+    /// Ranks intents against the query across alias, role, objective, action,
+    /// subject and tracker names. Recently used intents are boosted.
+    pub fn search_intents(
+        &self,
+        query: &str,
+        date: NaiveDate,
+        log_manager: &LogManager,
+    ) -> Result<Vec<IntentMatch>> {
+        block_on(
+            self.inner
+                .search_intents(query, date, log_manager.as_async()),
+        )
+    }
 
-        Ok(intents.into_iter().collect())
+    /// Build a resolver mapping logged intents to the intents valid on a given date
+    pub fn intent_resolver(&self, date: NaiveDate) -> Result<IntentResolver> {
+        block_on(self.inner.intent_resolver(date))
     }
 
     /// Get all roles from plans valid for a given date
@@ -146,106 +105,22 @@ impl PlanManager {
     /// Returns roles prefixed with their source (e.g., "element:engineer")
     /// plus any roles from intents
     pub fn get_roles(&self, date: NaiveDate) -> Result<Vec<String>> {
-        let plans = self.get_plans(date)?;
-        let mut roles = Vec::new();
-
-        for plan in plans.values() {
-            // Roles from plan (prefixed with source)
-            for role in &plan.roles {
-                roles.push(format!("{}:{}", plan.source, role));
-            }
-
-            // Roles from intents
-            for intent in &plan.intents {
-                if let Some(role) = &intent.role {
-                    roles.push(role.clone());
-                }
-            }
-        }
-
-        // Deduplicate and sort
-        roles.sort();
-        roles.dedup();
-
-        Ok(roles)
+        block_on(self.inner.get_roles(date))
     }
 
     /// Get all objectives from plans valid for a given date
     pub fn get_objectives(&self, date: NaiveDate) -> Result<Vec<String>> {
-        let plans = self.get_plans(date)?;
-        let mut objectives = Vec::new();
-
-        for plan in plans.values() {
-            // Objectives from plan (prefixed with source)
-            for objective in &plan.objectives {
-                objectives.push(format!("{}:{}", plan.source, objective));
-            }
-
-            // Objectives from intents
-            for intent in &plan.intents {
-                if let Some(objective) = &intent.objective {
-                    objectives.push(objective.clone());
-                }
-            }
-        }
-
-        // Deduplicate and sort
-        objectives.sort();
-        objectives.dedup();
-
-        Ok(objectives)
+        block_on(self.inner.get_objectives(date))
     }
 
     /// Get all actions from plans valid for a given date
     pub fn get_actions(&self, date: NaiveDate) -> Result<Vec<String>> {
-        let plans = self.get_plans(date)?;
-        let mut actions = Vec::new();
-
-        for plan in plans.values() {
-            // Actions from plan (prefixed with source)
-            for action in &plan.actions {
-                actions.push(format!("{}:{}", plan.source, action));
-            }
-
-            // Actions from intents
-            for intent in &plan.intents {
-                if let Some(action) = &intent.action {
-                    actions.push(action.clone());
-                }
-            }
-        }
-
-        // Deduplicate and sort
-        actions.sort();
-        actions.dedup();
-
-        Ok(actions)
+        block_on(self.inner.get_actions(date))
     }
 
     /// Get all subjects from plans valid for a given date
     pub fn get_subjects(&self, date: NaiveDate) -> Result<Vec<String>> {
-        let plans = self.get_plans(date)?;
-        let mut subjects = Vec::new();
-
-        for plan in plans.values() {
-            // Subjects from plan (prefixed with source)
-            for subject in &plan.subjects {
-                subjects.push(format!("{}:{}", plan.source, subject));
-            }
-
-            // Subjects from intents
-            for intent in &plan.intents {
-                if let Some(subject) = &intent.subject {
-                    subjects.push(subject.clone());
-                }
-            }
-        }
-
-        // Deduplicate and sort
-        subjects.sort();
-        subjects.dedup();
-
-        Ok(subjects)
+        block_on(self.inner.get_subjects(date))
     }
 
     /// Get all trackers from plans valid for a given date
@@ -253,17 +128,12 @@ impl PlanManager {
     /// Returns a map of tracker IDs (prefixed with source) to human-readable names
     /// Example: "element:12345" -> "Fix critical bug"
     pub fn get_trackers(&self, date: NaiveDate) -> Result<HashMap<String, String>> {
-        let plans = self.get_plans(date)?;
-        let mut trackers = HashMap::new();
-
-        for plan in plans.values() {
-            for (tracker_key, tracker_value) in &plan.trackers {
-                let prefixed_key = format!("{}:{}", plan.source, tracker_key);
-                trackers.insert(prefixed_key, tracker_value.clone());
-            }
-        }
+        block_on(self.inner.get_trackers(date))
+    }
 
-        Ok(trackers)
+    /// Whether each tracker is billable, per the plans valid on a date
+    pub fn get_billable(&self, date: NaiveDate) -> Result<HashMap<String, bool>> {
+        block_on(self.inner.get_billable(date))
     }
 
     /// Get the plan containing a specific tracker ID
@@ -274,126 +144,133 @@ impl PlanManager {
         tracker_id: &str,
         date: NaiveDate,
     ) -> Result<Option<Plan>> {
-        let plans = self.get_plans(date)?;
-
-        for plan in plans.values() {
-            if plan.trackers.contains_key(tracker_id) {
-                return Ok(Some(plan.clone()));
-            }
-        }
-
-        Ok(None)
+        block_on(self.inner.get_plan_by_tracker_id(tracker_id, date))
     }
 
     /// Get the local plan for a given date
     ///
     /// Returns None if the local plan doesn't exist
     pub fn get_local_plan(&self, date: NaiveDate) -> Result<Option<Plan>> {
-        let plans = self.get_plans(date)?;
-        Ok(plans.get(Self::LOCAL_PLAN_SOURCE).cloned())
+        block_on(self.inner.get_local_plan(date))
     }
 
     /// Get the local plan for a given date, creating an empty one if it doesn't exist
     ///
     /// This is a convenience method for callers who always want a plan to work with
     pub fn get_local_plan_or_create(&self, date: NaiveDate) -> Result<Plan> {
-        if let Some(plan) = self.get_local_plan(date)? {
-            Ok(plan)
-        } else {
-            Ok(Plan::new(
-                Self::LOCAL_PLAN_SOURCE.to_string(),
-                date,
-                None,
-                vec![],
-                vec![],
-                vec![],
-                vec![],
-                HashMap::new(),
-                vec![],
-            ))
-        }
+        block_on(self.inner.get_local_plan_or_create(date))
+    }
+
+    /// Get the plan stored for a specific source and date
+    ///
+    /// Returns None if there's no such plan file, regardless of validity dates.
+    pub fn get_plan(&self, source: &str, date: NaiveDate) -> Result<Option<Plan>> {
+        block_on(self.inner.get_plan(source, date))
     }
 
     /// Write a plan to storage
     pub fn write_plan(&self, plan: &Plan) -> Result<()> {
-        let plan_dir = self.storage.plan_dir();
-        self.storage.create_dir_all(&plan_dir)?;
+        block_on(self.inner.write_plan(plan))
+    }
 
-        let filename = format!("{}.{}.toml", plan.source, plan.valid_from.format("%Y%m%d"));
-        let file_path = plan_dir.join(filename);
+    /// Version token of the plan file for a source and date
+    ///
+    /// Returns None if the file doesn't exist.
+    pub fn plan_version(&self, source: &str, date: NaiveDate) -> Result<Option<VersionToken>> {
+        block_on(self.inner.plan_version(source, date))
+    }
+
+    /// Write a plan only if its file is still at `expected` (None: must not exist)
+    ///
+    /// Fails with a `VersionConflict` otherwise. Returns the new version.
+    pub fn write_plan_if(
+        &self,
+        plan: &Plan,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        block_on(self.inner.write_plan_if(plan, expected))
+    }
+
+    /// Add an intent to the local plan version dated `date`
+    pub fn add_intent(&self, date: NaiveDate, intent: Intent) -> Result<Plan> {
+        block_on(self.inner.add_intent(date, intent))
+    }
+
+    /// Remove an intent from the local plan version dated `date`
+    pub fn remove_intent(&self, date: NaiveDate, intent: &Intent) -> Result<Plan> {
+        block_on(self.inner.remove_intent(date, intent))
+    }
+
+    /// Give an intent a new alias in the local plan version dated `date`
+    pub fn rename_intent(&self, date: NaiveDate, intent: &Intent, alias: &str) -> Result<Plan> {
+        block_on(self.inner.rename_intent(date, intent, alias))
+    }
+
+    /// Retire an intent from the local plan from `date` onward
+    ///
+    /// Unlike `remove_intent`, this also removes the intent from any local
+    /// plan versions dated after `date`. Versions before `date` keep it, so
+    /// existing logs still resolve.
+    pub fn retire_intent(&self, date: NaiveDate, intent: &Intent) -> Result<()> {
+        block_on(self.inner.retire_intent(date, intent))
+    }
 
-        let toml_content =
-            toml::to_string_pretty(plan).context("Failed to serialize plan to TOML")?;
+    /// Add or rename a tracker in the local plan version dated `date`
+    pub fn add_tracker(&self, date: NaiveDate, id: &str, name: &str) -> Result<Plan> {
+        block_on(self.inner.add_tracker(date, id, name))
+    }
 
-        self.storage
-            .write_string(&file_path, &toml_content)
-            .context("Failed to write plan file")?;
+    /// Remove a tracker from the local plan version dated `date`
+    ///
+    /// Fails if the tracker doesn't exist or an intent still references it.
+    pub fn remove_tracker(&self, date: NaiveDate, id: &str) -> Result<Plan> {
+        block_on(self.inner.remove_tracker(date, id))
+    }
+
+    /// Add a role, objective, action or subject to the local plan version dated `date`
+    pub fn add_vocabulary(&self, date: NaiveDate, kind: Vocabulary, value: &str) -> Result<Plan> {
+        block_on(self.inner.add_vocabulary(date, kind, value))
+    }
+
+    /// Rename a role, objective, action or subject in the local plan version dated `date`
+    ///
+    /// Intents using the old name are updated too, with their ids pinned so
+    /// existing logs still resolve to them.
+    pub fn rename_vocabulary(
+        &self,
+        date: NaiveDate,
+        kind: Vocabulary,
+        old: &str,
+        new: &str,
+    ) -> Result<Plan> {
+        block_on(self.inner.rename_vocabulary(date, kind, old, new))
+    }
 
-        Ok(())
+    /// Remove a role, objective, action or subject from the local plan version dated `date`
+    pub fn remove_vocabulary(
+        &self,
+        date: NaiveDate,
+        kind: Vocabulary,
+        value: &str,
+    ) -> Result<Plan> {
+        block_on(self.inner.remove_vocabulary(date, kind, value))
     }
 
     /// List all plan files
     ///
     /// Returns a vector of (source, valid_from_date) tuples
     pub fn list_plans(&self) -> Result<Vec<(String, NaiveDate)>> {
-        let plan_dir = self.storage.plan_dir();
-        let files = self
-            .storage
-            .list_files(&plan_dir, "*.toml")
-            .context("Failed to list plan files")?;
-
-        let mut plan_info = Vec::new();
-
-        for file_path in files {
-            let filename = file_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .with_context(|| format!("Invalid filename in plan directory: {:?}", file_path))?;
-
-            if let Some(captures) = PLAN_FILENAME_REGEX.captures(filename) {
-                let source = captures.name("source").unwrap().as_str().to_string();
-                let datestr = captures.name("datestr").unwrap().as_str();
-
-                if let Ok(date) = NaiveDate::parse_from_str(datestr, "%Y%m%d") {
-                    plan_info.push((source, date));
-                }
-            }
-        }
-
-        plan_info.sort();
-        Ok(plan_info)
+        block_on(self.inner.list_plans())
     }
 
     /// Check if a plan exists for a specific source and date
     pub fn plan_exists(&self, source: &str, date: NaiveDate) -> bool {
-        let plan_dir = self.storage.plan_dir();
-        let filename = format!("{}.{}.toml", source, date.format("%Y%m%d"));
-        let file_path = plan_dir.join(filename);
-        self.storage.exists(&file_path)
+        block_on(self.inner.plan_exists(source, date))
     }
 
     /// Delete a plan
     pub fn delete_plan(&self, source: &str, date: NaiveDate) -> Result<()> {
-        let plan_dir = self.storage.plan_dir();
-        let filename = format!("{}.{}.toml", source, date.format("%Y%m%d"));
-        let file_path = plan_dir.join(filename);
-
-        if !self.storage.exists(&file_path) {
-            anyhow::bail!(
-                "Plan for source '{}' and date {} does not exist",
-                source,
-                date
-            );
-        }
-
-        self.storage.delete(&file_path).with_context(|| {
-            format!(
-                "Failed to delete plan for source '{}' and date {}",
-                source, date
-            )
-        })?;
-
-        Ok(())
+        block_on(self.inner.delete_plan(source, date))
     }
 
     /// Get plan remote plugin instances
@@ -420,7 +297,8 @@ impl PlanManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::mock_storage::MockStorage;
+    use crate::memory_storage::MemoryStorage;
+    use std::path::PathBuf;
 
     fn sample_plan_toml(source: &str, date: &str) -> String {
         format!(
@@ -446,7 +324,7 @@ objective = "{}:development"
 
     #[test]
     fn test_load_single_plan() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         storage.add_file(
             PathBuf::from("/faff/plans/local.20250101.toml"),
             sample_plan_toml("local", "2025-01-01"),
@@ -462,7 +340,7 @@ objective = "{}:development"
 
     #[test]
     fn test_get_trackers() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         storage.add_file(
             PathBuf::from("/faff/plans/local.20250101.toml"),
             sample_plan_toml("local", "2025-01-01"),
@@ -475,9 +353,297 @@ objective = "{}:development"
         assert_eq!(trackers.get("local:123"), Some(&"Task 123".to_string()));
     }
 
+    fn work_intent() -> Intent {
+        Intent::new(
+            Some("Work".to_string()),
+            None,
+            None,
+            None,
+            None,
+            vec!["local:T-1".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_edits_create_dated_versions() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = PlanManager::new(storage);
+        let jan = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let feb = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+
+        manager.add_tracker(jan, "T-1", "Task one").unwrap();
+        manager.add_intent(jan, work_intent()).unwrap();
+        manager
+            .add_vocabulary(jan, Vocabulary::Role, "engineer")
+            .unwrap();
+
+        let renamed = manager
+            .rename_intent(feb, &work_intent(), "Deep work")
+            .unwrap();
+        assert_eq!(renamed.valid_from, feb);
+
+        assert_eq!(
+            manager.list_plans().unwrap(),
+            vec![("local".to_string(), jan), ("local".to_string(), feb)]
+        );
+
+        // History before the edit is unchanged
+        let january = manager.get_local_plan(jan).unwrap().unwrap();
+        assert_eq!(january.intents[0].alias.as_deref(), Some("Work"));
+        assert_eq!(january.roles, vec!["engineer"]);
+
+        let february = manager.get_local_plan(feb).unwrap().unwrap();
+        assert_eq!(february.intents[0].alias.as_deref(), Some("Deep work"));
+        assert_eq!(february.roles, vec!["engineer"]);
+    }
+
+    #[test]
+    fn test_edits_leave_later_versions_alone() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = PlanManager::new(storage);
+        let jan = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let feb = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+        let mar = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+        manager.add_tracker(jan, "T-1", "Task one").unwrap();
+        manager.add_intent(jan, work_intent()).unwrap();
+        manager.add_tracker(mar, "T-2", "Task two").unwrap();
+
+        manager
+            .rename_intent(feb, &work_intent(), "Deep work")
+            .unwrap();
+        manager
+            .add_vocabulary(feb, Vocabulary::Role, "engineer")
+            .unwrap();
+
+        let february = manager.get_local_plan(feb).unwrap().unwrap();
+        assert_eq!(february.intents[0].alias.as_deref(), Some("Deep work"));
+        assert_eq!(february.roles, vec!["engineer"]);
+
+        let march = manager.get_local_plan(mar).unwrap().unwrap();
+        assert_eq!(march.intents[0].alias.as_deref(), Some("Work"));
+        assert!(march.roles.is_empty());
+        assert_eq!(march.trackers.len(), 2);
+    }
+
+    #[test]
+    fn test_remove_edits_validate_targets() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = PlanManager::new(storage);
+        let jan = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        manager.add_tracker(jan, "T-1", "Task one").unwrap();
+        manager.add_intent(jan, work_intent()).unwrap();
+
+        assert!(manager.remove_tracker(jan, "T-1").is_err());
+        assert!(manager.remove_tracker(jan, "T-2").is_err());
+        assert!(manager
+            .remove_vocabulary(jan, Vocabulary::Subject, "docs")
+            .is_err());
+
+        manager.remove_intent(jan, &work_intent()).unwrap();
+        assert!(manager.remove_intent(jan, &work_intent()).is_err());
+
+        let plan = manager.remove_tracker(jan, "T-1").unwrap();
+        assert!(plan.trackers.is_empty());
+    }
+
+    #[test]
+    fn test_retire_intent_updates_later_versions() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = PlanManager::new(storage);
+        let jan = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let feb = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+        let mar = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+        manager.add_tracker(jan, "T-1", "Task one").unwrap();
+        manager.add_intent(jan, work_intent()).unwrap();
+        manager.add_tracker(mar, "T-2", "Task two").unwrap();
+
+        manager.retire_intent(feb, &work_intent()).unwrap();
+
+        assert!(manager.get_local_plan(jan).unwrap().unwrap().intents.len() == 1);
+        assert!(manager
+            .get_local_plan(feb)
+            .unwrap()
+            .unwrap()
+            .intents
+            .is_empty());
+        let march = manager.get_local_plan(mar).unwrap().unwrap();
+        assert!(march.intents.is_empty());
+        assert_eq!(march.trackers.len(), 2);
+    }
+
+    #[test]
+    fn test_loading_skips_bad_files() {
+        let storage = Arc::new(MemoryStorage::new());
+        storage.add_file(
+            PathBuf::from("/faff/plans/local.20250101.toml"),
+            sample_plan_toml("local", "2025-01-01"),
+        );
+        storage.add_file(
+            PathBuf::from("/faff/plans/broken.20250101.toml"),
+            "source = \"broken\"\nvalid_from = [not toml".to_string(),
+        );
+
+        let manager = PlanManager::new(storage);
+        let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+
+        assert_eq!(
+            manager.get_plans(date).unwrap().keys().collect::<Vec<_>>(),
+            vec!["local"]
+        );
+        assert_eq!(manager.get_intents(date).unwrap().len(), 1);
+        assert_eq!(manager.get_trackers(date).unwrap().len(), 1);
+
+        let (plans, skipped) = manager.get_plans_with_diagnostics(date).unwrap();
+        assert_eq!(plans.len(), 1);
+        assert!(plans.contains_key("local"));
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(
+            skipped[0].path,
+            PathBuf::from("/faff/plans/broken.20250101.toml")
+        );
+        assert_eq!(skipped[0].diagnostics[0].code, "parse-error");
+    }
+
+    #[test]
+    fn test_edits_refuse_to_replace_a_bad_local_plan() {
+        let storage = Arc::new(MemoryStorage::new());
+        let path = PathBuf::from("/faff/plans/local.20250101.toml");
+        let broken = "source = \"local\"\nvalid_from = [not toml".to_string();
+        storage.add_file(path.clone(), broken.clone());
+
+        let manager = PlanManager::new(storage.clone());
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert!(manager.add_tracker(date, "PROJ-1", "One").is_err());
+        assert_eq!(storage.files()[&path], broken.into_bytes());
+    }
+
+    #[test]
+    fn test_lint_plans() {
+        let storage = Arc::new(MemoryStorage::new());
+        storage.add_file(
+            PathBuf::from("/faff/plans/local.20250101.toml"),
+            sample_plan_toml("local", "2025-01-01"),
+        );
+        storage.add_file(
+            PathBuf::from("/faff/plans/broken.20250101.toml"),
+            "not = [valid".to_string(),
+        );
+        storage.add_file(
+            PathBuf::from("/faff/plans/work.20250201.toml"),
+            r#"
+source = "work"
+valid_from = "2025-02-01"
+valid_until = "2025-01-01"
+
+[[intents]]
+alias = "Dup"
+trackers = ["missing"]
+
+[[intents]]
+alias = "Dup"
+"#
+            .to_string(),
+        );
+        storage.add_file(
+            PathBuf::from("/faff/plans/renamed.20250101.toml"),
+            sample_plan_toml("other", "2025-01-01"),
+        );
+
+        let manager = PlanManager::new(storage);
+        let reports = manager.lint_plans().unwrap();
+
+        let summary: Vec<(String, Vec<String>)> = reports
+            .iter()
+            .map(|r| {
+                (
+                    r.path.file_name().unwrap().to_string_lossy().to_string(),
+                    r.diagnostics.iter().map(|d| d.code.clone()).collect(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "broken.20250101.toml".to_string(),
+                    vec!["parse-error".to_string()]
+                ),
+                (
+                    "renamed.20250101.toml".to_string(),
+                    vec!["filename-mismatch".to_string()]
+                ),
+                (
+                    "work.20250201.toml".to_string(),
+                    vec![
+                        "invalid-validity-range".to_string(),
+                        "unknown-tracker".to_string(),
+                        "duplicate-alias".to_string(),
+                    ]
+                ),
+            ]
+        );
+        assert!(reports[2].has_errors());
+    }
+
+    #[test]
+    fn test_search_intents_boosts_recently_used() {
+        use crate::models::{Log, Session};
+        use chrono::TimeZone;
+
+        let storage = Arc::new(MemoryStorage::new());
+        storage.add_file(
+            PathBuf::from("/faff/plans/local.20250101.toml"),
+            r#"
+source = "local"
+valid_from = "2025-01-01"
+
+[[intents]]
+alias = "Work on feature"
+action = "coding"
+
+[[intents]]
+alias = "Work on docs"
+action = "writing"
+"#
+            .to_string(),
+        );
+
+        let manager = PlanManager::new(storage.clone());
+        let log_manager = LogManager::new(storage, chrono_tz::UTC);
+        let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+
+        let matches = manager.search_intents("work", date, &log_manager).unwrap();
+        assert_eq!(matches[0].intent.alias.as_deref(), Some("Work on docs"));
+
+        let feature = matches[1].intent.clone();
+        let used_on = NaiveDate::from_ymd_opt(2025, 1, 14).unwrap();
+        let session = Session::new(
+            feature,
+            chrono_tz::UTC
+                .with_ymd_and_hms(2025, 1, 14, 9, 0, 0)
+                .unwrap(),
+            None,
+            None,
+        );
+        log_manager
+            .write_log(
+                &Log::new(used_on, chrono_tz::UTC, vec![session]),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+        let matches = manager.search_intents("work", date, &log_manager).unwrap();
+        assert_eq!(matches[0].intent.alias.as_deref(), Some("Work on feature"));
+        assert_eq!(matches[0].last_used, Some(used_on));
+    }
+
     #[test]
     fn test_cache_works() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         storage.add_file(
             PathBuf::from("/faff/plans/local.20250101.toml"),
             sample_plan_toml("local", "2025-01-01"),
@@ -496,7 +662,7 @@ objective = "{}:development"
 
     #[test]
     fn test_get_local_plan_returns_none_when_missing() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = PlanManager::new(storage);
         let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
 
@@ -506,7 +672,7 @@ objective = "{}:development"
 
     #[test]
     fn test_get_local_plan_or_create() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = PlanManager::new(storage);
         let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
 
@@ -518,7 +684,7 @@ objective = "{}:development"
 
     #[test]
     fn test_get_plan_by_tracker_id_returns_none() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         storage.add_file(
             PathBuf::from("/faff/plans/local.20250101.toml"),
             sample_plan_toml("local", "2025-01-01"),
@@ -533,7 +699,7 @@ objective = "{}:development"
 
     #[test]
     fn test_list_plans() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         storage.add_file(
             PathBuf::from("/faff/plans/local.20250101.toml"),
             sample_plan_toml("local", "2025-01-01"),
@@ -555,7 +721,7 @@ objective = "{}:development"
 
     #[test]
     fn test_plan_exists() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         storage.add_file(
             PathBuf::from("/faff/plans/local.20250101.toml"),
             sample_plan_toml("local", "2025-01-01"),
@@ -570,7 +736,7 @@ objective = "{}:development"
 
     #[test]
     fn test_delete_plan() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         storage.add_file(
             PathBuf::from("/faff/plans/local.20250101.toml"),
             sample_plan_toml("local", "2025-01-01"),
@@ -588,7 +754,7 @@ objective = "{}:development"
 
     #[test]
     fn test_delete_nonexistent_plan() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = PlanManager::new(storage);
         let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
 
@@ -596,4 +762,30 @@ objective = "{}:development"
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("does not exist"));
     }
+
+    #[test]
+    fn test_edit_reports_plan_written_since_version_was_read() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = PlanManager::new(storage);
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        let expected = manager.plan_version("local", date).unwrap();
+        assert!(expected.is_none());
+
+        // Another process creates the plan first
+        let theirs = manager.add_tracker(date, "PROJ-1", "Theirs").unwrap();
+
+        let err = manager
+            .write_plan_if(&theirs, expected.as_ref())
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<crate::storage::VersionConflict>()
+            .is_some());
+
+        let current = manager.plan_version("local", date).unwrap();
+        manager
+            .write_plan_if(&theirs.add_tracker("PROJ-2", "Ours"), current.as_ref())
+            .unwrap();
+        assert_eq!(manager.get_trackers(date).unwrap().len(), 2);
+    }
 }
@@ -5,18 +5,22 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use crate::models::allocation::Allocation;
 use crate::models::log::Log;
 use crate::models::plan::Plan;
 use crate::models::timesheet::Timesheet;
 use crate::models::Config;
 use crate::storage::Storage;
 
+/// Loaded plugin classes, keyed by module name, with the file they came from
+type PluginCache = Arc<Mutex<Option<HashMap<String, (PathBuf, Py<PyAny>)>>>>;
+
 /// Manages loading and executing Python plugins
 #[derive(Clone)]
 pub struct PluginManager {
     storage: Arc<dyn Storage>,
     config: Config,
-    pub plugins_cache: Arc<Mutex<Option<HashMap<String, (PathBuf, Py<PyAny>)>>>>,
+    pub plugins_cache: PluginCache,
 }
 
 impl PluginManager {
@@ -354,26 +358,36 @@ impl PluginManager {
     /// This searches through all configured audience plugins and returns the one
     /// matching the given ID, or None if not found.
     pub fn get_audience_by_id(&mut self, audience_id: &str) -> Result<Option<Py<PyAny>>> {
+        self.find_audience("id", audience_id)
+    }
+
+    /// Get the audience plugin for the `[[timesheet_audience]]` named `name`
+    pub fn get_audience_by_name(&mut self, name: &str) -> Result<Option<Py<PyAny>>> {
+        self.find_audience("name", name)
+    }
+
+    /// The audience plugin whose `attr` attribute is `value`
+    fn find_audience(&mut self, attr: &str, value: &str) -> Result<Option<Py<PyAny>>> {
         let audiences = self.audiences()?;
 
         Python::attach(|py| -> PyResult<Option<Py<PyAny>>> {
             for audience in audiences {
-                // Get the 'id' attribute from the plugin instance
-                let id: String = match audience.getattr(py, "id") {
-                    Ok(id_attr) => match id_attr.extract(py) {
-                        Ok(id) => id,
-                        Err(_) => continue, // Skip if can't extract ID
+                // Get the attribute from the plugin instance
+                let found: String = match audience.getattr(py, attr) {
+                    Ok(found_attr) => match found_attr.extract(py) {
+                        Ok(found) => found,
+                        Err(_) => continue, // Skip if can't extract it
                     },
-                    Err(_) => continue, // Skip if no 'id' attribute
+                    Err(_) => continue, // Skip if no such attribute
                 };
 
-                if id == audience_id {
+                if found == value {
                     return Ok(Some(audience));
                 }
             }
             Ok(None)
         })
-        .map_err(|e: PyErr| anyhow::anyhow!("Failed to get audience by ID: {}", e))
+        .map_err(|e: PyErr| anyhow::anyhow!("Failed to get audience by {}: {}", attr, e))
     }
 }
 
@@ -419,16 +433,23 @@ impl AudiencePlugin {
     }
 
     /// Compile a timesheet for the given log
-    pub fn compile_timesheet(&self, log: &Log) -> Result<Timesheet> {
+    ///
+    /// The plugin is given the log's time per tracker as credited by
+    /// `allocation`, so it doesn't need a rule of its own.
+    pub fn compile_timesheet(&self, log: &Log, allocation: &Allocation) -> Result<Timesheet> {
         Python::attach(|py| -> PyResult<Timesheet> {
             // Create a PyLog wrapper around the Rust Log
             use crate::py_models::log::PyLog;
             let pylog = Py::new(py, PyLog { inner: log.clone() })?;
+            let totals = crate::py_models::config::tracker_totals_to_py(
+                py,
+                &log.tracker_totals(allocation),
+            )?;
 
             // Call the compile_time_sheet method
             let result = self
                 .instance
-                .call_method1(py, "compile_time_sheet", (pylog,))?;
+                .call_method1(py, "compile_time_sheet", (pylog, totals))?;
 
             // The result should be a PyTimesheet object
             use crate::py_models::timesheet::PyTimesheet;
@@ -465,90 +486,22 @@ impl AudiencePlugin {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Mutex;
-
-    struct MockStorage {
-        files: Mutex<HashMap<PathBuf, Vec<u8>>>,
-        root: PathBuf,
-    }
-
-    impl MockStorage {
-        fn new() -> Self {
-            Self {
-                files: Mutex::new(HashMap::new()),
-                root: PathBuf::from("/test"),
-            }
-        }
-    }
-
-    impl Storage for MockStorage {
-        fn root_dir(&self) -> PathBuf {
-            self.root.clone()
-        }
-        fn log_dir(&self) -> PathBuf {
-            self.root.join("logs")
-        }
-        fn plan_dir(&self) -> PathBuf {
-            self.root.join("plans")
-        }
-        fn identity_dir(&self) -> PathBuf {
-            self.root.join("identities")
-        }
-        fn timesheet_dir(&self) -> PathBuf {
-            self.root.join("timesheets")
-        }
-        fn config_file(&self) -> PathBuf {
-            self.root.join("config.toml")
-        }
-        fn read_string(&self, path: &PathBuf) -> Result<String> {
-            let bytes = self.read_bytes(path)?;
-            Ok(String::from_utf8(bytes)?)
-        }
-        fn read_bytes(&self, path: &PathBuf) -> Result<Vec<u8>> {
-            self.files
-                .lock()
-                .unwrap()
-                .get(path)
-                .cloned()
-                .ok_or_else(|| anyhow::anyhow!("File not found"))
-        }
-        fn write_string(&self, path: &PathBuf, data: &str) -> Result<()> {
-            self.write_bytes(path, data.as_bytes())
-        }
-        fn write_bytes(&self, path: &PathBuf, data: &[u8]) -> Result<()> {
-            self.files
-                .lock()
-                .unwrap()
-                .insert(path.clone(), data.to_vec());
-            Ok(())
-        }
-        fn delete(&self, path: &PathBuf) -> Result<()> {
-            let mut files = self.files.lock().unwrap();
-            if files.remove(path).is_some() {
-                Ok(())
-            } else {
-                anyhow::bail!("File not found: {:?}", path)
-            }
-        }
-        fn exists(&self, path: &PathBuf) -> bool {
-            self.files.lock().unwrap().contains_key(path)
-        }
-        fn create_dir_all(&self, _path: &PathBuf) -> Result<()> {
-            Ok(())
-        }
-        fn list_files(&self, _dir: &PathBuf, _pattern: &str) -> Result<Vec<PathBuf>> {
-            Ok(vec![])
-        }
-    }
+    use crate::memory_storage::MemoryStorage;
 
     #[test]
     fn test_plugin_manager_creation() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let config = crate::models::Config {
             timezone: chrono_tz::America::New_York,
             plan_remote: vec![],
             timesheet_audience: vec![],
             role: vec![],
+            directories: Default::default(),
+            allocation: Default::default(),
+            budget: vec![],
+            schedule: Default::default(),
+            import_rule: vec![],
+            rate: vec![],
         };
         let mut manager = PluginManager::new(storage, config);
 
@@ -1,57 +1,39 @@
-use crate::models::{Timesheet, TimesheetMeta};
-use crate::storage::Storage;
-use anyhow::Context;
+use crate::async_storage::block_on;
+use crate::managers::AsyncTimesheetManager;
+use crate::models::Timesheet;
+#[cfg(feature = "sqlite")]
+use crate::sqlite_index::SqliteIndex;
+use crate::storage::{Storage, VersionToken};
 use chrono::NaiveDate;
 use std::sync::Arc;
 
 /// Manages timesheet storage and retrieval
+///
+/// Drives an `AsyncTimesheetManager` over synchronous storage, which is
+/// where the operations are implemented.
 #[derive(Clone)]
 pub struct TimesheetManager {
-    storage: Arc<dyn Storage>,
+    inner: AsyncTimesheetManager<Arc<dyn Storage>>,
 }
 
 impl TimesheetManager {
     pub fn new(storage: Arc<dyn Storage>) -> Self {
-        Self { storage }
+        Self {
+            inner: AsyncTimesheetManager::new(storage),
+        }
+    }
+
+    /// Keep `index` up to date with every timesheet written or deleted
+    #[cfg(feature = "sqlite")]
+    pub fn with_index(self, index: Arc<SqliteIndex>) -> Self {
+        Self {
+            inner: self.inner.with_index(index),
+        }
     }
 
     /// Write a timesheet to storage
     pub fn write_timesheet(&self, timesheet: &Timesheet) -> anyhow::Result<()> {
-        let timesheet_dir = self.storage.timesheet_dir();
-        self.storage
-            .create_dir_all(&timesheet_dir)
-            .context("Failed to create timesheet directory")?;
-
-        // Write the canonical timesheet
-        let timesheet_filename = format!(
-            "{}.{}.json",
-            timesheet.meta.audience_id,
-            timesheet.date.format("%Y-%m-%d")
-        );
-        let timesheet_path = timesheet_dir.join(&timesheet_filename);
-        let canonical = timesheet
-            .submittable_timesheet()
-            .canonical_form()
-            .context("Failed to create canonical form")?;
-        self.storage
-            .write_bytes(&timesheet_path, &canonical)
-            .with_context(|| {
-                format!(
-                    "Failed to write timesheet for {} on {}",
-                    timesheet.meta.audience_id, timesheet.date
-                )
-            })?;
-
-        // Write the metadata separately
-        let meta_filename = format!("{}.meta", timesheet_filename);
-        let meta_path = timesheet_dir.join(&meta_filename);
-        let meta_json = serde_json::to_vec(&timesheet.meta)
-            .context("Failed to serialize timesheet metadata")?;
-        self.storage
-            .write_bytes(&meta_path, &meta_json)
-            .context("Failed to write timesheet metadata")?;
-
-        Ok(())
+        block_on(self.inner.write_timesheet(timesheet))
     }
 
     /// Get a timesheet for a specific audience and date
@@ -62,162 +44,42 @@ impl TimesheetManager {
         audience_id: &str,
         date: NaiveDate,
     ) -> anyhow::Result<Option<Timesheet>> {
-        let timesheet_dir = self.storage.timesheet_dir();
-        let timesheet_filename = format!("{}.{}.json", audience_id, date.format("%Y-%m-%d"));
-        let timesheet_path = timesheet_dir.join(&timesheet_filename);
-
-        if !self.storage.exists(&timesheet_path) {
-            return Ok(None);
-        }
-
-        // Read the timesheet
-        let timesheet_data = self
-            .storage
-            .read_string(&timesheet_path)
-            .with_context(|| format!("Failed to read timesheet for {} on {}", audience_id, date))?;
-        let mut timesheet: Timesheet =
-            serde_json::from_str(&timesheet_data).with_context(|| {
-                format!("Failed to parse timesheet for {} on {}", audience_id, date)
-            })?;
-
-        // Try to load metadata if it exists
-        let meta_filename = format!("{}.meta", timesheet_filename);
-        let meta_path = timesheet_dir.join(&meta_filename);
-
-        if self.storage.exists(&meta_path) {
-            let meta_data = self
-                .storage
-                .read_string(&meta_path)
-                .context("Failed to read timesheet metadata")?;
-            let meta: TimesheetMeta =
-                serde_json::from_str(&meta_data).context("Failed to parse timesheet metadata")?;
-            timesheet.meta = meta;
-        }
-
-        Ok(Some(timesheet))
+        block_on(self.inner.get_timesheet(audience_id, date))
     }
 
     /// List all timesheets, optionally filtered by date
     pub fn list_timesheets(&self, date: Option<NaiveDate>) -> anyhow::Result<Vec<Timesheet>> {
-        let timesheet_dir = self.storage.timesheet_dir();
-
-        let pattern = if let Some(d) = date {
-            format!("*.{}.json", d.format("%Y-%m-%d"))
-        } else {
-            "*.json".to_string()
-        };
-
-        let files = self
-            .storage
-            .list_files(&timesheet_dir, &pattern)
-            .context("Failed to list timesheet files")?;
-        let mut timesheets = Vec::new();
-
-        for file in files {
-            let filename = file
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| anyhow::anyhow!("Invalid filename"))?;
-
-            // Skip meta files
-            if filename.ends_with(".meta") {
-                continue;
-            }
-
-            // Parse audience_id and date from filename: audience.YYYY-MM-DD
-            let parts: Vec<&str> = filename.split('.').collect();
-            if parts.len() != 2 {
-                eprintln!(
-                    "[WARN] Skipping file with unexpected format: {} ({} parts)",
-                    filename,
-                    parts.len()
-                );
-                continue;
-            }
-
-            let audience_id = parts[0];
-            let date_str = parts[1];
-            let ts_date = match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                Ok(d) => d,
-                Err(e) => {
-                    eprintln!(
-                        "[WARN] Skipping file with invalid date format '{}': {}",
-                        date_str, e
-                    );
-                    continue;
-                }
-            };
-
-            // Filter by date if specified
-            if let Some(filter_date) = date {
-                if ts_date != filter_date {
-                    continue;
-                }
-            }
-
-            match self.get_timesheet(audience_id, ts_date) {
-                Ok(Some(timesheet)) => timesheets.push(timesheet),
-                Ok(None) => {
-                    eprintln!(
-                        "[WARN] Timesheet file exists but couldn't be loaded: {}.{}",
-                        audience_id, date_str
-                    );
-                }
-                Err(e) => {
-                    eprintln!(
-                        "[ERROR] Failed to load timesheet {}.{}: {}",
-                        audience_id, date_str, e
-                    );
-                    return Err(e);
-                }
-            }
-        }
+        block_on(self.inner.list_timesheets(date))
+    }
 
-        timesheets.sort_by_key(|t| t.date);
-        Ok(timesheets)
+    /// The audience and date of every timesheet in storage, optionally
+    /// filtered by date
+    pub fn list_timesheet_keys(
+        &self,
+        date: Option<NaiveDate>,
+    ) -> anyhow::Result<Vec<(String, NaiveDate)>> {
+        block_on(self.inner.list_timesheet_keys(date))
+    }
+
+    /// Version token of a timesheet's files, covering its metadata too
+    ///
+    /// Returns None if the timesheet doesn't exist.
+    pub fn timesheet_version(
+        &self,
+        audience_id: &str,
+        date: NaiveDate,
+    ) -> anyhow::Result<Option<VersionToken>> {
+        block_on(self.inner.timesheet_version(audience_id, date))
     }
 
     /// Check if a timesheet exists for a specific audience and date
     pub fn timesheet_exists(&self, audience_id: &str, date: NaiveDate) -> bool {
-        let timesheet_dir = self.storage.timesheet_dir();
-        let timesheet_filename = format!("{}.{}.json", audience_id, date.format("%Y-%m-%d"));
-        let timesheet_path = timesheet_dir.join(timesheet_filename);
-        self.storage.exists(&timesheet_path)
+        block_on(self.inner.timesheet_exists(audience_id, date))
     }
 
     /// Delete a timesheet
     pub fn delete_timesheet(&self, audience_id: &str, date: NaiveDate) -> anyhow::Result<()> {
-        let timesheet_dir = self.storage.timesheet_dir();
-        let timesheet_filename = format!("{}.{}.json", audience_id, date.format("%Y-%m-%d"));
-        let timesheet_path = timesheet_dir.join(&timesheet_filename);
-
-        if !self.storage.exists(&timesheet_path) {
-            anyhow::bail!(
-                "Timesheet for audience '{}' on {} does not exist",
-                audience_id,
-                date
-            );
-        }
-
-        // Delete the timesheet file
-        self.storage.delete(&timesheet_path).with_context(|| {
-            format!(
-                "Failed to delete timesheet for audience '{}' on {}",
-                audience_id, date
-            )
-        })?;
-
-        // Delete the metadata file if it exists
-        let meta_filename = format!("{}.meta", timesheet_filename);
-        let meta_path = timesheet_dir.join(&meta_filename);
-
-        if self.storage.exists(&meta_path) {
-            self.storage
-                .delete(&meta_path)
-                .context("Failed to delete timesheet metadata")?;
-        }
-
-        Ok(())
+        block_on(self.inner.delete_timesheet(audience_id, date))
     }
 
     /// Submit a timesheet via its audience plugin
@@ -271,9 +133,8 @@ impl TimesheetManager {
 
         // TODO: Update timesheet metadata with submitted_at and submitted_by
         // For now, just write it back as-is
-        self.write_timesheet(timesheet)?;
-
-        Ok(())
+        let message = format!("submit timesheet {} {}", audience_id, timesheet.date);
+        block_on(self.inner.write_timesheet_as(timesheet, &message))
     }
 
     /// Get audience plugin instances
@@ -300,13 +161,13 @@ impl TimesheetManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory_storage::MemoryStorage;
     use crate::models::TimesheetMeta;
-    use crate::test_utils::mock_storage::MockStorage;
     use std::collections::HashMap;
 
     #[test]
     fn test_write_and_read_timesheet() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = TimesheetManager::new(storage.clone());
 
         let date = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
@@ -338,7 +199,7 @@ mod tests {
 
     #[test]
     fn test_list_timesheets() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = TimesheetManager::new(storage.clone());
 
         let date1 = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
@@ -373,7 +234,7 @@ mod tests {
 
     #[test]
     fn test_timesheet_exists() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = TimesheetManager::new(storage.clone());
 
         let date = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
@@ -398,7 +259,7 @@ mod tests {
 
     #[test]
     fn test_delete_timesheet() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = TimesheetManager::new(storage.clone());
 
         let date = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
@@ -425,7 +286,7 @@ mod tests {
 
     #[test]
     fn test_delete_nonexistent_timesheet() {
-        let storage = Arc::new(MockStorage::new());
+        let storage = Arc::new(MemoryStorage::new());
         let manager = TimesheetManager::new(storage);
 
         let date = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
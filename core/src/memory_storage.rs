@@ -0,0 +1,584 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::file_system_storage::{resolve_dir, FileSystemStorage, LayoutConfig};
+use crate::models::config::Directories;
+use crate::storage::{Storage, StorageLock, VersionConflict, VersionToken};
+
+/// MemoryStorage implements the Storage trait entirely in memory.
+///
+/// A workspace can be loaded from a real directory, with the same layout
+/// `FileSystemStorage` would use, operated on without touching disk, and
+/// optionally dumped back. Useful for fast tests and for previewing what an
+/// operation would change.
+///
+/// Writing a file implicitly creates its parent directories.
+pub struct MemoryStorage {
+    faff_root: PathBuf,
+    faff_dir: PathBuf,
+    log_dir: PathBuf,
+    plan_dir: PathBuf,
+    identity_dir: PathBuf,
+    timesheet_dir: PathBuf,
+    state: RwLock<MemoryState>,
+    lock: Arc<WorkspaceLock>,
+    lock_timeout: Duration,
+}
+
+/// A point-in-time copy of a `MemoryStorage`'s contents
+///
+/// Taken with `MemoryStorage::snapshot` and put back with `restore`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    state: MemoryState,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct MemoryState {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+    dirs: BTreeSet<PathBuf>,
+}
+
+impl MemoryState {
+    fn add_dir_all(&mut self, path: &Path) {
+        for dir in path.ancestors() {
+            if dir.as_os_str().is_empty() || !self.dirs.insert(dir.to_path_buf()) {
+                break;
+            }
+        }
+    }
+
+    fn insert_file(&mut self, path: &Path, data: Vec<u8>) {
+        if let Some(parent) = path.parent() {
+            self.add_dir_all(parent);
+        }
+        self.files.insert(path.to_path_buf(), data);
+    }
+}
+
+/// In-process stand-in for the advisory lock file
+#[derive(Default)]
+struct WorkspaceLock {
+    held: Mutex<bool>,
+    released: Condvar,
+}
+
+struct WorkspaceLockGuard(Arc<WorkspaceLock>);
+
+impl Drop for WorkspaceLockGuard {
+    fn drop(&mut self) {
+        *self.0.held.lock().unwrap() = false;
+        self.0.released.notify_one();
+    }
+}
+
+impl MemoryStorage {
+    /// Lock file inside `.faff`, which only matters on disk
+    const LOCK_FILE: &'static str = ".lock";
+
+    /// Create an empty workspace rooted at `/faff`
+    ///
+    /// The config file and data directories sit directly under the root,
+    /// e.g. `/faff/logs`, which keeps fixture paths short.
+    pub fn new() -> Self {
+        let root = PathBuf::from("/faff");
+        Self::with_layout(root.clone(), root, Directories::default())
+    }
+
+    /// Create an empty workspace rooted at the given (virtual) path
+    ///
+    /// Uses `FileSystemStorage`'s default layout, with everything under
+    /// `<root>/.faff`.
+    pub fn with_root(faff_root: PathBuf) -> Self {
+        let faff_dir = faff_root.join(".faff");
+        Self::with_layout(faff_root, faff_dir, Directories::default())
+    }
+
+    /// Create an empty workspace whose config lives in `faff_dir`
+    ///
+    /// `dirs` places the data directories as in `config.toml`, resolved
+    /// the way `FileSystemStorage` resolves them.
+    fn with_layout(faff_root: PathBuf, faff_dir: PathBuf, dirs: Directories) -> Self {
+        let mut state = MemoryState::default();
+        state.add_dir_all(&faff_dir);
+        Self {
+            log_dir: resolve_dir(&faff_dir, dirs.logs, "logs"),
+            plan_dir: resolve_dir(&faff_dir, dirs.plans, "plans"),
+            identity_dir: resolve_dir(&faff_dir, dirs.identities, "keys"),
+            timesheet_dir: resolve_dir(&faff_dir, dirs.timesheets, "timesheets"),
+            faff_root,
+            faff_dir,
+            state: RwLock::new(state),
+            lock: Arc::new(WorkspaceLock::default()),
+            lock_timeout: FileSystemStorage::DEFAULT_LOCK_TIMEOUT,
+        }
+    }
+
+    /// How long `lock` waits for another holder before giving up
+    pub fn with_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// Load a copy of the workspace at `faff_root` (the directory containing `.faff`)
+    ///
+    /// Data directories moved by `[directories]` in `config.toml` are loaded
+    /// from where they are. Paths are kept as they are on disk, so the copy
+    /// can be dumped back over the original.
+    pub fn load_from_dir(faff_root: &Path) -> Result<Self> {
+        let faff_dir = faff_root.join(".faff");
+        if !faff_dir.is_dir() {
+            anyhow::bail!("No .faff directory found in {}", faff_root.display());
+        }
+
+        let dirs = LayoutConfig::read(&faff_dir.join("config.toml"))?.directories;
+        let storage = Self::with_layout(faff_root.to_path_buf(), faff_dir, dirs);
+        let mut state = storage.state.write().unwrap();
+        load_dir(&storage.faff_dir, &mut state)?;
+        let faff_dir = storage.faff_dir.canonicalize()?;
+        for dir in storage.data_dirs() {
+            // Relative overrides like `../logs` only look like they're inside
+            if dir.is_dir() && !dir.canonicalize()?.starts_with(&faff_dir) {
+                load_dir(&dir, &mut state)?;
+            }
+        }
+        drop(state);
+        Ok(storage)
+    }
+
+    fn data_dirs(&self) -> [PathBuf; 4] {
+        [
+            self.log_dir.clone(),
+            self.plan_dir.clone(),
+            self.identity_dir.clone(),
+            self.timesheet_dir.clone(),
+        ]
+    }
+
+    /// Write every directory and file to disk under `faff_root`
+    ///
+    /// Files are placed relative to `faff_root` as they are to this storage's
+    /// root, so data directories moved outside the root can't be dumped.
+    /// Files on disk that aren't in memory are left alone.
+    pub fn dump_to_dir(&self, faff_root: &Path) -> Result<()> {
+        let state = self.state.read().unwrap();
+        let target = |path: &Path| -> Result<PathBuf> {
+            let relative = path
+                .strip_prefix(&self.faff_root)
+                .with_context(|| format!("{} is outside the workspace root", path.display()))?;
+            Ok(faff_root.join(relative))
+        };
+
+        for dir in state.dirs.iter().filter(|d| d.starts_with(&self.faff_root)) {
+            let dir = target(dir)?;
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        for (path, data) in &state.files {
+            let path = target(path)?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+            }
+            std::fs::write(&path, data)
+                .with_context(|| format!("Failed to write file: {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Copy the current contents, to `restore` later
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot {
+            state: self.state.read().unwrap().clone(),
+        }
+    }
+
+    /// Replace the current contents with a snapshot
+    pub fn restore(&self, snapshot: &MemorySnapshot) {
+        *self.state.write().unwrap() = snapshot.state.clone();
+    }
+
+    /// Add a file to storage (useful for setting up fixtures)
+    pub fn add_file(&self, path: PathBuf, content: impl Into<Vec<u8>>) {
+        self.state
+            .write()
+            .unwrap()
+            .insert_file(&path, content.into());
+    }
+
+    /// All files currently in storage, keyed by path
+    pub fn files(&self) -> BTreeMap<PathBuf, Vec<u8>> {
+        self.state.read().unwrap().files.clone()
+    }
+
+    /// Remove all files and directories
+    pub fn clear(&self) {
+        *self.state.write().unwrap() = MemoryState::default();
+    }
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn load_dir(dir: &Path, state: &mut MemoryState) -> Result<()> {
+    state.add_dir_all(dir);
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+            .path();
+        if path.is_dir() {
+            load_dir(&path, state)?;
+        } else if path.file_name().and_then(|n| n.to_str()) != Some(MemoryStorage::LOCK_FILE) {
+            let data = std::fs::read(&path)
+                .with_context(|| format!("Failed to read file: {}", path.display()))?;
+            state.files.insert(path, data);
+        }
+    }
+    Ok(())
+}
+
+impl Storage for MemoryStorage {
+    fn root_dir(&self) -> PathBuf {
+        self.faff_root.clone()
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        self.log_dir.clone()
+    }
+
+    fn plan_dir(&self) -> PathBuf {
+        self.plan_dir.clone()
+    }
+
+    fn identity_dir(&self) -> PathBuf {
+        self.identity_dir.clone()
+    }
+
+    fn timesheet_dir(&self) -> PathBuf {
+        self.timesheet_dir.clone()
+    }
+
+    fn config_file(&self) -> PathBuf {
+        self.faff_dir.join("config.toml")
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        let state = self.state.read().unwrap();
+        state
+            .files
+            .get(path)
+            .cloned()
+            .with_context(|| format!("File not found: {}", path.display()))
+    }
+
+    fn read_string(&self, path: &Path) -> Result<String> {
+        String::from_utf8(self.read_bytes(path)?)
+            .with_context(|| format!("File is not valid UTF-8: {}", path.display()))
+    }
+
+    fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+        if state.dirs.contains(path) {
+            anyhow::bail!("Cannot write file over a directory: {}", path.display());
+        }
+        state.insert_file(path, data.to_vec());
+        Ok(())
+    }
+
+    fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+        self.write_bytes(path, data.as_bytes())
+    }
+
+    fn delete(&self, path: &Path) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+        match state.files.remove(path) {
+            Some(_) => Ok(()),
+            None => anyhow::bail!("File not found: {}", path.display()),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        let state = self.state.read().unwrap();
+        state.files.contains_key(path) || state.dirs.contains(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+        if let Some(file) = path.ancestors().find(|p| state.files.contains_key(*p)) {
+            anyhow::bail!("Failed to create directory: {} is a file", file.display());
+        }
+        state.add_dir_all(path);
+        Ok(())
+    }
+
+    /// Match `pattern` against paths relative to `dir`, as `glob` does on disk
+    ///
+    /// Wildcards don't cross `/`, so `*.toml` only matches direct children and
+    /// `*/*.toml` matches one level down. Matching directories are included
+    /// and results are sorted.
+    fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+        let glob_pattern = glob::Pattern::new(pattern).context("Failed to parse glob pattern")?;
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        let state = self.state.read().unwrap();
+        let matches = |path: &&PathBuf| {
+            path.strip_prefix(dir).is_ok_and(|rel| {
+                !rel.as_os_str().is_empty() && glob_pattern.matches_path_with(rel, options)
+            })
+        };
+
+        let mut paths: Vec<PathBuf> = state
+            .files
+            .keys()
+            .chain(state.dirs.iter())
+            .filter(matches)
+            .cloned()
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn lock(&self) -> Result<StorageLock> {
+        let deadline = Instant::now() + self.lock_timeout;
+        let mut held = self.lock.held.lock().unwrap();
+        while *held {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                anyhow::bail!(
+                    "Workspace {} is locked by another holder (waited {:?})",
+                    self.faff_root.display(),
+                    self.lock_timeout
+                );
+            };
+            held = self.lock.released.wait_timeout(held, remaining).unwrap().0;
+        }
+        *held = true;
+        Ok(StorageLock::new(WorkspaceLockGuard(self.lock.clone())))
+    }
+
+    // Compare and write under one write lock, so concurrent writers can't
+    // both pass the version check
+    fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        let mut state = self.state.write().unwrap();
+        let actual = state.files.get(path).map(|d| VersionToken::of(d));
+        if actual.as_ref() != expected {
+            return Err(VersionConflict {
+                path: path.to_path_buf(),
+                expected: expected.cloned(),
+                actual,
+            }
+            .into());
+        }
+        state.insert_file(path, data.as_bytes().to_vec());
+        Ok(VersionToken::of(data.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_write() {
+        let storage = MemoryStorage::new();
+        let path = PathBuf::from("/test/file.txt");
+
+        storage.write_string(&path, "Hello, world!").unwrap();
+        assert!(storage.exists(&path));
+        assert!(storage.exists(Path::new("/test")));
+        assert_eq!(storage.read_string(&path).unwrap(), "Hello, world!");
+
+        storage.write_bytes(&path, &[0xff, 0x00]).unwrap();
+        assert_eq!(storage.read_bytes(&path).unwrap(), vec![0xff, 0x00]);
+        assert!(storage.read_string(&path).is_err());
+
+        storage.delete(&path).unwrap();
+        assert!(!storage.exists(&path));
+        assert!(storage.delete(&path).is_err());
+    }
+
+    #[test]
+    fn test_list_files_matches_like_glob() {
+        let storage = MemoryStorage::new();
+        let dir = PathBuf::from("/test");
+        storage.add_file(dir.join("b.txt"), "b");
+        storage.add_file(dir.join("a.txt"), "a");
+        storage.add_file(dir.join("c.log"), "c");
+        storage.add_file(dir.join("sub/d.txt"), "d");
+        storage.add_file(PathBuf::from("/test2/e.txt"), "e");
+
+        assert_eq!(
+            storage.list_files(&dir, "*.txt").unwrap(),
+            vec![dir.join("a.txt"), dir.join("b.txt")]
+        );
+        assert_eq!(
+            storage.list_files(&dir, "*/*.txt").unwrap(),
+            vec![dir.join("sub/d.txt")]
+        );
+        assert_eq!(
+            storage.list_files(&dir, "*").unwrap(),
+            vec![
+                dir.join("a.txt"),
+                dir.join("b.txt"),
+                dir.join("c.log"),
+                dir.join("sub")
+            ]
+        );
+        assert!(storage
+            .list_files(Path::new("/missing"), "*")
+            .unwrap()
+            .is_empty());
+        assert!(storage.list_files(&dir, "[").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let storage = MemoryStorage::new();
+        let path = storage.log_dir().join("2025-03-15.toml");
+        storage.write_string(&path, "before").unwrap();
+
+        let snapshot = storage.snapshot();
+        storage.write_string(&path, "after").unwrap();
+        storage
+            .write_string(&storage.plan_dir().join("local.toml"), "new")
+            .unwrap();
+
+        storage.restore(&snapshot);
+        assert_eq!(storage.read_string(&path).unwrap(), "before");
+        assert!(!storage.exists(&storage.plan_dir()));
+        assert_eq!(storage.snapshot(), snapshot);
+    }
+
+    #[test]
+    fn test_conditional_write() {
+        let storage = MemoryStorage::new();
+        let path = storage.config_file();
+
+        let v1 = storage.write_string_if(&path, "one", None).unwrap();
+        assert!(storage.write_string_if(&path, "two", None).is_err());
+        storage.write_string_if(&path, "two", Some(&v1)).unwrap();
+        let err = storage
+            .write_string_if(&path, "three", Some(&v1))
+            .unwrap_err();
+        assert!(err.downcast_ref::<VersionConflict>().is_some());
+    }
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let storage = Arc::new(MemoryStorage::new());
+        let counter = Arc::new(Mutex::new(Vec::new()));
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let storage = storage.clone();
+                let counter = counter.clone();
+                std::thread::spawn(move || {
+                    let _lock = storage.lock().unwrap();
+                    counter.lock().unwrap().push(i);
+                    std::thread::sleep(std::time::Duration::from_millis(5));
+                    counter.lock().unwrap().push(i);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let order = counter.lock().unwrap();
+        assert!(order.chunks(2).all(|pair| pair[0] == pair[1]));
+    }
+
+    #[test]
+    fn test_lock_gives_up_after_timeout() {
+        let storage = MemoryStorage::new().with_lock_timeout(Duration::from_millis(50));
+
+        let held = storage.lock().unwrap();
+        let err = storage.lock().err().expect("lock should be held");
+        assert!(err.to_string().contains("locked by another holder"));
+
+        drop(held);
+        assert!(storage.lock().is_ok());
+    }
+
+    #[test]
+    fn test_load_and_dump_real_directory() {
+        let source = tempfile::tempdir().unwrap();
+        let faff_dir = source.path().join(".faff");
+        std::fs::create_dir_all(faff_dir.join("logs")).unwrap();
+        std::fs::create_dir_all(faff_dir.join("keys")).unwrap();
+        std::fs::write(faff_dir.join("config.toml"), "timezone = \"UTC\"").unwrap();
+        std::fs::write(faff_dir.join("logs/2025-03-15.toml"), "log").unwrap();
+        std::fs::write(faff_dir.join(".lock"), "").unwrap();
+
+        let storage = MemoryStorage::load_from_dir(source.path()).unwrap();
+        assert_eq!(
+            storage.read_string(&storage.config_file()).unwrap(),
+            "timezone = \"UTC\""
+        );
+        assert!(storage.exists(&storage.identity_dir()));
+        assert!(!storage.exists(&faff_dir.join(".lock")));
+
+        // Changes stay in memory until dumped
+        storage
+            .write_string(&storage.plan_dir().join("local.20250301.toml"), "plan")
+            .unwrap();
+        assert!(!faff_dir.join("plans").exists());
+
+        let target = tempfile::tempdir().unwrap();
+        storage.dump_to_dir(target.path()).unwrap();
+        let dumped = target.path().join(".faff");
+        assert_eq!(
+            std::fs::read_to_string(dumped.join("logs/2025-03-15.toml")).unwrap(),
+            "log"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dumped.join("plans/local.20250301.toml")).unwrap(),
+            "plan"
+        );
+        assert!(dumped.join("keys").is_dir());
+
+        assert!(MemoryStorage::load_from_dir(target.path().join("missing").as_path()).is_err());
+    }
+
+    #[test]
+    fn test_load_honours_directory_overrides() {
+        let source = tempfile::tempdir().unwrap();
+        let faff_dir = source.path().join(".faff");
+        let shared = source.path().join("shared-logs");
+        std::fs::create_dir_all(&faff_dir).unwrap();
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(
+            faff_dir.join("config.toml"),
+            "[directories]\nlogs = \"../shared-logs\"\n",
+        )
+        .unwrap();
+        std::fs::write(shared.join("2025-03-15.toml"), "log").unwrap();
+
+        let storage = MemoryStorage::load_from_dir(source.path()).unwrap();
+        let disk = FileSystemStorage::open(faff_dir).unwrap();
+        assert_eq!(storage.log_dir(), disk.log_dir());
+        assert_eq!(storage.plan_dir(), disk.plan_dir());
+        assert_eq!(
+            storage
+                .read_string(&storage.log_dir().join("2025-03-15.toml"))
+                .unwrap(),
+            "log"
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::session::Session;
+
+/// How a session's time is credited among its intent's trackers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AllocationStrategy {
+    /// Split the time equally between the trackers
+    #[default]
+    Even,
+    /// Split the time in proportion to each tracker's weight
+    Weighted,
+    /// Credit every tracker with the whole session
+    ///
+    /// Per-tracker totals then add up to more than the recorded time.
+    Full,
+}
+
+impl AllocationStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AllocationStrategy::Even => "even",
+            AllocationStrategy::Weighted => "weighted",
+            AllocationStrategy::Full => "full",
+        }
+    }
+}
+
+impl fmt::Display for AllocationStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AllocationStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "even" => Ok(AllocationStrategy::Even),
+            "weighted" => Ok(AllocationStrategy::Weighted),
+            "full" => Ok(AllocationStrategy::Full),
+            other => Err(format!(
+                "Unknown allocation strategy '{other}', expected even, weighted or full"
+            )),
+        }
+    }
+}
+
+/// The `[allocation]` section of the config
+///
+/// ```toml
+/// [allocation]
+/// strategy = "weighted"
+/// weights = { "element:123" = 3, "element:456" = 1 }
+/// ```
+///
+/// Trackers without a weight count as 1.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Allocation {
+    #[serde(default)]
+    pub strategy: AllocationStrategy,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub weights: HashMap<String, f64>,
+}
+
+/// Time credited to each tracker over a set of sessions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackerTotals {
+    pub trackers: BTreeMap<String, Duration>,
+    /// Time in sessions whose intent has no trackers
+    pub untracked: Duration,
+    /// All recorded time
+    pub total: Duration,
+}
+
+impl TrackerTotals {
+    /// Whether the per-tracker and untracked time add up to the total
+    ///
+    /// Always true for even and weighted allocation.
+    pub fn reconciles(&self) -> bool {
+        self.trackers
+            .values()
+            .fold(self.untracked, |sum, d| sum + *d)
+            == self.total
+    }
+}
+
+impl Allocation {
+    pub fn new(strategy: AllocationStrategy) -> Self {
+        Self {
+            strategy,
+            weights: HashMap::new(),
+        }
+    }
+
+    pub fn with_weight(mut self, tracker: impl Into<String>, weight: f64) -> Self {
+        self.weights.insert(tracker.into(), weight);
+        self
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn weight(&self, tracker: &str) -> f64 {
+        match self.strategy {
+            AllocationStrategy::Weighted => self
+                .weights
+                .get(tracker)
+                .copied()
+                .filter(|w| w.is_finite() && *w >= 0.0)
+                .unwrap_or(1.0),
+            _ => 1.0,
+        }
+    }
+
+    /// Credit `duration` to `trackers`, returning each tracker's share
+    ///
+    /// Trackers are deduplicated and sorted. For even and weighted
+    /// allocation the shares add up to exactly `duration`: time is split to
+    /// the microsecond, and leftover microseconds go to the trackers with the
+    /// largest remainders. If every weight is zero the time is split evenly.
+    pub fn split(&self, trackers: &[String], duration: Duration) -> Vec<(String, Duration)> {
+        let mut trackers: Vec<&String> = trackers.iter().collect();
+        trackers.sort();
+        trackers.dedup();
+
+        if trackers.is_empty() {
+            return Vec::new();
+        }
+        if self.strategy == AllocationStrategy::Full {
+            return trackers
+                .into_iter()
+                .map(|tracker| (tracker.clone(), duration))
+                .collect();
+        }
+
+        let mut weights: Vec<f64> = trackers.iter().map(|t| self.weight(t)).collect();
+        if weights.iter().sum::<f64>() <= 0.0 {
+            weights = vec![1.0; trackers.len()];
+        }
+        let weight_sum: f64 = weights.iter().sum();
+
+        let micros = duration.num_microseconds().unwrap_or(i64::MAX);
+        let exact: Vec<f64> = weights
+            .iter()
+            .map(|w| micros as f64 * w / weight_sum)
+            .collect();
+        let mut shares: Vec<i64> = exact.iter().map(|e| e.floor() as i64).collect();
+
+        let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+        by_remainder.sort_by(|&a, &b| {
+            let ra = exact[a] - exact[a].floor();
+            let rb = exact[b] - exact[b].floor();
+            rb.total_cmp(&ra).then(a.cmp(&b))
+        });
+        let leftover = micros - shares.iter().sum::<i64>();
+        for &i in by_remainder.iter().cycle().take(leftover.max(0) as usize) {
+            shares[i] += 1;
+        }
+
+        let mut result: Vec<(String, Duration)> = trackers
+            .into_iter()
+            .zip(shares)
+            .map(|(tracker, share)| (tracker.clone(), Duration::microseconds(share)))
+            .collect();
+        // Sub-microsecond precision is credited to the first tracker
+        let credited = result.iter().fold(Duration::zero(), |sum, (_, d)| sum + *d);
+        result[0].1 += duration - credited;
+        result
+    }
+
+    /// Credit the closed sessions among their trackers
+    pub fn tracker_totals<'a>(
+        &self,
+        sessions: impl IntoIterator<Item = &'a Session>,
+    ) -> TrackerTotals {
+        let mut totals = TrackerTotals {
+            trackers: BTreeMap::new(),
+            untracked: Duration::zero(),
+            total: Duration::zero(),
+        };
+        for session in sessions {
+            let Ok(duration) = session.duration() else {
+                continue;
+            };
+            totals.total += duration;
+            let shares = self.split(&session.intent.trackers, duration);
+            if shares.is_empty() {
+                totals.untracked += duration;
+            }
+            for (tracker, share) in shares {
+                *totals
+                    .trackers
+                    .entry(tracker)
+                    .or_insert_with(Duration::zero) += share;
+            }
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::session;
+
+    fn trackers(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_even_split_reconciles() {
+        let allocation = Allocation::default();
+        let shares = allocation.split(&trackers(&["b", "a", "c", "a"]), Duration::minutes(10));
+
+        assert_eq!(
+            shares.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        let sum = shares.iter().fold(Duration::zero(), |s, (_, d)| s + *d);
+        assert_eq!(sum, Duration::minutes(10));
+        assert_eq!(shares[0].1, Duration::microseconds(200_000_000));
+        assert_eq!(shares[1].1, Duration::microseconds(200_000_000));
+    }
+
+    #[test]
+    fn test_weighted_split() {
+        let allocation = Allocation::new(AllocationStrategy::Weighted).with_weight("a", 3.0);
+        let shares = allocation.split(&trackers(&["a", "b"]), Duration::hours(2));
+
+        assert_eq!(shares[0], ("a".to_string(), Duration::minutes(90)));
+        assert_eq!(shares[1], ("b".to_string(), Duration::minutes(30)));
+    }
+
+    #[test]
+    fn test_weighted_all_zero_splits_evenly() {
+        let allocation = Allocation::new(AllocationStrategy::Weighted)
+            .with_weight("a", 0.0)
+            .with_weight("b", 0.0);
+        let shares = allocation.split(&trackers(&["a", "b"]), Duration::hours(1));
+
+        assert_eq!(shares[0].1, Duration::minutes(30));
+        assert_eq!(shares[1].1, Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_tracker_totals() {
+        let sessions = vec![
+            session(17, 9, 60).trackers(&["a", "b"]).build(),
+            session(17, 9, 30).trackers(&["a"]).build(),
+            session(17, 9, 15).build(),
+        ];
+
+        let even = Allocation::default().tracker_totals(&sessions);
+        assert_eq!(even.trackers["a"], Duration::minutes(60));
+        assert_eq!(even.trackers["b"], Duration::minutes(30));
+        assert_eq!(even.untracked, Duration::minutes(15));
+        assert_eq!(even.total, Duration::minutes(105));
+        assert!(even.reconciles());
+
+        let full = Allocation::new(AllocationStrategy::Full).tracker_totals(&sessions);
+        assert_eq!(full.trackers["a"], Duration::minutes(90));
+        assert_eq!(full.trackers["b"], Duration::minutes(60));
+        assert!(!full.reconciles());
+    }
+
+    #[test]
+    fn test_config_section() {
+        let allocation: Allocation = toml::from_str(
+            r#"
+            strategy = "weighted"
+            weights = { "element:1" = 2 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(allocation.strategy, AllocationStrategy::Weighted);
+        assert_eq!(allocation.weights["element:1"], 2.0);
+        assert!(Allocation::default().is_default());
+    }
+}
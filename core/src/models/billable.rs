@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::config::Role;
+use crate::models::intent::Intent;
+use crate::models::log::Log;
+
+/// Which sessions to keep, by whether they are billable
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BillableFilter {
+    #[default]
+    All,
+    Billable,
+    NonBillable,
+}
+
+impl BillableFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BillableFilter::All => "all",
+            BillableFilter::Billable => "billable",
+            BillableFilter::NonBillable => "non-billable",
+        }
+    }
+
+    pub fn is_all(&self) -> bool {
+        *self == BillableFilter::All
+    }
+
+    pub fn admits(&self, billable: bool) -> bool {
+        match self {
+            BillableFilter::All => true,
+            BillableFilter::Billable => billable,
+            BillableFilter::NonBillable => !billable,
+        }
+    }
+
+    /// Copy of a resolved log with only the sessions the filter admits
+    ///
+    /// Sessions whose intent has no `billable` value count as non-billable,
+    /// so resolve the log with `Billability::resolve` first.
+    pub fn filter_log(&self, log: &Log) -> Log {
+        let timeline = log
+            .timeline
+            .iter()
+            .filter(|session| self.admits(session.intent.billable == Some(true)))
+            .cloned()
+            .collect();
+        Log::new(log.date, log.timezone, timeline)
+    }
+}
+
+impl fmt::Display for BillableFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BillableFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(BillableFilter::All),
+            "billable" => Ok(BillableFilter::Billable),
+            "non-billable" | "internal" => Ok(BillableFilter::NonBillable),
+            other => Err(format!(
+                "Unknown billable filter '{other}', expected all, billable or non-billable"
+            )),
+        }
+    }
+}
+
+/// Decides whether time on an intent is billable
+///
+/// The first of these that says applies:
+///
+/// 1. the intent's own `billable`
+/// 2. the plans' `[billable]` tables, by tracker: billable if any of the
+///    intent's trackers is, non-billable if any is marked as not
+/// 3. the `billable` of the intent's role in the config's `[[role]]`s
+///
+/// Time nothing says anything about is non-billable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Billability {
+    /// By source-prefixed tracker ID
+    trackers: HashMap<String, bool>,
+    roles: HashMap<String, bool>,
+}
+
+impl Billability {
+    pub fn new(roles: &[Role], trackers: HashMap<String, bool>) -> Self {
+        Self {
+            trackers,
+            roles: roles
+                .iter()
+                .filter_map(|role| role.billable.map(|billable| (role.name.clone(), billable)))
+                .collect(),
+        }
+    }
+
+    pub fn is_billable(&self, intent: &Intent) -> bool {
+        if let Some(billable) = intent.billable {
+            return billable;
+        }
+        let trackers: Vec<bool> = intent
+            .trackers
+            .iter()
+            .filter_map(|tracker| self.trackers.get(tracker).copied())
+            .collect();
+        if !trackers.is_empty() {
+            return trackers.contains(&true);
+        }
+        intent
+            .role
+            .as_ref()
+            .and_then(|role| self.roles.get(role).copied())
+            .unwrap_or(false)
+    }
+
+    /// Copy of `log` with every session's intent `billable` filled in
+    pub fn resolve(&self, log: &Log) -> Log {
+        let timeline = log
+            .timeline
+            .iter()
+            .map(|session| {
+                let mut session = session.clone();
+                session.intent.billable = Some(self.is_billable(&session.intent));
+                session
+            })
+            .collect();
+        Log::new(log.date, log.timezone, timeline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Session;
+    use chrono::TimeZone;
+
+    fn intent(role: &str, trackers: &[&str]) -> Intent {
+        Intent::new(
+            Some(role.to_string()),
+            Some(role.to_string()),
+            None,
+            None,
+            None,
+            trackers.iter().map(|t| t.to_string()).collect(),
+        )
+    }
+
+    fn billability() -> Billability {
+        let roles: Vec<Role> = toml::from_str::<HashMap<String, Vec<Role>>>(
+            r#"
+            role = [
+                { name = "consultant", billable = true },
+                { name = "admin", billable = false },
+                { name = "engineer" },
+            ]
+            "#,
+        )
+        .unwrap()
+        .remove("role")
+        .unwrap();
+        Billability::new(
+            &roles,
+            HashMap::from([
+                ("local:client".to_string(), true),
+                ("local:internal".to_string(), false),
+            ]),
+        )
+    }
+
+    #[test]
+    fn test_precedence() {
+        let billability = billability();
+
+        assert!(billability.is_billable(&intent("consultant", &[])));
+        assert!(!billability.is_billable(&intent("engineer", &[])));
+        // Trackers before roles
+        assert!(!billability.is_billable(&intent("consultant", &["local:internal"])));
+        assert!(billability.is_billable(&intent("admin", &["local:internal", "local:client"])));
+        // Unknown trackers fall through to the role
+        assert!(billability.is_billable(&intent("consultant", &["local:other"])));
+        // The intent itself comes first
+        assert!(billability
+            .is_billable(&intent("admin", &["local:internal"]).with_billable(Some(true))));
+    }
+
+    #[test]
+    fn test_resolve_and_filter() {
+        let tz = chrono_tz::UTC;
+        let start = tz.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+        let session = |intent| Session::new(intent, start, None, None);
+        let log = Log::new(
+            start.date_naive(),
+            tz,
+            vec![
+                session(intent("consultant", &[])),
+                session(intent("admin", &[])),
+            ],
+        );
+
+        let resolved = billability().resolve(&log);
+        assert_eq!(resolved.timeline[0].intent.billable, Some(true));
+        assert_eq!(resolved.timeline[1].intent.billable, Some(false));
+
+        let billable = BillableFilter::Billable.filter_log(&resolved);
+        assert_eq!(billable.timeline.len(), 1);
+        assert_eq!(
+            "internal".parse::<BillableFilter>().unwrap(),
+            BillableFilter::NonBillable
+        );
+        assert_eq!(BillableFilter::All.filter_log(&resolved), resolved);
+    }
+}
@@ -0,0 +1,346 @@
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::allocation::Allocation;
+use crate::models::intent::Intent;
+use crate::models::log::Log;
+use crate::models::report::{serialize_seconds, Dimension};
+
+/// The calendar period a budget or target covers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Day,
+    /// Monday to Sunday
+    Week,
+    #[default]
+    Month,
+    Year,
+}
+
+impl Period {
+    /// The first and last day of the period containing `date`
+    pub fn bounds(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            Period::Day => (date, date),
+            Period::Week => {
+                let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                (start, start + Duration::days(6))
+            }
+            Period::Month => {
+                let start = date.with_day(1).expect("day 1 exists in every month");
+                let next = start
+                    .checked_add_months(chrono::Months::new(1))
+                    .expect("date within range");
+                (start, next.pred_opt().expect("date within range"))
+            }
+            Period::Year => (
+                NaiveDate::from_ymd_opt(date.year(), 1, 1).expect("1 January exists"),
+                NaiveDate::from_ymd_opt(date.year(), 12, 31).expect("31 December exists"),
+            ),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::Year => "year",
+        }
+    }
+}
+
+/// Whether the hours are a limit not to exceed or a goal to reach
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetKind {
+    #[default]
+    Budget,
+    Target,
+}
+
+impl BudgetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetKind::Budget => "budget",
+            BudgetKind::Target => "target",
+        }
+    }
+}
+
+/// A `[[budget]]` entry in the config
+///
+/// Exactly one of `tracker`, `objective` or `role` says what the hours are
+/// for:
+///
+/// ```toml
+/// [[budget]]
+/// tracker = "ABC-123"
+/// hours = 40
+/// period = "month"
+///
+/// [[budget]]
+/// role = "engineer"
+/// hours = 6
+/// period = "day"
+/// kind = "target"
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    pub hours: f64,
+    #[serde(default)]
+    pub period: Period,
+    #[serde(default)]
+    pub kind: BudgetKind,
+}
+
+impl Budget {
+    /// What the budget is for, e.g. `(Dimension::Tracker, "ABC-123")`
+    pub fn scope(&self) -> Result<(Dimension, &str)> {
+        let scopes: Vec<(Dimension, &str)> = [
+            (Dimension::Tracker, &self.tracker),
+            (Dimension::Objective, &self.objective),
+            (Dimension::Role, &self.role),
+        ]
+        .into_iter()
+        .filter_map(|(dimension, value)| value.as_deref().map(|v| (dimension, v)))
+        .collect();
+        match scopes.as_slice() {
+            [scope] => Ok(*scope),
+            [] => bail!("Budget needs one of tracker, objective or role"),
+            _ => bail!("Budget must set only one of tracker, objective or role"),
+        }
+    }
+
+    /// The budget's name, or its scope such as "tracker ABC-123"
+    pub fn label(&self) -> String {
+        match (&self.name, self.scope()) {
+            (Some(name), _) => name.clone(),
+            (None, Ok((dimension, value))) => format!("{dimension} {value}"),
+            (None, Err(_)) => "budget".to_string(),
+        }
+    }
+
+    pub fn amount(&self) -> Duration {
+        Duration::seconds((self.hours * 3600.0).round() as i64)
+    }
+
+    /// Whether time spent on `intent` counts towards the budget
+    pub fn applies_to(&self, intent: &Intent) -> bool {
+        match self.scope() {
+            Ok((Dimension::Tracker, tracker)) => intent.trackers.iter().any(|t| t == tracker),
+            Ok((Dimension::Objective, objective)) => intent.objective.as_deref() == Some(objective),
+            Ok((Dimension::Role, role)) => intent.role.as_deref() == Some(role),
+            _ => false,
+        }
+    }
+
+    /// Progress in the period containing `today`, from the closed sessions
+    ///
+    /// Time on a tracker is credited per `allocation`, as in reports.
+    pub fn status(
+        &self,
+        logs: &[Log],
+        today: NaiveDate,
+        allocation: &Allocation,
+    ) -> Result<BudgetStatus> {
+        let (dimension, value) = self.scope()?;
+        let (start, end) = self.period.bounds(today);
+
+        let mut consumed = Duration::zero();
+        for log in logs
+            .iter()
+            .filter(|log| log.date >= start && log.date <= end)
+        {
+            for session in &log.timeline {
+                if !self.applies_to(&session.intent) {
+                    continue;
+                }
+                let Ok(duration) = session.duration() else {
+                    continue;
+                };
+                consumed += match dimension {
+                    Dimension::Tracker => allocation
+                        .split(&session.intent.trackers, duration)
+                        .into_iter()
+                        .filter(|(tracker, _)| tracker == value)
+                        .fold(Duration::zero(), |sum, (_, share)| sum + share),
+                    _ => duration,
+                };
+            }
+        }
+
+        let days = (end - start).num_days() + 1;
+        let elapsed = ((today - start).num_days() + 1).clamp(1, days);
+        let projected = Duration::seconds(consumed.num_seconds() * days / elapsed);
+
+        Ok(BudgetStatus {
+            label: self.label(),
+            kind: self.kind,
+            period: self.period,
+            start,
+            end,
+            budget: self.amount(),
+            consumed,
+            remaining: self.amount() - consumed,
+            projected,
+        })
+    }
+}
+
+/// How much of a budget or target has been used in its current period
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BudgetStatus {
+    pub label: String,
+    pub kind: BudgetKind,
+    pub period: Period,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    #[serde(rename = "budget_seconds", serialize_with = "serialize_seconds")]
+    pub budget: Duration,
+    #[serde(rename = "consumed_seconds", serialize_with = "serialize_seconds")]
+    pub consumed: Duration,
+    /// Negative once the budget is exceeded
+    #[serde(rename = "remaining_seconds", serialize_with = "serialize_seconds")]
+    pub remaining: Duration,
+    /// Time used by the end of the period at the rate so far
+    #[serde(rename = "projected_seconds", serialize_with = "serialize_seconds")]
+    pub projected: Duration,
+}
+
+impl BudgetStatus {
+    /// Time spent beyond the budget so far
+    pub fn overrun(&self) -> Option<Duration> {
+        (self.consumed > self.budget).then(|| self.consumed - self.budget)
+    }
+
+    /// Time the budget will be exceeded by at the end of the period
+    pub fn projected_overrun(&self) -> Option<Duration> {
+        (self.projected > self.budget).then(|| self.projected - self.budget)
+    }
+
+    /// A message for front ends to show before starting more work on it
+    ///
+    /// Only budgets warn; targets are goals, so exceeding them is fine.
+    pub fn warning(&self) -> Option<String> {
+        if self.kind != BudgetKind::Budget {
+            return None;
+        }
+        let hours = |d: Duration| d.num_minutes() as f64 / 60.0;
+        if let Some(overrun) = self.overrun() {
+            Some(format!(
+                "{} is {:.1}h over its {:.1}h {} budget",
+                self.label,
+                hours(overrun),
+                hours(self.budget),
+                self.period.as_str()
+            ))
+        } else {
+            self.projected_overrun().map(|overrun| {
+                format!(
+                    "{} is on course to exceed its {:.1}h {} budget by {:.1}h",
+                    self.label,
+                    hours(self.budget),
+                    self.period.as_str(),
+                    hours(overrun)
+                )
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::allocation::AllocationStrategy;
+    use crate::test_utils::{date, log, session};
+
+    /// A log of one engineer session of `hours` on `trackers`
+    fn engineer(day: u32, hours: i64, trackers: &[&str]) -> Log {
+        log(
+            day,
+            vec![session(day, 9, hours * 60)
+                .role("engineer")
+                .trackers(trackers)
+                .build()],
+        )
+    }
+
+    fn budget(toml_str: &str) -> Budget {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        assert_eq!(Period::Week.bounds(date(12)), (date(10), date(16)));
+        assert_eq!(Period::Month.bounds(date(12)), (date(1), date(31)));
+        assert_eq!(
+            Period::Month
+                .bounds(NaiveDate::from_ymd_opt(2024, 2, 10).unwrap())
+                .1,
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scope() {
+        assert!(budget("hours = 1").scope().is_err());
+        assert!(budget("hours = 1\nrole = \"a\"\ntracker = \"b\"")
+            .scope()
+            .is_err());
+        assert_eq!(
+            budget("hours = 1\nrole = \"a\"").scope().unwrap(),
+            (Dimension::Role, "a")
+        );
+    }
+
+    #[test]
+    fn test_tracker_budget_with_projection() {
+        let logs = vec![
+            engineer(1, 4, &["ABC-123"]),
+            // Split evenly: 2 hours each
+            engineer(2, 4, &["ABC-123", "XYZ-9"]),
+            engineer(3, 8, &["XYZ-9"]),
+        ];
+        let budget = budget("tracker = \"ABC-123\"\nhours = 40");
+
+        let status = budget
+            .status(&logs, date(3), &Allocation::default())
+            .unwrap();
+        assert_eq!(status.label, "tracker ABC-123");
+        assert_eq!(status.consumed, Duration::hours(6));
+        assert_eq!(status.remaining, Duration::hours(34));
+        // 6 hours in 3 of 31 days
+        assert_eq!(status.projected, Duration::hours(62));
+        assert_eq!(status.overrun(), None);
+        assert_eq!(status.projected_overrun(), Some(Duration::hours(22)));
+        assert!(status.warning().unwrap().contains("on course to exceed"));
+
+        let full = budget
+            .status(&logs, date(3), &Allocation::new(AllocationStrategy::Full))
+            .unwrap();
+        assert_eq!(full.consumed, Duration::hours(8));
+    }
+
+    #[test]
+    fn test_targets_do_not_warn() {
+        let logs = vec![engineer(3, 8, &[])];
+        let target = budget("role = \"engineer\"\nhours = 6\nperiod = \"day\"\nkind = \"target\"");
+
+        let status = target
+            .status(&logs, date(3), &Allocation::default())
+            .unwrap();
+        assert_eq!(status.overrun(), Some(Duration::hours(2)));
+        assert_eq!(status.warning(), None);
+    }
+}
@@ -1,6 +1,15 @@
 use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::import::ImportRule;
+use crate::models::allocation::Allocation;
+use crate::models::billable::BillableFilter;
+use crate::models::budget::Budget;
+use crate::models::rate::Rate;
+use crate::models::rounding::Rounding;
+use crate::models::working_hours::Schedule;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Config {
@@ -11,6 +20,37 @@ pub struct Config {
     pub timesheet_audience: Vec<TimesheetAudience>,
     #[serde(default)]
     pub role: Vec<Role>,
+    #[serde(default, skip_serializing_if = "Directories::is_empty")]
+    pub directories: Directories,
+    #[serde(default, skip_serializing_if = "Allocation::is_default")]
+    pub allocation: Allocation,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budget: Vec<Budget>,
+    #[serde(default, skip_serializing_if = "Schedule::is_default")]
+    pub schedule: Schedule,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub import_rule: Vec<ImportRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rate: Vec<Rate>,
+}
+
+/// Per-directory overrides for where a workspace keeps its files
+///
+/// Unset entries use the standard directory inside `.faff`. Relative paths
+/// are resolved against the `.faff` directory, and a leading `~/` against the
+/// home directory, e.g. `logs = "~/Vault/Timesheets"`.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct Directories {
+    pub logs: Option<PathBuf>,
+    pub plans: Option<PathBuf>,
+    pub identities: Option<PathBuf>,
+    pub timesheets: Option<PathBuf>,
+}
+
+impl Directories {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -41,6 +81,12 @@ pub struct TimesheetAudience {
     pub config: HashMap<String, toml::Value>,
     #[serde(default)]
     pub signing_ids: Vec<String>,
+    /// How time is rounded for this audience
+    #[serde(default, skip_serializing_if = "Rounding::is_default")]
+    pub rounding: Rounding,
+    /// Which sessions the audience sees, e.g. `include = "billable"`
+    #[serde(default, skip_serializing_if = "BillableFilter::is_all")]
+    pub include: BillableFilter,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -48,9 +94,18 @@ pub struct Role {
     pub name: String,
     #[serde(default)]
     pub config: HashMap<String, toml::Value>,
+    /// Whether time in this role is billable, unless its intent or trackers
+    /// say otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub billable: Option<bool>,
 }
 
 impl Config {
+    /// The timesheet audience named `name`
+    pub fn audience(&self, name: &str) -> Option<&TimesheetAudience> {
+        self.timesheet_audience.iter().find(|a| a.name == name)
+    }
+
     /// Load config from TOML string
     pub fn from_toml(toml_str: &str) -> Result<Self, toml::de::Error> {
         toml::from_str(toml_str)
@@ -77,6 +132,33 @@ mod tests {
         assert_eq!(config.plan_remote.len(), 0);
         assert_eq!(config.timesheet_audience.len(), 0);
         assert_eq!(config.role.len(), 0);
+        assert!(config.directories.is_empty());
+        assert!(config.allocation.is_default());
+        assert!(config.budget.is_empty());
+        assert!(config.schedule.is_default());
+        assert!(config.import_rule.is_empty());
+        assert!(config.rate.is_empty());
+    }
+
+    #[test]
+    fn test_directories() {
+        let toml_str = r#"
+            timezone = "Europe/London"
+
+            [directories]
+            logs = "~/Vault/Timesheets"
+            identities = "/home/me/.config/faff/keys"
+        "#;
+
+        let config = Config::from_toml(toml_str).unwrap();
+        assert_eq!(
+            config.directories.logs,
+            Some(PathBuf::from("~/Vault/Timesheets"))
+        );
+        assert_eq!(config.directories.plans, None);
+
+        let round_trip = Config::from_toml(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(round_trip, config);
     }
 
     #[test]
@@ -98,9 +180,17 @@ mod tests {
             name = "audience1"
             plugin = "myhours"
             signing_ids = ["id1", "id2"]
+            rounding = { increment = 6, mode = "up" }
+            include = "billable"
 
             [[role]]
             name = "developer"
+
+            [[rate]]
+            role = "developer"
+            hourly = 95.5
+            currency = "EUR"
+            valid_from = "2025-01-01"
         "#;
 
         let config = Config::from_toml(toml_str).unwrap();
@@ -110,7 +200,23 @@ mod tests {
         assert_eq!(config.plan_remote[0].plugin, "myhours");
         assert_eq!(config.plan_remote[0].defaults.roles, vec!["role1", "role2"]);
         assert_eq!(config.timesheet_audience.len(), 1);
+        assert_eq!(
+            config.audience("audience1").unwrap().rounding,
+            Rounding::new(6, crate::models::RoundingMode::Up)
+        );
+        assert_eq!(
+            config.audience("audience1").unwrap().include,
+            BillableFilter::Billable
+        );
         assert_eq!(config.role.len(), 1);
+        assert_eq!(config.rate[0].hourly, 95.5);
+        assert_eq!(
+            config.rate[0].valid_from,
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 1)
+        );
+
+        let round_trip = Config::from_toml(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(round_trip, config);
     }
 
     #[test]
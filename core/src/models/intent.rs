@@ -1,7 +1,10 @@
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashSet;
 
+use crate::models::plan::Vocabulary;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Intent {
     pub alias: Option<String>,
@@ -11,6 +14,13 @@ pub struct Intent {
     pub subject: Option<String>,
     #[serde(default, deserialize_with = "deserialize_trackers")]
     pub trackers: Vec<String>,
+    /// Stable identifier that survives renames; see `effective_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Whether time on this intent is billable; see `Billability` for what
+    /// decides when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub billable: Option<bool>,
 }
 
 /// Custom deserializer for trackers that handles both string and array formats
@@ -77,6 +87,143 @@ impl Intent {
             action,
             subject,
             trackers: deduped,
+            id: None,
+            billable: None,
+        }
+    }
+
+    /// Copy with trackers sorted, for comparisons that ignore tracker order
+    pub fn normalized(&self) -> Intent {
+        let mut intent = self.clone();
+        intent.trackers.sort();
+        intent
+    }
+
+    /// Identifier derived from the intent's fields (tracker order is ignored)
+    ///
+    /// Intents without an explicit `id` are identified by this, so an intent
+    /// can be given a permanent id after the fact by pinning it before any
+    /// field is changed.
+    pub fn derived_id(&self) -> String {
+        let mut trackers = self.trackers.clone();
+        trackers.sort();
+
+        let fields = [
+            self.alias.as_deref().unwrap_or(""),
+            self.role.as_deref().unwrap_or(""),
+            self.objective.as_deref().unwrap_or(""),
+            self.action.as_deref().unwrap_or(""),
+            self.subject.as_deref().unwrap_or(""),
+            &trackers.join("\x1e"),
+        ];
+        let digest = Sha256::digest(fields.join("\x1f").as_bytes());
+        format!("i-{}", &hex::encode(digest)[..12])
+    }
+
+    /// The explicit id if set, otherwise the derived one
+    pub fn effective_id(&self) -> String {
+        self.id.clone().unwrap_or_else(|| self.derived_id())
+    }
+
+    /// Short human-readable name: the alias, or the id if there isn't one
+    pub fn label(&self) -> String {
+        self.alias.clone().unwrap_or_else(|| self.effective_id())
+    }
+
+    /// Copy of this intent with the given id
+    pub fn with_id(&self, id: String) -> Intent {
+        Intent {
+            id: Some(id),
+            ..self.clone()
+        }
+    }
+
+    /// Copy of this intent marked billable or not, or unmarked with None
+    pub fn with_billable(&self, billable: Option<bool>) -> Intent {
+        Intent {
+            billable,
+            ..self.clone()
         }
     }
+
+    /// The role, objective, action or subject of this intent
+    pub fn field(&self, kind: Vocabulary) -> &Option<String> {
+        match kind {
+            Vocabulary::Role => &self.role,
+            Vocabulary::Objective => &self.objective,
+            Vocabulary::Action => &self.action,
+            Vocabulary::Subject => &self.subject,
+        }
+    }
+
+    pub(crate) fn field_mut(&mut self, kind: Vocabulary) -> &mut Option<String> {
+        match kind {
+            Vocabulary::Role => &mut self.role,
+            Vocabulary::Objective => &mut self.objective,
+            Vocabulary::Action => &mut self.action,
+            Vocabulary::Subject => &mut self.subject,
+        }
+    }
+
+    /// Copy of this intent with its effective id made explicit
+    ///
+    /// Call this before changing any field so that logs recorded against the
+    /// old fields still resolve to the intent.
+    pub fn pinned(&self) -> Intent {
+        self.with_id(self.effective_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_intent() -> Intent {
+        Intent::new(
+            Some("work".to_string()),
+            Some("engineer".to_string()),
+            None,
+            Some("coding".to_string()),
+            None,
+            vec!["b".to_string(), "a".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_derived_id_is_stable_and_ignores_tracker_order() {
+        let intent = sample_intent();
+        let mut reordered = intent.clone();
+        reordered.trackers.reverse();
+
+        assert!(intent.derived_id().starts_with("i-"));
+        assert_eq!(intent.derived_id(), reordered.derived_id());
+        assert_eq!(intent.effective_id(), intent.derived_id());
+
+        let mut renamed = intent.clone();
+        renamed.role = Some("manager".to_string());
+        assert_ne!(intent.derived_id(), renamed.derived_id());
+    }
+
+    #[test]
+    fn test_pinned_id_survives_field_changes() {
+        let intent = sample_intent();
+        let mut pinned = intent.pinned();
+        pinned.role = Some("manager".to_string());
+
+        assert_eq!(pinned.effective_id(), intent.derived_id());
+        assert_eq!(pinned.with_id("x".to_string()).effective_id(), "x");
+    }
+
+    #[test]
+    fn test_id_roundtrips_through_toml() {
+        let intent = sample_intent().with_id("i-123".to_string());
+        let toml_str = toml::to_string(&intent).unwrap();
+        assert!(toml_str.contains("id = \"i-123\""));
+
+        let parsed: Intent = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed, intent);
+
+        let without_id = toml::to_string(&sample_intent()).unwrap();
+        assert!(!without_id.contains("id ="));
+    }
 }
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use crate::models::intent::Intent;
+use crate::models::log::Log;
+
+/// Maps logged intents back to their current definitions
+///
+/// Logs store a full copy of each intent, so after a plan renames a role or
+/// subject, old sessions no longer match any current intent. The resolver
+/// matches them up by id instead of by fields.
+#[derive(Clone, Debug, Default)]
+pub struct IntentResolver {
+    by_id: HashMap<String, Intent>,
+    by_fields: HashMap<Intent, Intent>,
+}
+
+impl IntentResolver {
+    /// Build a resolver over the current intent definitions
+    pub fn new(intents: &[Intent]) -> Self {
+        let mut resolver = Self::default();
+        for intent in intents {
+            resolver.by_id.insert(intent.effective_id(), intent.clone());
+            resolver
+                .by_fields
+                .insert(Self::fields_key(intent), intent.clone());
+        }
+        resolver
+    }
+
+    /// Find the current definition of a logged intent
+    ///
+    /// Matches on the logged intent's effective id (its explicit id, or one
+    /// derived from the fields it was logged with), then falls back to
+    /// comparing fields. Returns None for intents no longer in any plan.
+    pub fn resolve(&self, intent: &Intent) -> Option<&Intent> {
+        self.by_id
+            .get(&intent.effective_id())
+            .or_else(|| self.by_fields.get(&Self::fields_key(intent)))
+    }
+
+    /// Rewrite a log's sessions to use the current intent definitions
+    ///
+    /// Returns the rewritten log and the number of sessions that changed.
+    /// Sessions whose intent can't be resolved are left as they are.
+    pub fn resolve_log(&self, log: &Log) -> (Log, usize) {
+        let mut changed = 0;
+        let timeline = log
+            .timeline
+            .iter()
+            .map(|session| match self.resolve(&session.intent) {
+                Some(current) if *current != session.intent => {
+                    changed += 1;
+                    let mut session = session.clone();
+                    session.intent = current.clone();
+                    session
+                }
+                _ => session.clone(),
+            })
+            .collect();
+
+        (Log::new(log.date, log.timezone, timeline), changed)
+    }
+
+    fn fields_key(intent: &Intent) -> Intent {
+        let mut key = intent.normalized();
+        key.id = None;
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::session::Session;
+    use chrono::{NaiveDate, TimeZone};
+
+    fn intent(alias: &str, role: &str) -> Intent {
+        Intent::new(
+            Some(alias.to_string()),
+            Some(role.to_string()),
+            None,
+            None,
+            None,
+            vec![],
+        )
+    }
+
+    fn renamed(original: &Intent, role: &str) -> Intent {
+        let mut intent = original.pinned();
+        intent.role = Some(role.to_string());
+        intent
+    }
+
+    #[test]
+    fn test_resolves_renamed_intent_by_derived_id() {
+        let old = intent("Work", "dev");
+        let current = renamed(&old, "engineer");
+        let resolver = IntentResolver::new(std::slice::from_ref(&current));
+
+        assert_eq!(resolver.resolve(&old), Some(&current));
+        assert_eq!(resolver.resolve(&current), Some(&current));
+    }
+
+    #[test]
+    fn test_resolves_unchanged_intent_by_fields() {
+        let current = intent("Work", "dev").with_id("custom".to_string());
+        let resolver = IntentResolver::new(std::slice::from_ref(&current));
+
+        assert_eq!(resolver.resolve(&intent("Work", "dev")), Some(&current));
+    }
+
+    #[test]
+    fn test_unknown_intent_is_unresolved() {
+        let resolver = IntentResolver::new(&[intent("Work", "dev")]);
+        assert_eq!(resolver.resolve(&intent("Play", "dev")), None);
+    }
+
+    #[test]
+    fn test_resolve_log_rewrites_changed_sessions() {
+        let old = intent("Work", "dev");
+        let unknown = intent("Play", "dev");
+        let current = renamed(&old, "engineer");
+        let resolver = IntentResolver::new(std::slice::from_ref(&current));
+
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+        let log = Log::new(
+            NaiveDate::from_ymd_opt(2025, 3, 15).unwrap(),
+            chrono_tz::UTC,
+            vec![
+                Session::new(old, start, None, None),
+                Session::new(unknown.clone(), start, None, None),
+            ],
+        );
+
+        let (rewritten, changed) = resolver.resolve_log(&log);
+        assert_eq!(changed, 1);
+        assert_eq!(rewritten.timeline[0].intent, current);
+        assert_eq!(rewritten.timeline[1].intent, unknown);
+    }
+}
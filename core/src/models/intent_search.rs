@@ -0,0 +1,316 @@
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use crate::models::intent::Intent;
+use crate::models::log::Log;
+
+/// Weight applied to a match in each searchable field
+const ALIAS_WEIGHT: f64 = 3.0;
+const ACTION_WEIGHT: f64 = 2.0;
+const OBJECTIVE_WEIGHT: f64 = 2.0;
+const SUBJECT_WEIGHT: f64 = 2.0;
+const ROLE_WEIGHT: f64 = 1.5;
+const TRACKER_WEIGHT: f64 = 1.0;
+
+/// Boost given to an intent used today; halves every RECENCY_HALF_LIFE_DAYS
+const RECENCY_BOOST: f64 = 2.0;
+const RECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+
+/// An intent ranked against a search query
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntentMatch {
+    pub intent: Intent,
+    pub score: f64,
+    /// Names of the fields that matched the query (e.g. "alias", "trackers")
+    pub matched_fields: Vec<String>,
+    /// The most recent date this intent appears in a log, if any
+    pub last_used: Option<NaiveDate>,
+}
+
+/// Find the most recent date each intent was used across a set of logs
+///
+/// Keyed by `Intent::effective_id`, so sessions logged before an intent was
+/// renamed still count towards it.
+pub fn last_used_from_logs(logs: &[Log]) -> HashMap<String, NaiveDate> {
+    let mut last_used: HashMap<String, NaiveDate> = HashMap::new();
+
+    for log in logs {
+        for session in &log.timeline {
+            let entry = last_used
+                .entry(session.intent.effective_id())
+                .or_insert(log.date);
+            if log.date > *entry {
+                *entry = log.date;
+            }
+        }
+    }
+
+    last_used
+}
+
+/// Rank intents against a query
+///
+/// The query is split into whitespace-separated terms, and every term must
+/// match at least one of alias, role, objective, action, subject or a
+/// tracker (by ID or name). Matching is case-insensitive and falls back to
+/// fuzzy subsequence matching. Recently used intents are boosted.
+///
+/// An empty query returns every intent, most recently used first.
+pub fn rank_intents(
+    query: &str,
+    intents: &[Intent],
+    trackers: &HashMap<String, String>,
+    last_used: &HashMap<String, NaiveDate>,
+    today: NaiveDate,
+) -> Vec<IntentMatch> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+    let mut matches: Vec<IntentMatch> = intents
+        .iter()
+        .filter_map(|intent| {
+            let (score, matched_fields) = score_intent(intent, &terms, trackers)?;
+            let last_used = last_used.get(&intent.effective_id()).copied();
+            Some(IntentMatch {
+                intent: intent.clone(),
+                score: score + recency_boost(last_used, today),
+                matched_fields,
+                last_used,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.intent.alias.cmp(&b.intent.alias))
+    });
+
+    matches
+}
+
+/// Score an intent against all query terms, or None if any term fails to match
+fn score_intent(
+    intent: &Intent,
+    terms: &[String],
+    trackers: &HashMap<String, String>,
+) -> Option<(f64, Vec<String>)> {
+    let mut fields: Vec<(&str, f64, String)> = Vec::new();
+    let text_fields = [
+        ("alias", ALIAS_WEIGHT, &intent.alias),
+        ("role", ROLE_WEIGHT, &intent.role),
+        ("objective", OBJECTIVE_WEIGHT, &intent.objective),
+        ("action", ACTION_WEIGHT, &intent.action),
+        ("subject", SUBJECT_WEIGHT, &intent.subject),
+    ];
+    for (name, weight, value) in text_fields {
+        if let Some(value) = value {
+            fields.push((name, weight, value.to_lowercase()));
+        }
+    }
+    for tracker in &intent.trackers {
+        fields.push(("trackers", TRACKER_WEIGHT, tracker.to_lowercase()));
+        if let Some(name) = trackers.get(tracker) {
+            fields.push(("trackers", TRACKER_WEIGHT, name.to_lowercase()));
+        }
+    }
+
+    let mut total = 0.0;
+    let mut matched_fields: Vec<String> = Vec::new();
+
+    for term in terms {
+        let mut best: Option<(f64, &str)> = None;
+        for (name, weight, value) in &fields {
+            let score = weight * term_score(term, value);
+            if score > 0.0 && best.is_none_or(|(b, _)| score > b) {
+                best = Some((score, name));
+            }
+        }
+
+        let (score, name) = best?;
+        total += score;
+        if !matched_fields.iter().any(|f| f == name) {
+            matched_fields.push(name.to_string());
+        }
+    }
+
+    Some((total, matched_fields))
+}
+
+/// How well a single lowercase term matches a lowercase field value (0.0 to 1.0)
+fn term_score(term: &str, value: &str) -> f64 {
+    if value == term {
+        1.0
+    } else if value.starts_with(term) {
+        0.8
+    } else if value
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(term))
+    {
+        0.7
+    } else if value.contains(term) {
+        0.5
+    } else if is_subsequence(term, value) {
+        0.2
+    } else {
+        0.0
+    }
+}
+
+/// Whether every character of `needle` appears in `haystack` in order
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|n| chars.any(|h| h == n))
+}
+
+fn recency_boost(last_used: Option<NaiveDate>, today: NaiveDate) -> f64 {
+    match last_used {
+        Some(date) => {
+            let days_ago = (today - date).num_days().max(0) as f64;
+            RECENCY_BOOST * 0.5_f64.powf(days_ago / RECENCY_HALF_LIFE_DAYS)
+        }
+        None => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::session::Session;
+    use chrono::TimeZone;
+    use chrono_tz::Europe::London;
+
+    fn intent(alias: &str, action: &str, subject: &str, trackers: Vec<&str>) -> Intent {
+        Intent::new(
+            Some(alias.to_string()),
+            Some("element:engineer".to_string()),
+            Some("element:development".to_string()),
+            Some(action.to_string()),
+            Some(subject.to_string()),
+            trackers.into_iter().map(String::from).collect(),
+        )
+    }
+
+    fn sample_intents() -> Vec<Intent> {
+        vec![
+            intent("Code review", "reviewing", "pull requests", vec![]),
+            intent("Write docs", "writing", "documentation", vec![]),
+            intent("Fix bugs", "fixing", "bugs", vec!["element:123"]),
+        ]
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    fn aliases(matches: &[IntentMatch]) -> Vec<&str> {
+        matches
+            .iter()
+            .map(|m| m.intent.alias.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_query_returns_all_sorted_by_alias() {
+        let matches = rank_intents(
+            "",
+            &sample_intents(),
+            &HashMap::new(),
+            &HashMap::new(),
+            date(20),
+        );
+        assert_eq!(
+            aliases(&matches),
+            vec!["Code review", "Fix bugs", "Write docs"]
+        );
+    }
+
+    #[test]
+    fn test_all_terms_must_match() {
+        let intents = sample_intents();
+        let (no_trackers, no_usage) = (HashMap::new(), HashMap::new());
+
+        let matches = rank_intents(
+            "write documentation",
+            &intents,
+            &no_trackers,
+            &no_usage,
+            date(20),
+        );
+        assert_eq!(aliases(&matches), vec!["Write docs"]);
+        assert_eq!(matches[0].matched_fields, vec!["alias", "subject"]);
+
+        let matches = rank_intents("write zzz", &intents, &no_trackers, &no_usage, date(20));
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_matches_tracker_names() {
+        let mut trackers = HashMap::new();
+        trackers.insert("element:123".to_string(), "Login crash".to_string());
+
+        let matches = rank_intents(
+            "crash",
+            &sample_intents(),
+            &trackers,
+            &HashMap::new(),
+            date(20),
+        );
+        assert_eq!(aliases(&matches), vec!["Fix bugs"]);
+        assert_eq!(matches[0].matched_fields, vec!["trackers"]);
+    }
+
+    #[test]
+    fn test_fuzzy_subsequence_match() {
+        let (no_trackers, no_usage) = (HashMap::new(), HashMap::new());
+        let matches = rank_intents("cdrv", &sample_intents(), &no_trackers, &no_usage, date(20));
+        assert_eq!(aliases(&matches), vec!["Code review"]);
+    }
+
+    #[test]
+    fn test_exact_match_ranks_above_substring() {
+        let intents = vec![
+            intent("Planning", "planning", "sprint", vec![]),
+            intent("Replanning", "replanning", "roadmap", vec![]),
+        ];
+        let (no_trackers, no_usage) = (HashMap::new(), HashMap::new());
+        let matches = rank_intents("planning", &intents, &no_trackers, &no_usage, date(20));
+        assert_eq!(aliases(&matches), vec!["Planning", "Replanning"]);
+        assert!(matches[0].score > matches[1].score);
+    }
+
+    #[test]
+    fn test_recent_usage_boosts_ranking() {
+        let intents = sample_intents();
+        let mut last_used = HashMap::new();
+        last_used.insert(intents[1].effective_id(), date(19));
+
+        let matches = rank_intents("", &intents, &HashMap::new(), &last_used, date(20));
+        assert_eq!(matches[0].intent.alias.as_deref(), Some("Write docs"));
+        assert_eq!(matches[0].last_used, Some(date(19)));
+        assert_eq!(matches[1].last_used, None);
+    }
+
+    #[test]
+    fn test_last_used_from_logs_keeps_latest_date() {
+        let intents = sample_intents();
+        let session = |day: u32, intent: &Intent| {
+            Session::new(
+                intent.clone(),
+                London.with_ymd_and_hms(2025, 3, day, 9, 0, 0).unwrap(),
+                None,
+                None,
+            )
+        };
+
+        let logs = vec![
+            Log::new(date(18), London, vec![session(18, &intents[0])]),
+            Log::new(date(10), London, vec![session(10, &intents[0])]),
+            Log::new(date(12), London, vec![session(12, &intents[2])]),
+        ];
+
+        let last_used = last_used_from_logs(&logs);
+        assert_eq!(last_used.get(&intents[0].effective_id()), Some(&date(18)));
+        assert_eq!(last_used.get(&intents[2].effective_id()), Some(&date(12)));
+        assert_eq!(last_used.get(&intents[1].effective_id()), None);
+    }
+}
@@ -0,0 +1,299 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike};
+use chrono_tz::Tz;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::models::intent::Intent;
+use crate::models::log::Log;
+
+/// Sessions starting within this many minutes of the current time of day
+/// count towards `starts_near_now`
+const TIME_OF_DAY_WINDOW_MINUTES: i64 = 60;
+
+/// Usage statistics for a single intent, computed relative to a moment in time
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntentStats {
+    pub intent: Intent,
+    /// Number of sessions recorded against this intent
+    pub session_count: usize,
+    /// Total time of closed sessions
+    pub total_duration: Duration,
+    /// Number of sessions in the current week (Monday to today)
+    pub week_session_count: usize,
+    /// Total time of closed sessions in the current week
+    pub week_duration: Duration,
+    /// Number of sessions started near the current time of day
+    pub starts_near_now: usize,
+    /// The most recent date this intent was used
+    pub last_used: Option<NaiveDate>,
+    /// The most recent date this intent was used on the current weekday
+    pub last_used_on_weekday: Option<NaiveDate>,
+}
+
+impl IntentStats {
+    fn new(intent: Intent) -> Self {
+        Self {
+            intent,
+            session_count: 0,
+            total_duration: Duration::zero(),
+            week_session_count: 0,
+            week_duration: Duration::zero(),
+            starts_near_now: 0,
+            last_used: None,
+            last_used_on_weekday: None,
+        }
+    }
+}
+
+/// Quick-pick lists of intents suggested from history
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntentSuggestions {
+    /// Intents with the most recorded time this week
+    pub most_used_this_week: Vec<IntentStats>,
+    /// Intents usually started around the current time of day
+    pub usual_at_this_time: Vec<IntentStats>,
+    /// Intents most recently used on the current weekday
+    pub last_used_on_weekday: Vec<IntentStats>,
+}
+
+impl IntentSuggestions {
+    /// Build suggestion lists from stats, keeping at most `limit` intents per list
+    pub fn from_stats(stats: &[IntentStats], limit: usize) -> Self {
+        Self {
+            most_used_this_week: top(
+                stats,
+                limit,
+                |s| s.week_session_count > 0,
+                |a, b| {
+                    b.week_duration
+                        .cmp(&a.week_duration)
+                        .then_with(|| b.week_session_count.cmp(&a.week_session_count))
+                },
+            ),
+            usual_at_this_time: top(
+                stats,
+                limit,
+                |s| s.starts_near_now > 0,
+                |a, b| {
+                    b.starts_near_now
+                        .cmp(&a.starts_near_now)
+                        .then_with(|| b.last_used.cmp(&a.last_used))
+                },
+            ),
+            last_used_on_weekday: top(
+                stats,
+                limit,
+                |s| s.last_used_on_weekday.is_some(),
+                |a, b| b.last_used_on_weekday.cmp(&a.last_used_on_weekday),
+            ),
+        }
+    }
+}
+
+/// The first `limit` stats passing `filter`, ordered by `cmp` then alias
+fn top(
+    stats: &[IntentStats],
+    limit: usize,
+    filter: impl Fn(&IntentStats) -> bool,
+    cmp: impl Fn(&IntentStats, &IntentStats) -> Ordering,
+) -> Vec<IntentStats> {
+    let mut list: Vec<IntentStats> = stats.iter().filter(|s| filter(s)).cloned().collect();
+    list.sort_by(|a, b| cmp(a, b).then_with(|| a.intent.alias.cmp(&b.intent.alias)));
+    list.truncate(limit);
+    list
+}
+
+/// Compute usage statistics for the given intents from a set of logs
+///
+/// Sessions whose intent isn't in `intents` are ignored. Intents are matched
+/// by `Intent::effective_id`, so tracker order and pinned renames don't
+/// matter. One entry is returned per intent, in the order given, including
+/// intents that have never been used.
+pub fn compute_intent_stats(
+    intents: &[Intent],
+    logs: &[Log],
+    now: DateTime<Tz>,
+) -> Vec<IntentStats> {
+    let today = now.date_naive();
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let now_minutes = minutes_of_day(&now);
+
+    let mut stats: Vec<IntentStats> = intents.iter().cloned().map(IntentStats::new).collect();
+    let index: HashMap<String, usize> = intents
+        .iter()
+        .enumerate()
+        .map(|(i, intent)| (intent.effective_id(), i))
+        .collect();
+
+    for log in logs {
+        if log.date > today {
+            continue;
+        }
+        let this_week = log.date >= week_start;
+        let same_weekday = log.date.weekday() == today.weekday();
+
+        for session in &log.timeline {
+            let Some(&i) = index.get(&session.intent.effective_id()) else {
+                continue;
+            };
+            let entry = &mut stats[i];
+            let duration = session.duration().unwrap_or_else(|_| Duration::zero());
+
+            entry.session_count += 1;
+            entry.total_duration += duration;
+            if this_week {
+                entry.week_session_count += 1;
+                entry.week_duration += duration;
+            }
+
+            let start = session.start.with_timezone(&now.timezone());
+            let distance = (minutes_of_day(&start) - now_minutes).abs();
+            if distance.min(24 * 60 - distance) <= TIME_OF_DAY_WINDOW_MINUTES {
+                entry.starts_near_now += 1;
+            }
+
+            if entry.last_used.is_none_or(|d| log.date > d) {
+                entry.last_used = Some(log.date);
+            }
+            if same_weekday && entry.last_used_on_weekday.is_none_or(|d| log.date > d) {
+                entry.last_used_on_weekday = Some(log.date);
+            }
+        }
+    }
+
+    stats
+}
+
+fn minutes_of_day(dt: &DateTime<Tz>) -> i64 {
+    (dt.hour() * 60 + dt.minute()) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::session::Session;
+    use chrono::TimeZone;
+    use chrono_tz::Europe::London;
+
+    fn intent(alias: &str) -> Intent {
+        Intent::new(Some(alias.to_string()), None, None, None, None, vec![])
+    }
+
+    fn session(intent: &Intent, day: u32, start: u32, end: u32) -> Session {
+        Session::new(
+            intent.clone(),
+            London.with_ymd_and_hms(2025, 3, day, start, 0, 0).unwrap(),
+            Some(London.with_ymd_and_hms(2025, 3, day, end, 0, 0).unwrap()),
+            None,
+        )
+    }
+
+    fn log(day: u32, timeline: Vec<Session>) -> Log {
+        Log::new(
+            NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            London,
+            timeline,
+        )
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    // 2025-03-20 is a Thursday; the week starts on Monday 2025-03-17
+    fn now() -> DateTime<Tz> {
+        London.with_ymd_and_hms(2025, 3, 20, 9, 15, 0).unwrap()
+    }
+
+    #[test]
+    fn test_compute_intent_stats() {
+        let standup = intent("Standup");
+        let coding = intent("Coding");
+        let unused = intent("Unused");
+        let logs = vec![
+            log(
+                13,
+                vec![session(&standup, 13, 9, 10), session(&coding, 13, 10, 12)],
+            ),
+            log(18, vec![session(&coding, 18, 14, 17)]),
+            log(20, vec![session(&standup, 20, 9, 10)]),
+        ];
+
+        let stats = compute_intent_stats(
+            &[standup.clone(), coding.clone(), unused.clone()],
+            &logs,
+            now(),
+        );
+
+        assert_eq!(stats.len(), 3);
+
+        let s = &stats[0];
+        assert_eq!(s.session_count, 2);
+        assert_eq!(s.total_duration, Duration::hours(2));
+        assert_eq!(s.week_session_count, 1);
+        assert_eq!(s.week_duration, Duration::hours(1));
+        assert_eq!(s.starts_near_now, 2);
+        assert_eq!(s.last_used, Some(date(20)));
+        assert_eq!(s.last_used_on_weekday, Some(date(20)));
+
+        let c = &stats[1];
+        assert_eq!(c.session_count, 2);
+        assert_eq!(c.total_duration, Duration::hours(5));
+        assert_eq!(c.week_duration, Duration::hours(3));
+        assert_eq!(c.starts_near_now, 1);
+        assert_eq!(c.last_used, Some(date(18)));
+        assert_eq!(c.last_used_on_weekday, Some(date(13)));
+
+        let u = &stats[2];
+        assert_eq!(u.session_count, 0);
+        assert_eq!(u.last_used, None);
+    }
+
+    #[test]
+    fn test_sessions_for_unknown_intents_are_ignored() {
+        let known = intent("Known");
+        let logs = vec![log(20, vec![session(&intent("Other"), 20, 8, 9)])];
+
+        let stats = compute_intent_stats(&[known], &logs, now());
+        assert_eq!(stats[0].session_count, 0);
+    }
+
+    #[test]
+    fn test_suggestions_from_stats() {
+        let standup = intent("Standup");
+        let coding = intent("Coding");
+        let unused = intent("Unused");
+        let logs = vec![
+            log(
+                13,
+                vec![session(&standup, 13, 9, 10), session(&coding, 13, 10, 12)],
+            ),
+            log(18, vec![session(&coding, 18, 14, 17)]),
+            log(20, vec![session(&standup, 20, 9, 10)]),
+        ];
+        let stats = compute_intent_stats(&[standup, coding, unused], &logs, now());
+
+        let suggestions = IntentSuggestions::from_stats(&stats, 5);
+        let aliases = |list: &[IntentStats]| -> Vec<String> {
+            list.iter()
+                .map(|s| s.intent.alias.clone().unwrap())
+                .collect()
+        };
+
+        assert_eq!(
+            aliases(&suggestions.most_used_this_week),
+            vec!["Coding", "Standup"]
+        );
+        assert_eq!(
+            aliases(&suggestions.usual_at_this_time),
+            vec!["Standup", "Coding"]
+        );
+        assert_eq!(
+            aliases(&suggestions.last_used_on_weekday),
+            vec!["Standup", "Coding"]
+        );
+
+        let limited = IntentSuggestions::from_stats(&stats, 1);
+        assert_eq!(aliases(&limited.most_used_this_week), vec!["Coding"]);
+    }
+}
@@ -5,6 +5,7 @@ use std::collections::HashMap;
 use std::sync::LazyLock;
 use thiserror::Error;
 
+use crate::models::allocation::{Allocation, TrackerTotals};
 use crate::models::session::Session;
 
 // Compiled regex for commentifying derived values - validated at compile time
@@ -83,6 +84,11 @@ impl Log {
         self.timeline.iter().all(|session| session.end.is_some())
     }
 
+    /// Time credited to each tracker by the closed sessions
+    pub fn tracker_totals(&self, allocation: &Allocation) -> TrackerTotals {
+        allocation.tracker_totals(&self.timeline)
+    }
+
     /// Calculate total recorded time across all sessions
     ///
     /// Returns an error if timezone conversion fails (e.g., during DST transitions)
@@ -121,7 +127,7 @@ impl Log {
                 }
             };
 
-            total = total + duration;
+            total += duration;
         }
 
         Ok(total)
@@ -227,6 +233,12 @@ impl Log {
         if let Some(subject) = &session.intent.subject {
             lines.push(format!("subject = \"{}\"", subject));
         }
+        if let Some(id) = &session.intent.id {
+            lines.push(format!("id = \"{}\"", id));
+        }
+        if let Some(billable) = session.intent.billable {
+            lines.push(format!("billable = {}", billable));
+        }
 
         // Trackers
         let tracker_list = &session.intent.trackers;
@@ -692,6 +704,38 @@ mod tests {
         assert!(output.contains("# duration = \"1 hour and 30 minutes\""));
     }
 
+    #[test]
+    fn test_intent_id_roundtrips_through_log_file() {
+        let intent = sample_intent().with_id("i-abc".to_string());
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+        let session = Session::new(intent.clone(), start, None, None);
+        let log = Log::new(sample_date(), chrono_tz::UTC, vec![session]);
+
+        let output = log.to_log_file(&HashMap::new());
+        assert!(output.contains("id        = \"i-abc\""));
+
+        let parsed = Log::from_log_file(&output).unwrap();
+        assert_eq!(parsed.timeline[0].intent, intent);
+    }
+
+    #[test]
+    fn test_billable_roundtrips_through_log_file() {
+        let intent = sample_intent().with_billable(Some(false));
+        let start = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+        let session = Session::new(intent.clone(), start, None, None);
+        let log = Log::new(sample_date(), chrono_tz::UTC, vec![session]);
+
+        let output = log.to_log_file(&HashMap::new());
+        assert!(output.contains("billable  = false"));
+
+        let parsed = Log::from_log_file(&output).unwrap();
+        assert_eq!(parsed.timeline[0].intent, intent);
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(Log::format_duration(Duration::hours(2)), "2 hours");
@@ -1,17 +1,39 @@
+pub mod allocation;
+pub mod billable;
+pub mod budget;
 pub mod config;
 pub mod intent;
+pub mod intent_resolver;
+pub mod intent_search;
+pub mod intent_stats;
 pub mod log;
 pub mod plan;
+pub mod plan_lint;
+pub mod rate;
+pub mod report;
+pub mod rounding;
 pub mod session;
 pub mod timesheet;
 pub mod toy;
 pub mod valuetype;
+pub mod working_hours;
 
+pub use allocation::{Allocation, AllocationStrategy, TrackerTotals};
+pub use billable::{Billability, BillableFilter};
+pub use budget::{Budget, BudgetKind, BudgetStatus, Period};
 pub use config::Config;
 pub use intent::Intent;
+pub use intent_resolver::IntentResolver;
+pub use intent_search::IntentMatch;
+pub use intent_stats::{IntentStats, IntentSuggestions};
 pub use log::Log;
-pub use plan::Plan;
+pub use plan::{Plan, Vocabulary};
+pub use plan_lint::{PlanDiagnostic, PlanFileReport, Severity};
+pub use rate::{Charge, Charges, Rate, RateKind, RateScope};
+pub use report::{Dimension, Report, ReportRow};
+pub use rounding::{RoundedTime, Rounding, RoundingMode, RoundingScope};
 pub use session::Session;
 pub use timesheet::{SubmittableTimesheet, Timesheet, TimesheetMeta};
 pub use toy::Toy;
 pub use valuetype::ValueType;
+pub use working_hours::{Balance, Schedule, WorkingHours};
@@ -23,9 +23,13 @@ pub struct Plan {
     pub trackers: HashMap<String, String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub intents: Vec<Intent>,
+    /// Whether time on each tracker is billable, by tracker ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub billable: HashMap<String, bool>,
 }
 
 impl Plan {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         source: String,
         valid_from: NaiveDate,
@@ -47,6 +51,7 @@ impl Plan {
             subjects,
             trackers,
             intents,
+            billable: HashMap::new(),
         }
     }
 
@@ -79,6 +84,178 @@ impl Plan {
             subjects: self.subjects.clone(),
             trackers: self.trackers.clone(),
             intents: new_intents,
+            billable: self.billable.clone(),
+        }
+    }
+
+    /// Whether the plan contains an intent, ignoring tracker order
+    pub fn has_intent(&self, intent: &Intent) -> bool {
+        let key = intent.normalized();
+        self.intents.iter().any(|i| i.normalized() == key)
+    }
+
+    /// Remove an intent from the plan (tracker order is ignored when matching)
+    pub fn remove_intent(&self, intent: &Intent) -> Plan {
+        let key = intent.normalized();
+        self.edited(|plan| plan.intents.retain(|i| i.normalized() != key))
+    }
+
+    /// Give an intent a new alias, leaving its other fields unchanged
+    ///
+    /// The intent's id is pinned first so logs recorded under the old alias
+    /// still resolve to it.
+    pub fn rename_intent(&self, intent: &Intent, alias: &str) -> Plan {
+        let key = intent.normalized();
+        self.edited(|plan| {
+            for i in plan.intents.iter_mut().filter(|i| i.normalized() == key) {
+                *i = i.pinned();
+                i.alias = Some(alias.to_string());
+            }
+        })
+    }
+
+    /// Add or rename a tracker
+    pub fn add_tracker(&self, id: &str, name: &str) -> Plan {
+        self.edited(|plan| {
+            plan.trackers.insert(id.to_string(), name.to_string());
+        })
+    }
+
+    /// Remove a tracker
+    pub fn remove_tracker(&self, id: &str) -> Plan {
+        self.edited(|plan| {
+            plan.trackers.remove(id);
+            plan.billable.remove(id);
+        })
+    }
+
+    /// Mark a tracker billable or not, or clear the mark with None
+    pub fn set_billable(&self, id: &str, billable: Option<bool>) -> Plan {
+        self.edited(|plan| match billable {
+            Some(billable) => {
+                plan.billable.insert(id.to_string(), billable);
+            }
+            None => {
+                plan.billable.remove(id);
+            }
+        })
+    }
+
+    /// Whether any intent references a tracker, by plain or source-prefixed ID
+    pub fn tracker_in_use(&self, id: &str) -> bool {
+        let prefixed = format!("{}:{}", self.source, id);
+        self.intents
+            .iter()
+            .any(|i| i.trackers.iter().any(|t| *t == id || *t == prefixed))
+    }
+
+    /// The roles, objectives, actions or subjects of this plan
+    pub fn vocabulary(&self, kind: Vocabulary) -> &Vec<String> {
+        match kind {
+            Vocabulary::Role => &self.roles,
+            Vocabulary::Objective => &self.objectives,
+            Vocabulary::Action => &self.actions,
+            Vocabulary::Subject => &self.subjects,
+        }
+    }
+
+    fn vocabulary_mut(&mut self, kind: Vocabulary) -> &mut Vec<String> {
+        match kind {
+            Vocabulary::Role => &mut self.roles,
+            Vocabulary::Objective => &mut self.objectives,
+            Vocabulary::Action => &mut self.actions,
+            Vocabulary::Subject => &mut self.subjects,
+        }
+    }
+
+    /// Add a role, objective, action or subject, deduplicating if it already exists
+    pub fn add_vocabulary(&self, kind: Vocabulary, value: &str) -> Plan {
+        self.edited(|plan| {
+            let values = plan.vocabulary_mut(kind);
+            if !values.iter().any(|v| v == value) {
+                values.push(value.to_string());
+            }
+        })
+    }
+
+    /// Rename a role, objective, action or subject, including in intents that use it
+    ///
+    /// Intent fields may refer to the value either plainly or prefixed with the
+    /// plan source. Changed intents have their ids pinned first so logs recorded
+    /// under the old name still resolve to them.
+    pub fn rename_vocabulary(&self, kind: Vocabulary, old: &str, new: &str) -> Plan {
+        let prefixed_old = format!("{}:{}", self.source, old);
+        let prefixed_new = format!("{}:{}", self.source, new);
+
+        self.edited(|plan| {
+            for value in plan.vocabulary_mut(kind).iter_mut() {
+                if value == old {
+                    *value = new.to_string();
+                }
+            }
+
+            for intent in plan.intents.iter_mut() {
+                let replacement = match intent.field(kind) {
+                    Some(v) if v == old => new.to_string(),
+                    Some(v) if *v == prefixed_old => prefixed_new.clone(),
+                    _ => continue,
+                };
+                *intent = intent.pinned();
+                *intent.field_mut(kind) = Some(replacement);
+            }
+        })
+    }
+
+    /// Remove a role, objective, action or subject
+    pub fn remove_vocabulary(&self, kind: Vocabulary, value: &str) -> Plan {
+        self.edited(|plan| plan.vocabulary_mut(kind).retain(|v| v != value))
+    }
+
+    /// Copy of this plan taking effect from a different date
+    pub fn with_valid_from(&self, valid_from: NaiveDate) -> Plan {
+        self.edited(|plan| plan.valid_from = valid_from)
+    }
+
+    fn edited(&self, edit: impl FnOnce(&mut Plan)) -> Plan {
+        let mut plan = self.clone();
+        edit(&mut plan);
+        plan
+    }
+}
+
+/// The vocabulary lists a plan defines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Vocabulary {
+    Role,
+    Objective,
+    Action,
+    Subject,
+}
+
+impl std::fmt::Display for Vocabulary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Vocabulary::Role => write!(f, "role"),
+            Vocabulary::Objective => write!(f, "objective"),
+            Vocabulary::Action => write!(f, "action"),
+            Vocabulary::Subject => write!(f, "subject"),
+        }
+    }
+}
+
+impl std::str::FromStr for Vocabulary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "role" | "roles" => Ok(Vocabulary::Role),
+            "objective" | "objectives" => Ok(Vocabulary::Objective),
+            "action" | "actions" => Ok(Vocabulary::Action),
+            "subject" | "subjects" => Ok(Vocabulary::Subject),
+            other => Err(format!(
+                "Unknown vocabulary '{}': expected role, objective, action or subject",
+                other
+            )),
         }
     }
 }
@@ -484,4 +661,106 @@ trackers = []
             Some(&"Refactoring".to_string())
         );
     }
+
+    fn empty_plan() -> Plan {
+        Plan::new(
+            "local".to_string(),
+            NaiveDate::from_ymd_opt(2025, 3, 20).unwrap(),
+            None,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            HashMap::new(),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_remove_and_rename_intent() {
+        let intent = sample_intent();
+        let plan = empty_plan().add_intent(intent.clone());
+
+        let renamed = plan.rename_intent(&intent, "deep work");
+        assert_eq!(renamed.intents[0].alias.as_deref(), Some("deep work"));
+        assert_eq!(renamed.intents[0].role, intent.role);
+        assert!(!renamed.has_intent(&intent));
+
+        let removed = plan.remove_intent(&intent);
+        assert!(removed.intents.is_empty());
+        assert_eq!(plan.intents.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_intent_ignores_tracker_order() {
+        let mut intent = sample_intent();
+        intent.trackers = vec!["a".to_string(), "b".to_string()];
+        let plan = empty_plan().add_intent(intent.clone());
+
+        intent.trackers.reverse();
+        assert!(plan.has_intent(&intent));
+        assert!(plan.remove_intent(&intent).intents.is_empty());
+    }
+
+    #[test]
+    fn test_add_and_remove_tracker() {
+        let plan = empty_plan().add_tracker("PROJ-1", "Project one");
+        assert_eq!(
+            plan.trackers.get("PROJ-1"),
+            Some(&"Project one".to_string())
+        );
+        assert!(!plan.tracker_in_use("PROJ-1"));
+
+        let mut intent = sample_intent();
+        intent.trackers = vec!["local:PROJ-1".to_string()];
+        assert!(plan.add_intent(intent).tracker_in_use("PROJ-1"));
+
+        assert!(plan.remove_tracker("PROJ-1").trackers.is_empty());
+    }
+
+    #[test]
+    fn test_add_and_remove_vocabulary() {
+        let plan = empty_plan()
+            .add_vocabulary(Vocabulary::Role, "engineer")
+            .add_vocabulary(Vocabulary::Role, "engineer")
+            .add_vocabulary(Vocabulary::Subject, "docs");
+
+        assert_eq!(plan.roles, vec!["engineer"]);
+        assert_eq!(plan.vocabulary(Vocabulary::Subject), &vec!["docs"]);
+
+        let plan = plan.remove_vocabulary(Vocabulary::Role, "engineer");
+        assert!(plan.roles.is_empty());
+        assert_eq!(plan.subjects, vec!["docs"]);
+    }
+
+    #[test]
+    fn test_rename_vocabulary_updates_intents_and_pins_ids() {
+        let intent = sample_intent();
+        let mut prefixed = sample_intent();
+        prefixed.alias = Some("prefixed".to_string());
+        prefixed.role = Some("local:engineer".to_string());
+
+        let plan = empty_plan()
+            .add_vocabulary(Vocabulary::Role, "engineer")
+            .add_intent(intent.clone())
+            .add_intent(prefixed.clone());
+
+        let renamed = plan.rename_vocabulary(Vocabulary::Role, "engineer", "developer");
+        assert_eq!(renamed.roles, vec!["developer"]);
+        assert_eq!(renamed.intents[0].role.as_deref(), Some("developer"));
+        assert_eq!(renamed.intents[1].role.as_deref(), Some("local:developer"));
+        assert_eq!(renamed.intents[0].effective_id(), intent.derived_id());
+        assert_eq!(renamed.intents[1].effective_id(), prefixed.derived_id());
+
+        // Intents that don't use the value are left alone
+        let untouched = plan.rename_vocabulary(Vocabulary::Subject, "other", "x");
+        assert_eq!(untouched.intents, plan.intents);
+    }
+
+    #[test]
+    fn test_vocabulary_from_str() {
+        assert_eq!("roles".parse::<Vocabulary>(), Ok(Vocabulary::Role));
+        assert_eq!("action".parse::<Vocabulary>(), Ok(Vocabulary::Action));
+        assert!("tracker".parse::<Vocabulary>().is_err());
+    }
 }
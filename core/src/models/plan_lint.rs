@@ -0,0 +1,240 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::models::plan::Plan;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The plan can't be used as written
+    Error,
+    /// The plan loads, but probably doesn't say what its author meant
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A single problem found in a plan
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlanDiagnostic {
+    pub severity: Severity,
+    /// Stable, machine-readable identifier (e.g. "unknown-tracker")
+    pub code: String,
+    pub message: String,
+}
+
+impl PlanDiagnostic {
+    pub fn new(severity: Severity, code: &str, message: String) -> Self {
+        Self {
+            severity,
+            code: code.to_string(),
+            message,
+        }
+    }
+
+    pub fn error(code: &str, message: String) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: &str, message: String) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+}
+
+impl std::fmt::Display for PlanDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+/// Diagnostics for one plan file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlanFileReport {
+    pub path: PathBuf,
+    pub diagnostics: Vec<PlanDiagnostic>,
+}
+
+impl PlanFileReport {
+    pub fn new(path: PathBuf, diagnostics: Vec<PlanDiagnostic>) -> Self {
+        Self { path, diagnostics }
+    }
+
+    /// Whether any diagnostic is an error
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+}
+
+/// Check a parsed plan for semantic problems
+///
+/// Intent trackers may be written either as plain tracker IDs or prefixed
+/// with the plan source (e.g. "PROJ-123" or "local:PROJ-123").
+pub fn lint_plan(plan: &Plan) -> Vec<PlanDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if plan.source.trim().is_empty() {
+        diagnostics.push(PlanDiagnostic::error(
+            "empty-source",
+            "Plan source is empty".to_string(),
+        ));
+    }
+
+    if let Some(valid_until) = plan.valid_until {
+        if valid_until < plan.valid_from {
+            diagnostics.push(PlanDiagnostic::error(
+                "invalid-validity-range",
+                format!(
+                    "valid_until ({}) is before valid_from ({})",
+                    valid_until, plan.valid_from
+                ),
+            ));
+        }
+    }
+
+    let source_prefix = format!("{}:", plan.source);
+    let mut seen_aliases = HashSet::new();
+    let mut reported_aliases = HashSet::new();
+
+    for (index, intent) in plan.intents.iter().enumerate() {
+        let label = intent
+            .alias
+            .as_deref()
+            .map(|a| format!("'{}'", a))
+            .unwrap_or_else(|| format!("#{}", index + 1));
+
+        for tracker in &intent.trackers {
+            let id = tracker.strip_prefix(&source_prefix).unwrap_or(tracker);
+            if !plan.trackers.contains_key(id) {
+                diagnostics.push(PlanDiagnostic::error(
+                    "unknown-tracker",
+                    format!(
+                        "Intent {} references tracker '{}' which is not in trackers",
+                        label, tracker
+                    ),
+                ));
+            }
+        }
+
+        if let Some(alias) = &intent.alias {
+            if !seen_aliases.insert(alias) && reported_aliases.insert(alias) {
+                diagnostics.push(PlanDiagnostic::warning(
+                    "duplicate-alias",
+                    format!("Alias '{}' is used by more than one intent", alias),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::intent::Intent;
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    fn plan(valid_until: Option<NaiveDate>, intents: Vec<Intent>) -> Plan {
+        let mut trackers = HashMap::new();
+        trackers.insert("PROJ-1".to_string(), "Project one".to_string());
+
+        Plan::new(
+            "local".to_string(),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            valid_until,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            trackers,
+            intents,
+        )
+    }
+
+    fn intent(alias: &str, trackers: Vec<&str>) -> Intent {
+        Intent::new(
+            Some(alias.to_string()),
+            None,
+            None,
+            None,
+            None,
+            trackers.into_iter().map(String::from).collect(),
+        )
+    }
+
+    fn codes(diagnostics: &[PlanDiagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_clean_plan_has_no_diagnostics() {
+        let plan = plan(
+            None,
+            vec![
+                intent("one", vec!["PROJ-1"]),
+                intent("two", vec!["local:PROJ-1"]),
+            ],
+        );
+        assert!(lint_plan(&plan).is_empty());
+    }
+
+    #[test]
+    fn test_valid_until_before_valid_from() {
+        let plan = plan(NaiveDate::from_ymd_opt(2025, 2, 1), vec![]);
+        let diagnostics = lint_plan(&plan);
+        assert_eq!(codes(&diagnostics), vec!["invalid-validity-range"]);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_unknown_tracker() {
+        let plan = plan(
+            None,
+            vec![
+                intent("one", vec!["PROJ-2"]),
+                intent("two", vec!["other:PROJ-1"]),
+            ],
+        );
+        let diagnostics = lint_plan(&plan);
+        assert_eq!(
+            codes(&diagnostics),
+            vec!["unknown-tracker", "unknown-tracker"]
+        );
+        assert!(diagnostics[0].message.contains("'one'"));
+    }
+
+    #[test]
+    fn test_duplicate_alias_reported_once() {
+        let plan = plan(
+            None,
+            vec![
+                intent("same", vec![]),
+                intent("same", vec![]),
+                intent("same", vec![]),
+            ],
+        );
+        let diagnostics = lint_plan(&plan);
+        assert_eq!(codes(&diagnostics), vec!["duplicate-alias"]);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_report_has_errors() {
+        let warning = PlanDiagnostic::warning("duplicate-alias", "x".to_string());
+        let error = PlanDiagnostic::error("unknown-tracker", "y".to_string());
+
+        assert!(!PlanFileReport::new(PathBuf::from("a"), vec![warning.clone()]).has_errors());
+        assert!(PlanFileReport::new(PathBuf::from("a"), vec![warning, error]).has_errors());
+    }
+}
@@ -0,0 +1,552 @@
+use anyhow::{bail, Context, Result};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::allocation::Allocation;
+use crate::models::intent::Intent;
+use crate::models::log::Log;
+use crate::models::report::serialize_seconds;
+use crate::models::rounding::{Rounding, RoundingScope};
+
+/// Whether a rate is what time is charged at or what it costs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateKind {
+    #[default]
+    Revenue,
+    Cost,
+}
+
+impl RateKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateKind::Revenue => "revenue",
+            RateKind::Cost => "cost",
+        }
+    }
+}
+
+impl fmt::Display for RateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RateKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "revenue" => Ok(RateKind::Revenue),
+            "cost" => Ok(RateKind::Cost),
+            other => Err(format!(
+                "Unknown rate kind '{other}', expected revenue or cost"
+            )),
+        }
+    }
+}
+
+/// What a rate is attached to, most specific first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateScope {
+    Tracker,
+    Role,
+    Audience,
+}
+
+impl RateScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateScope::Tracker => "tracker",
+            RateScope::Role => "role",
+            RateScope::Audience => "audience",
+        }
+    }
+}
+
+impl fmt::Display for RateScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `[[rate]]` entry in the config
+///
+/// Exactly one of `tracker`, `role` or `audience` says what the rate is
+/// for, and `valid_from` and `valid_until` (both inclusive, both optional)
+/// when it applies:
+///
+/// ```toml
+/// [[rate]]
+/// role = "consultant"
+/// hourly = 120
+/// currency = "GBP"
+/// valid_from = "2025-01-01"
+///
+/// [[rate]]
+/// tracker = "acme:ABC-123"
+/// hourly = 150
+/// currency = "GBP"
+///
+/// [[rate]]
+/// role = "consultant"
+/// hourly = 45
+/// currency = "GBP"
+/// kind = "cost"
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+    /// Amount per hour, in `currency`
+    pub hourly: f64,
+    pub currency: String,
+    #[serde(default)]
+    pub kind: RateKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<NaiveDate>,
+}
+
+impl Rate {
+    /// What the rate is for, e.g. `(RateScope::Role, "consultant")`
+    pub fn scope(&self) -> Result<(RateScope, &str)> {
+        let scopes: Vec<(RateScope, &str)> = [
+            (RateScope::Tracker, &self.tracker),
+            (RateScope::Role, &self.role),
+            (RateScope::Audience, &self.audience),
+        ]
+        .into_iter()
+        .filter_map(|(scope, value)| value.as_deref().map(|v| (scope, v)))
+        .collect();
+        match scopes.as_slice() {
+            [scope] => Ok(*scope),
+            [] => bail!("Rate needs one of tracker, role or audience"),
+            _ => bail!("Rate must set only one of tracker, role or audience"),
+        }
+    }
+
+    /// The rate's name, or its scope such as "role consultant"
+    pub fn label(&self) -> String {
+        match (&self.name, self.scope()) {
+            (Some(name), _) => name.clone(),
+            (None, Ok((scope, value))) => format!("{scope} {value}"),
+            (None, Err(_)) => "rate".to_string(),
+        }
+    }
+
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.valid_from.is_none_or(|from| date >= from)
+            && self.valid_until.is_none_or(|until| date <= until)
+    }
+
+    /// Whether time on `intent` for `audience` is charged at this rate
+    pub fn applies_to(&self, intent: &Intent, audience: Option<&str>) -> bool {
+        match self.scope() {
+            Ok((RateScope::Tracker, tracker)) => intent.trackers.iter().any(|t| t == tracker),
+            Ok((RateScope::Role, role)) => intent.role.as_deref() == Some(role),
+            Ok((RateScope::Audience, name)) => audience == Some(name),
+            Err(_) => false,
+        }
+    }
+
+    /// What `duration` at this rate comes to, to the hundredth
+    pub fn charge(&self, duration: Duration) -> f64 {
+        round_cents(self.hourly * duration.num_seconds() as f64 / 3600.0)
+    }
+}
+
+/// Time on one day charged at one rate
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Charge {
+    pub date: NaiveDate,
+    pub rate: String,
+    pub currency: String,
+    pub hourly: f64,
+    #[serde(rename = "raw_seconds", serialize_with = "serialize_seconds")]
+    pub raw: Duration,
+    /// The time charged for, after rounding
+    #[serde(rename = "rounded_seconds", serialize_with = "serialize_seconds")]
+    pub rounded: Duration,
+    pub amount: f64,
+}
+
+/// Logged time turned into money, by day and rate
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Charges {
+    pub kind: RateKind,
+    pub charges: Vec<Charge>,
+    /// By currency
+    pub totals: BTreeMap<String, f64>,
+    /// Time no rate applied to
+    #[serde(rename = "unrated_seconds", serialize_with = "serialize_seconds")]
+    pub unrated: Duration,
+}
+
+impl Charges {
+    /// Charge the closed sessions in `logs` at the `kind` rates
+    ///
+    /// A session with several trackers is split among them per
+    /// `allocation`, as in reports, and each tracker's share is charged at
+    /// that tracker's rate. Shares without one, and sessions without
+    /// trackers, are charged at the intent's role's rate, then the
+    /// audience's. Rates not valid on the log's date are skipped, and of
+    /// rates for the same thing the latest to start wins. Revenue is only
+    /// earned on billable time, so resolve the logs with
+    /// `Billability::resolve` first; cost is incurred on all of it.
+    ///
+    /// Time is rounded per `rounding`, over days as `Rounding::apply` has
+    /// them: the dates of the logs sessions are in. Time split across rates
+    /// is rounded in parts, so with session scope each session's time at
+    /// each rate is rounded, and with day scope each day's time at each
+    /// rate.
+    pub fn build(
+        logs: &[Log],
+        rates: &[Rate],
+        kind: RateKind,
+        audience: Option<&str>,
+        allocation: &Allocation,
+        rounding: &Rounding,
+    ) -> Result<Charges> {
+        let mut candidates = Vec::new();
+        for rate in rates.iter().filter(|rate| rate.kind == kind) {
+            let (scope, _) = rate
+                .scope()
+                .with_context(|| format!("Invalid rate '{}'", rate.label()))?;
+            candidates.push((scope, rate));
+        }
+        // The most specific, latest starting candidate `applies` to
+        let pick = |date: NaiveDate, applies: &dyn Fn(RateScope, &Rate) -> bool| {
+            candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, (scope, rate))| rate.is_valid_on(date) && applies(*scope, rate))
+                .min_by_key(|(_, (scope, rate))| (*scope, Reverse(rate.valid_from)))
+                .map(|(index, _)| index)
+        };
+
+        let mut by_rate: BTreeMap<(NaiveDate, usize), (Duration, Duration)> = BTreeMap::new();
+        let mut unrated = Duration::zero();
+        for log in logs {
+            for session in &log.timeline {
+                if kind == RateKind::Revenue && session.intent.billable != Some(true) {
+                    continue;
+                }
+                let Ok(raw) = session.duration() else {
+                    continue;
+                };
+                let fallback = pick(log.date, &|scope, rate| {
+                    scope != RateScope::Tracker && rate.applies_to(&session.intent, audience)
+                });
+                let shares = allocation.split(&session.intent.trackers, raw);
+
+                let mut session_rates: BTreeMap<usize, Duration> = BTreeMap::new();
+                let mut credit = |rate: Option<usize>, share: Duration| match rate {
+                    Some(index) => {
+                        *session_rates.entry(index).or_insert_with(Duration::zero) += share;
+                    }
+                    None => unrated += share,
+                };
+                if shares.is_empty() {
+                    credit(fallback, raw);
+                }
+                for (tracker, share) in shares {
+                    let rate = pick(log.date, &|scope, rate| {
+                        scope == RateScope::Tracker && rate.tracker.as_deref() == Some(&tracker)
+                    });
+                    credit(rate.or(fallback), share);
+                }
+
+                for (index, raw) in session_rates {
+                    let rounded = match rounding.scope {
+                        RoundingScope::Session => rounding.round(raw),
+                        RoundingScope::Day => raw,
+                    };
+                    let entry = by_rate
+                        .entry((log.date, index))
+                        .or_insert((Duration::zero(), Duration::zero()));
+                    entry.0 += raw;
+                    entry.1 += rounded;
+                }
+            }
+        }
+
+        let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+        let charges = by_rate
+            .into_iter()
+            .map(|((date, index), (raw, rounded))| {
+                let rate = candidates[index].1;
+                let rounded = match rounding.scope {
+                    RoundingScope::Session => rounded,
+                    RoundingScope::Day => rounding.round(raw),
+                };
+                let amount = rate.charge(rounded);
+                *totals.entry(rate.currency.clone()).or_default() += amount;
+                Charge {
+                    date,
+                    rate: rate.label(),
+                    currency: rate.currency.clone(),
+                    hourly: rate.hourly,
+                    raw,
+                    rounded,
+                    amount,
+                }
+            })
+            .collect();
+        for total in totals.values_mut() {
+            *total = round_cents(*total);
+        }
+
+        Ok(Charges {
+            kind,
+            charges,
+            totals,
+            unrated,
+        })
+    }
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::allocation::AllocationStrategy;
+    use crate::models::rounding::RoundingMode;
+    use crate::test_utils::{date, log, session, SessionBuilder};
+
+    /// A session in `role`, billable unless the role is admin
+    fn worked(day: u32, hour: u32, minutes: i64, role: &str) -> SessionBuilder {
+        session(day, hour, minutes)
+            .role(role)
+            .billable(role != "admin")
+    }
+
+    fn rates(toml_str: &str) -> Vec<Rate> {
+        toml::from_str::<BTreeMap<String, Vec<Rate>>>(toml_str)
+            .unwrap()
+            .remove("rate")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scope_and_validity() {
+        let rates = rates(
+            r#"
+            rate = [
+                { role = "consultant", hourly = 100, currency = "GBP", valid_until = "2025-03-09" },
+                { role = "consultant", tracker = "x", hourly = 1, currency = "GBP" },
+            ]
+            "#,
+        );
+        assert_eq!(rates[0].label(), "role consultant");
+        assert!(rates[0].is_valid_on(date(9)));
+        assert!(!rates[0].is_valid_on(date(10)));
+        assert!(rates[1].scope().is_err());
+        assert!(Charges::build(
+            &[],
+            &rates,
+            RateKind::Revenue,
+            None,
+            &Allocation::default(),
+            &Rounding::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_revenue_by_precedence_and_date() {
+        let rates = rates(
+            r#"
+            rate = [
+                { role = "consultant", hourly = 100, currency = "GBP" },
+                { role = "consultant", hourly = 120, currency = "GBP", valid_from = "2025-03-11" },
+                { tracker = "acme:rush", hourly = 200, currency = "GBP" },
+                { audience = "acme", hourly = 80, currency = "EUR" },
+                { role = "consultant", hourly = 40, currency = "GBP", kind = "cost" },
+            ]
+            "#,
+        );
+        let logs = vec![
+            log(
+                10,
+                vec![
+                    worked(10, 9, 60, "consultant").build(),
+                    worked(10, 10, 30, "consultant")
+                        .trackers(&["acme:rush"])
+                        .build(),
+                    worked(10, 11, 60, "engineer").build(),
+                    worked(10, 12, 60, "admin").build(),
+                ],
+            ),
+            log(11, vec![worked(11, 9, 90, "consultant").build()]),
+        ];
+
+        let revenue = Charges::build(
+            &logs,
+            &rates,
+            RateKind::Revenue,
+            None,
+            &Allocation::default(),
+            &Rounding::default(),
+        )
+        .unwrap();
+        let amounts: Vec<(NaiveDate, &str, f64)> = revenue
+            .charges
+            .iter()
+            .map(|c| (c.date, c.rate.as_str(), c.amount))
+            .collect();
+        assert_eq!(
+            amounts,
+            vec![
+                (date(10), "role consultant", 100.0),
+                (date(10), "tracker acme:rush", 100.0),
+                (date(11), "role consultant", 180.0),
+            ]
+        );
+        assert_eq!(revenue.totals["GBP"], 380.0);
+        // The engineer's hour has no rate; the admin's isn't billable
+        assert_eq!(revenue.unrated, Duration::hours(1));
+
+        let for_acme = Charges::build(
+            &logs,
+            &rates,
+            RateKind::Revenue,
+            Some("acme"),
+            &Allocation::default(),
+            &Rounding::default(),
+        )
+        .unwrap();
+        assert_eq!(for_acme.totals["EUR"], 80.0);
+        assert_eq!(for_acme.unrated, Duration::zero());
+
+        let cost = Charges::build(
+            &logs,
+            &rates,
+            RateKind::Cost,
+            None,
+            &Allocation::default(),
+            &Rounding::default(),
+        )
+        .unwrap();
+        assert_eq!(cost.totals["GBP"], 120.0);
+        assert_eq!(cost.unrated, Duration::hours(2));
+    }
+
+    #[test]
+    fn test_split_between_tracker_rates() {
+        let rates = rates(
+            r#"
+            rate = [
+                { tracker = "a", hourly = 100, currency = "GBP" },
+                { tracker = "b", hourly = 200, currency = "GBP" },
+                { role = "consultant", hourly = 60, currency = "GBP" },
+            ]
+            "#,
+        );
+        let logs = vec![log(
+            10,
+            vec![
+                worked(10, 9, 60, "consultant")
+                    .trackers(&["a", "b"])
+                    .build(),
+                worked(10, 11, 60, "consultant")
+                    .trackers(&["a", "c"])
+                    .build(),
+            ],
+        )];
+        let charge = |allocation: &Allocation| {
+            let charges = Charges::build(
+                &logs,
+                &rates,
+                RateKind::Revenue,
+                None,
+                allocation,
+                &Rounding::default(),
+            )
+            .unwrap();
+            let amounts: Vec<(String, Duration, f64)> = charges
+                .charges
+                .iter()
+                .map(|c| (c.rate.clone(), c.raw, c.amount))
+                .collect();
+            (amounts, charges.totals["GBP"])
+        };
+
+        // c has no rate, so its share falls back to the role's
+        let (even, total) = charge(&Allocation::default());
+        assert_eq!(
+            even,
+            vec![
+                ("tracker a".to_string(), Duration::minutes(60), 100.0),
+                ("tracker b".to_string(), Duration::minutes(30), 100.0),
+                ("role consultant".to_string(), Duration::minutes(30), 30.0),
+            ]
+        );
+        assert_eq!(total, 230.0);
+
+        let weighted = Allocation::new(AllocationStrategy::Weighted).with_weight("a", 3.0);
+        let (weighted, total) = charge(&weighted);
+        assert_eq!(weighted[0].1, Duration::minutes(90));
+        assert_eq!(weighted[1].1, Duration::minutes(15));
+        assert_eq!(total, 215.0);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let rates = rates(r#"rate = [{ role = "consultant", hourly = 60, currency = "GBP" }]"#);
+        let logs = vec![log(
+            10,
+            vec![
+                worked(10, 9, 5, "consultant").build(),
+                worked(10, 10, 5, "consultant").build(),
+            ],
+        )];
+        let up = Rounding::new(15, RoundingMode::Up);
+
+        let by_session = Charges::build(
+            &logs,
+            &rates,
+            RateKind::Revenue,
+            None,
+            &Allocation::default(),
+            &up,
+        )
+        .unwrap();
+        assert_eq!(by_session.charges[0].raw, Duration::minutes(10));
+        assert_eq!(by_session.charges[0].rounded, Duration::minutes(30));
+        assert_eq!(by_session.totals["GBP"], 30.0);
+
+        let by_day = up.with_scope(RoundingScope::Day);
+        let by_day = Charges::build(
+            &logs,
+            &rates,
+            RateKind::Revenue,
+            None,
+            &Allocation::default(),
+            &by_day,
+        )
+        .unwrap();
+        assert_eq!(by_day.charges[0].rounded, Duration::minutes(15));
+        assert_eq!(by_day.totals["GBP"], 15.0);
+        // At a single rate, charged time matches the rounded time
+        let rounded = Rounding::new(15, RoundingMode::Up)
+            .with_scope(RoundingScope::Day)
+            .apply(&logs);
+        assert_eq!(rounded.rounded, by_day.charges[0].rounded);
+    }
+}
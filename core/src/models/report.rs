@@ -0,0 +1,482 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::allocation::Allocation;
+use crate::models::log::Log;
+use crate::models::session::Session;
+
+/// Something a report can group recorded time by
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dimension {
+    Alias,
+    Role,
+    Objective,
+    Action,
+    Subject,
+    /// A session's time is credited among its trackers per the allocation
+    Tracker,
+    /// The log's date, e.g. "2025-03-15"
+    Date,
+    /// The log's day of the week, e.g. "Monday"
+    Weekday,
+    /// The log's ISO week, e.g. "2025-W11"
+    Week,
+    /// "billable" or "non-billable", once resolved by `Billability`
+    Billable,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 10] = [
+        Dimension::Alias,
+        Dimension::Role,
+        Dimension::Objective,
+        Dimension::Action,
+        Dimension::Subject,
+        Dimension::Tracker,
+        Dimension::Date,
+        Dimension::Weekday,
+        Dimension::Week,
+        Dimension::Billable,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dimension::Alias => "alias",
+            Dimension::Role => "role",
+            Dimension::Objective => "objective",
+            Dimension::Action => "action",
+            Dimension::Subject => "subject",
+            Dimension::Tracker => "tracker",
+            Dimension::Date => "date",
+            Dimension::Weekday => "weekday",
+            Dimension::Week => "week",
+            Dimension::Billable => "billable",
+        }
+    }
+
+    /// The values a session in a log on `date` has for this dimension
+    ///
+    /// Empty if the session has none (e.g. no role), and possibly several
+    /// for trackers.
+    fn values(&self, date: NaiveDate, session: &Session) -> Vec<String> {
+        let intent = &session.intent;
+        let single = |value: &Option<String>| value.iter().cloned().collect();
+        match self {
+            Dimension::Alias => single(&intent.alias),
+            Dimension::Role => single(&intent.role),
+            Dimension::Objective => single(&intent.objective),
+            Dimension::Action => single(&intent.action),
+            Dimension::Subject => single(&intent.subject),
+            Dimension::Tracker => {
+                let mut trackers = intent.trackers.clone();
+                trackers.sort();
+                trackers.dedup();
+                trackers
+            }
+            Dimension::Date => vec![date.to_string()],
+            Dimension::Weekday => vec![weekday_name(date.weekday()).to_string()],
+            Dimension::Week => {
+                let week = date.iso_week();
+                vec![format!("{}-W{:02}", week.year(), week.week())]
+            }
+            Dimension::Billable => intent
+                .billable
+                .map(|billable| if billable { "billable" } else { "non-billable" }.to_string())
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Order values the way a reader expects (weekdays from Monday), with
+    /// missing values last
+    fn compare(&self, a: &Option<String>, b: &Option<String>) -> Ordering {
+        match (a, b) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) if *self == Dimension::Weekday => {
+                match (a.parse::<Weekday>(), b.parse::<Weekday>()) {
+                    (Ok(a), Ok(b)) => a.num_days_from_monday().cmp(&b.num_days_from_monday()),
+                    _ => a.cmp(b),
+                }
+            }
+            (Some(a), Some(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Dimension {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Dimension::ALL
+            .into_iter()
+            .find(|dimension| dimension.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown report dimension '{}' (expected one of: {})",
+                    s,
+                    Dimension::ALL.map(|d| d.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// Recorded time for one combination of dimension values
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReportRow {
+    /// One value per grouping dimension, None where sessions have no value
+    pub key: Vec<Option<String>>,
+    /// Number of sessions contributing to this row
+    pub sessions: usize,
+    #[serde(rename = "seconds", serialize_with = "serialize_seconds")]
+    pub duration: Duration,
+}
+
+/// Recorded time over a date range, grouped by one or more dimensions
+///
+/// Only closed sessions are counted. Sessions belong to the date of the log
+/// they're in, even if they run past midnight.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub group_by: Vec<Dimension>,
+    /// Rows ordered by key, first dimension first
+    pub rows: Vec<ReportRow>,
+    /// Number of closed sessions in the range
+    pub sessions: usize,
+    /// Total recorded time in the range
+    ///
+    /// Rows add up to this. When grouping by tracker, sessions with none fall
+    /// under a None tracker, and sessions with several are credited per the
+    /// allocation strategy: even and weighted shares still add up, but with
+    /// `AllocationStrategy::Full` they count in full towards each tracker,
+    /// so rows add up to more.
+    #[serde(rename = "total_seconds", serialize_with = "serialize_seconds")]
+    pub total: Duration,
+}
+
+/// Serialize a duration as whole seconds, for front ends to format
+pub(crate) fn serialize_seconds<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i64(duration.num_seconds())
+}
+
+impl Report {
+    /// Group the closed sessions of logs dated `start..=end` by `group_by`
+    ///
+    /// With no dimensions, the report has a single row with the total. When
+    /// grouping by tracker, session time is credited per `allocation`.
+    pub fn build(
+        logs: &[Log],
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &[Dimension],
+        allocation: &Allocation,
+    ) -> Self {
+        let mut groups: HashMap<Vec<Option<String>>, (usize, Duration)> = HashMap::new();
+        let mut sessions = 0;
+        let mut total = Duration::zero();
+
+        for log in logs
+            .iter()
+            .filter(|log| log.date >= start && log.date <= end)
+        {
+            for session in &log.timeline {
+                let Ok(duration) = session.duration() else {
+                    continue;
+                };
+                sessions += 1;
+                total += duration;
+
+                for (key, credited) in Self::keys(group_by, allocation, log.date, session, duration)
+                {
+                    let entry = groups.entry(key).or_insert((0, Duration::zero()));
+                    entry.0 += 1;
+                    entry.1 += credited;
+                }
+            }
+        }
+
+        let mut rows: Vec<ReportRow> = groups
+            .into_iter()
+            .map(|(key, (sessions, duration))| ReportRow {
+                key,
+                sessions,
+                duration,
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            group_by
+                .iter()
+                .zip(a.key.iter().zip(&b.key))
+                .map(|(dimension, (a, b))| dimension.compare(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        Self {
+            start,
+            end,
+            group_by: group_by.to_vec(),
+            rows,
+            sessions,
+            total,
+        }
+    }
+
+    /// Every combination of the session's values for the dimensions, with
+    /// the time credited to it
+    fn keys(
+        group_by: &[Dimension],
+        allocation: &Allocation,
+        date: NaiveDate,
+        session: &Session,
+        duration: Duration,
+    ) -> Vec<(Vec<Option<String>>, Duration)> {
+        let mut keys = vec![(Vec::with_capacity(group_by.len()), duration)];
+        for dimension in group_by {
+            let values: Vec<(Option<String>, Option<Duration>)> = match dimension {
+                Dimension::Tracker => allocation
+                    .split(&session.intent.trackers, duration)
+                    .into_iter()
+                    .map(|(tracker, share)| (Some(tracker), Some(share)))
+                    .collect(),
+                _ => dimension
+                    .values(date, session)
+                    .into_iter()
+                    .map(|value| (Some(value), None))
+                    .collect(),
+            };
+            let values = if values.is_empty() {
+                vec![(None, None)]
+            } else {
+                values
+            };
+            keys = keys
+                .into_iter()
+                .flat_map(|(key, credited)| {
+                    values.iter().map(move |(value, share)| {
+                        let mut key = key.clone();
+                        key.push(value.clone());
+                        (key, share.unwrap_or(credited))
+                    })
+                })
+                .collect();
+        }
+        keys
+    }
+
+    /// The report as text cells, header first, for front ends to render as-is
+    ///
+    /// Columns are the dimensions, then "sessions" and "hours" (as H:MM).
+    /// Missing values are shown as "-", and a final "total" row is added.
+    pub fn table(&self) -> Vec<Vec<String>> {
+        let mut header: Vec<String> = self.group_by.iter().map(|d| d.to_string()).collect();
+        header.push("sessions".to_string());
+        header.push("hours".to_string());
+
+        let mut table = vec![header];
+        for row in &self.rows {
+            let mut cells: Vec<String> = row
+                .key
+                .iter()
+                .map(|value| value.clone().unwrap_or_else(|| "-".to_string()))
+                .collect();
+            cells.push(row.sessions.to_string());
+            cells.push(format_hours(row.duration));
+            table.push(cells);
+        }
+
+        let mut total = vec![String::new(); self.group_by.len()];
+        if let Some(first) = total.first_mut() {
+            *first = "total".to_string();
+        }
+        total.push(self.sessions.to_string());
+        total.push(format_hours(self.total));
+        table.push(total);
+        table
+    }
+}
+
+/// Format a duration as hours and minutes, e.g. "12:05"
+pub fn format_hours(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::allocation::AllocationStrategy;
+    use crate::test_utils::{date, log, session};
+
+    fn logs() -> Vec<Log> {
+        let engineer = |day, hour, minutes| {
+            session(day, hour, minutes)
+                .role("engineer")
+                .trackers(&["acme:1", "acme:2"])
+                .build()
+        };
+        let manager = |day, hour, minutes| session(day, hour, minutes).role("manager");
+        vec![
+            log(10, vec![engineer(10, 9, 120), manager(10, 11, 60).build()]),
+            log(
+                11,
+                vec![
+                    engineer(11, 9, 180),
+                    // Still running: not counted
+                    manager(11, 13, 0).open().build(),
+                ],
+            ),
+        ]
+    }
+
+    fn range() -> (NaiveDate, NaiveDate) {
+        (date(10), date(16))
+    }
+
+    #[test]
+    fn test_group_by_role() {
+        let (start, end) = range();
+        let report = Report::build(
+            &logs(),
+            start,
+            end,
+            &[Dimension::Role],
+            &Allocation::default(),
+        );
+
+        assert_eq!(report.sessions, 3);
+        assert_eq!(report.total, Duration::hours(6));
+        assert_eq!(
+            report.rows,
+            vec![
+                ReportRow {
+                    key: vec![Some("engineer".to_string())],
+                    sessions: 2,
+                    duration: Duration::hours(5),
+                },
+                ReportRow {
+                    key: vec![Some("manager".to_string())],
+                    sessions: 1,
+                    duration: Duration::hours(1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_tracker_and_weekday() {
+        let (start, end) = range();
+        let report = Report::build(
+            &logs(),
+            start,
+            end,
+            &[Dimension::Weekday, Dimension::Tracker],
+            &Allocation::default(),
+        );
+
+        let keys: Vec<Vec<Option<&str>>> = report
+            .rows
+            .iter()
+            .map(|row| row.key.iter().map(|v| v.as_deref()).collect())
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                vec![Some("Monday"), Some("acme:1")],
+                vec![Some("Monday"), Some("acme:2")],
+                vec![Some("Monday"), None],
+                vec![Some("Tuesday"), Some("acme:1")],
+                vec![Some("Tuesday"), Some("acme:2")],
+            ]
+        );
+        // Monday's two hours are split evenly between the two trackers
+        assert_eq!(report.rows[0].duration, Duration::hours(1));
+        let credited = report
+            .rows
+            .iter()
+            .fold(Duration::zero(), |sum, row| sum + row.duration);
+        assert_eq!(credited, report.total);
+    }
+
+    #[test]
+    fn test_group_by_tracker_full_credit() {
+        let (start, end) = range();
+        let allocation = Allocation::new(AllocationStrategy::Full);
+        let report = Report::build(&logs(), start, end, &[Dimension::Tracker], &allocation);
+
+        assert_eq!(report.rows[0].key, vec![Some("acme:1".to_string())]);
+        assert_eq!(report.rows[0].duration, Duration::hours(5));
+        assert_eq!(report.total, Duration::hours(6));
+    }
+
+    #[test]
+    fn test_range_and_week() {
+        let start = date(11);
+        let report = Report::build(
+            &logs(),
+            start,
+            start,
+            &[Dimension::Week],
+            &Allocation::default(),
+        );
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.rows[0].key, vec![Some("2025-W11".to_string())]);
+        assert_eq!(report.total, Duration::hours(3));
+    }
+
+    #[test]
+    fn test_table() {
+        let (start, end) = range();
+        let report = Report::build(
+            &logs(),
+            start,
+            end,
+            &[Dimension::Role],
+            &Allocation::default(),
+        );
+        assert_eq!(
+            report.table(),
+            vec![
+                vec!["role", "sessions", "hours"],
+                vec!["engineer", "2", "5:00"],
+                vec!["manager", "1", "1:00"],
+                vec!["total", "3", "6:00"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_dimension() {
+        assert_eq!("weekday".parse::<Dimension>(), Ok(Dimension::Weekday));
+        assert!("client".parse::<Dimension>().is_err());
+    }
+}
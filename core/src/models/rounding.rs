@@ -0,0 +1,328 @@
+use chrono::{DateTime, Duration, NaiveDate};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::log::Log;
+use crate::models::report::serialize_seconds;
+use crate::models::session::Session;
+
+/// Which way time is rounded to the increment
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    Up,
+    /// Halfway rounds up
+    #[default]
+    Nearest,
+    Down,
+}
+
+/// What is rounded: each session, or each day's total
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingScope {
+    #[default]
+    Session,
+    Day,
+}
+
+impl RoundingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundingMode::Up => "up",
+            RoundingMode::Nearest => "nearest",
+            RoundingMode::Down => "down",
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(RoundingMode::Up),
+            "nearest" => Ok(RoundingMode::Nearest),
+            "down" => Ok(RoundingMode::Down),
+            other => Err(format!(
+                "Unknown rounding mode '{other}', expected up, nearest or down"
+            )),
+        }
+    }
+}
+
+impl RoundingScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundingScope::Session => "session",
+            RoundingScope::Day => "day",
+        }
+    }
+}
+
+impl fmt::Display for RoundingScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RoundingScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "session" => Ok(RoundingScope::Session),
+            "day" => Ok(RoundingScope::Day),
+            other => Err(format!(
+                "Unknown rounding scope '{other}', expected session or day"
+            )),
+        }
+    }
+}
+
+/// A timesheet audience's rounding policy
+///
+/// ```toml
+/// [[timesheet_audience]]
+/// name = "acme"
+/// plugin = "myhours"
+/// rounding = { increment = 15, mode = "up", scope = "day" }
+/// ```
+///
+/// `increment` is in minutes; without one, time is not rounded.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rounding {
+    #[serde(default)]
+    pub increment: u32,
+    #[serde(default)]
+    pub mode: RoundingMode,
+    #[serde(default)]
+    pub scope: RoundingScope,
+}
+
+impl Rounding {
+    pub fn new(increment: u32, mode: RoundingMode) -> Self {
+        Self {
+            increment,
+            mode,
+            scope: RoundingScope::default(),
+        }
+    }
+
+    pub fn with_scope(mut self, scope: RoundingScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Round `duration` to the increment, to the second
+    ///
+    /// Use this for aggregates such as a tracker's weekly total.
+    pub fn round(&self, duration: Duration) -> Duration {
+        let increment = i64::from(self.increment) * 60;
+        if increment == 0 {
+            return duration;
+        }
+        let seconds = duration.num_seconds();
+        let whole = seconds.div_euclid(increment);
+        let remainder = seconds.rem_euclid(increment);
+        let steps = match self.mode {
+            RoundingMode::Up if remainder > 0 => whole + 1,
+            RoundingMode::Nearest if remainder * 2 >= increment => whole + 1,
+            _ => whole,
+        };
+        Duration::seconds(steps * increment)
+    }
+
+    /// Round the closed sessions in `logs` per the policy's scope
+    ///
+    /// With session scope each session is rounded and a day's rounded time
+    /// is the sum of its sessions'. With day scope each day's total is
+    /// rounded and the sessions are left as recorded. Sessions belong to the
+    /// date of the log they're in, even if they run past midnight, as they
+    /// do in reports and charges.
+    pub fn apply(&self, logs: &[Log]) -> RoundedTime {
+        self.apply_dated(
+            logs.iter()
+                .flat_map(|log| log.timeline.iter().map(move |session| (log.date, session))),
+        )
+    }
+
+    /// `apply` over sessions paired with the date each belongs to
+    pub fn apply_dated<'a>(
+        &self,
+        sessions: impl IntoIterator<Item = (NaiveDate, &'a Session)>,
+    ) -> RoundedTime {
+        let mut rounded = RoundedTime {
+            sessions: Vec::new(),
+            days: Vec::new(),
+            raw: Duration::zero(),
+            rounded: Duration::zero(),
+        };
+        for (date, session) in sessions {
+            let Ok(raw) = session.duration() else {
+                continue;
+            };
+            let session_rounded = match self.scope {
+                RoundingScope::Session => self.round(raw),
+                RoundingScope::Day => raw,
+            };
+            rounded.sessions.push(RoundedSession {
+                start: session.start,
+                alias: session.intent.alias.clone(),
+                raw,
+                rounded: session_rounded,
+            });
+
+            match rounded.days.iter_mut().find(|day| day.date == date) {
+                Some(day) => {
+                    day.raw += raw;
+                    day.rounded += session_rounded;
+                }
+                None => rounded.days.push(RoundedDay {
+                    date,
+                    raw,
+                    rounded: session_rounded,
+                }),
+            }
+        }
+
+        rounded.days.sort_by_key(|day| day.date);
+        for day in &mut rounded.days {
+            if self.scope == RoundingScope::Day {
+                day.rounded = self.round(day.raw);
+            }
+            rounded.raw += day.raw;
+            rounded.rounded += day.rounded;
+        }
+        rounded
+    }
+}
+
+/// A session's recorded and rounded time
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RoundedSession {
+    pub start: DateTime<Tz>,
+    pub alias: Option<String>,
+    #[serde(rename = "raw_seconds", serialize_with = "serialize_seconds")]
+    pub raw: Duration,
+    #[serde(rename = "rounded_seconds", serialize_with = "serialize_seconds")]
+    pub rounded: Duration,
+}
+
+/// A day's recorded and rounded time
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RoundedDay {
+    pub date: NaiveDate,
+    #[serde(rename = "raw_seconds", serialize_with = "serialize_seconds")]
+    pub raw: Duration,
+    #[serde(rename = "rounded_seconds", serialize_with = "serialize_seconds")]
+    pub rounded: Duration,
+}
+
+/// Recorded and rounded time side by side, so the difference can be audited
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RoundedTime {
+    pub sessions: Vec<RoundedSession>,
+    pub days: Vec<RoundedDay>,
+    #[serde(rename = "raw_seconds", serialize_with = "serialize_seconds")]
+    pub raw: Duration,
+    #[serde(rename = "rounded_seconds", serialize_with = "serialize_seconds")]
+    pub rounded: Duration,
+}
+
+impl RoundedTime {
+    /// Time added by rounding; negative if rounding took time off
+    pub fn adjustment(&self) -> Duration {
+        self.rounded - self.raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{date, log, session};
+
+    #[test]
+    fn test_round() {
+        let up = Rounding::new(15, RoundingMode::Up);
+        assert_eq!(up.round(Duration::minutes(16)), Duration::minutes(30));
+        assert_eq!(up.round(Duration::minutes(15)), Duration::minutes(15));
+        assert_eq!(up.round(Duration::zero()), Duration::zero());
+
+        let nearest = Rounding::new(6, RoundingMode::Nearest);
+        assert_eq!(nearest.round(Duration::minutes(8)), Duration::minutes(6));
+        assert_eq!(nearest.round(Duration::minutes(9)), Duration::minutes(12));
+
+        let down = Rounding::new(15, RoundingMode::Down);
+        assert_eq!(down.round(Duration::minutes(29)), Duration::minutes(15));
+
+        let none = Rounding::default();
+        assert_eq!(none.round(Duration::seconds(61)), Duration::seconds(61));
+        assert!(none.is_default());
+    }
+
+    fn logs() -> Vec<Log> {
+        vec![
+            log(
+                3,
+                vec![session(3, 9, 10).build(), session(3, 11, 20).build()],
+            ),
+            log(4, vec![session(4, 9, 50).build()]),
+        ]
+    }
+
+    #[test]
+    fn test_session_scope() {
+        let rounded = Rounding::new(15, RoundingMode::Up).apply(&logs());
+
+        assert_eq!(rounded.sessions[0].rounded, Duration::minutes(15));
+        assert_eq!(rounded.days.len(), 2);
+        assert_eq!(rounded.days[0].raw, Duration::minutes(30));
+        assert_eq!(rounded.days[0].rounded, Duration::minutes(45));
+        assert_eq!(rounded.raw, Duration::minutes(80));
+        assert_eq!(rounded.rounded, Duration::minutes(105));
+        assert_eq!(rounded.adjustment(), Duration::minutes(25));
+    }
+
+    #[test]
+    fn test_day_scope() {
+        let rounded = Rounding::new(15, RoundingMode::Up)
+            .with_scope(RoundingScope::Day)
+            .apply(&logs());
+
+        assert_eq!(rounded.sessions[0].rounded, Duration::minutes(10));
+        assert_eq!(rounded.days[0].rounded, Duration::minutes(30));
+        assert_eq!(rounded.days[1].rounded, Duration::minutes(60));
+        assert_eq!(rounded.rounded, Duration::minutes(90));
+    }
+
+    #[test]
+    fn test_sessions_belong_to_their_log_date() {
+        // After midnight, but in the previous day's log
+        let late = log(2, vec![session(3, 0, 10).build()]);
+        let rounded = Rounding::new(15, RoundingMode::Up)
+            .with_scope(RoundingScope::Day)
+            .apply(&[late]);
+
+        assert_eq!(rounded.days.len(), 1);
+        assert_eq!(rounded.days[0].date, date(2));
+    }
+
+    #[test]
+    fn test_config() {
+        let rounding: Rounding = toml::from_str("increment = 6\nmode = \"up\"").unwrap();
+        assert_eq!(rounding, Rounding::new(6, RoundingMode::Up));
+    }
+}
@@ -160,7 +160,12 @@ impl Session {
             })
             .unwrap_or_default();
 
-        let intent: Intent = Intent::new(alias, role, objective, action, subject, trackers);
+        let mut intent: Intent = Intent::new(alias, role, objective, action, subject, trackers);
+        intent.id = dict.get("id").and_then(|v| v.as_string()).cloned();
+        intent.billable = dict
+            .get("billable")
+            .and_then(|v| v.as_string())
+            .and_then(|s| s.parse().ok());
 
         let start: String = dict
             .get("start")
@@ -6,6 +6,8 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
+use crate::models::allocation::{Allocation, TrackerTotals};
+use crate::models::rounding::{RoundedTime, Rounding};
 use crate::models::valuetype::ValueType;
 use crate::models::Session;
 
@@ -84,7 +86,7 @@ pub struct UnsignedTimesheet {
     pub timeline: Vec<Session>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct TimesheetMeta {
     pub audience_id: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -97,16 +99,6 @@ pub struct TimesheetMeta {
     pub submitted_by: Option<String>,
 }
 
-impl Default for TimesheetMeta {
-    fn default() -> Self {
-        Self {
-            audience_id: String::new(),
-            submitted_at: None,
-            submitted_by: None,
-        }
-    }
-}
-
 impl TimesheetMeta {
     pub fn new(
         audience_id: String,
@@ -188,6 +180,19 @@ impl Timesheet {
         }
     }
 
+    /// Time credited to each tracker by the timeline
+    ///
+    /// The same credit audience plugins are given when compiling, so
+    /// timesheets agree with reports.
+    pub fn tracker_totals(&self, allocation: &Allocation) -> TrackerTotals {
+        allocation.tracker_totals(&self.timeline)
+    }
+
+    /// The timeline's time rounded per the audience's `rounding`
+    pub fn rounded(&self, rounding: &Rounding) -> RoundedTime {
+        rounding.apply_dated(self.timeline.iter().map(|session| (self.date, session)))
+    }
+
     fn unsigned(&self) -> UnsignedTimesheet {
         UnsignedTimesheet {
             actor: self.actor.clone(),
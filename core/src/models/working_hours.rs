@@ -0,0 +1,296 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::log::Log;
+use crate::models::report::serialize_seconds;
+
+/// Hours expected on each day of the week
+///
+/// Unset days expect no work.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyHours {
+    #[serde(default)]
+    pub monday: f64,
+    #[serde(default)]
+    pub tuesday: f64,
+    #[serde(default)]
+    pub wednesday: f64,
+    #[serde(default)]
+    pub thursday: f64,
+    #[serde(default)]
+    pub friday: f64,
+    #[serde(default)]
+    pub saturday: f64,
+    #[serde(default)]
+    pub sunday: f64,
+}
+
+impl WeeklyHours {
+    pub fn on(&self, weekday: Weekday) -> f64 {
+        match weekday {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+}
+
+/// Different weekly hours for a stretch of time, e.g. a part-time period
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SchedulePattern {
+    pub from: NaiveDate,
+    /// Last day the pattern applies; open-ended if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+    pub hours: WeeklyHours,
+}
+
+impl SchedulePattern {
+    fn covers(&self, date: NaiveDate) -> bool {
+        date >= self.from && self.until.is_none_or(|until| date <= until)
+    }
+}
+
+/// The `[schedule]` section of the config
+///
+/// ```toml
+/// [schedule]
+/// hours = { monday = 8, tuesday = 8, wednesday = 8, thursday = 8, friday = 8 }
+/// holidays = ["2025-12-25", "2025-12-26"]
+///
+/// [[schedule.pattern]]
+/// from = "2026-01-01"
+/// hours = { monday = 6, tuesday = 6, wednesday = 6 }
+/// ```
+///
+/// Where patterns overlap, the last one listed applies.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(default)]
+    pub hours: WeeklyHours,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holidays: Vec<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pattern: Vec<SchedulePattern>,
+}
+
+impl Schedule {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Time expected to be worked on `date`
+    pub fn expected(&self, date: NaiveDate) -> Duration {
+        if self.holidays.contains(&date) {
+            return Duration::zero();
+        }
+        let hours = self
+            .pattern
+            .iter()
+            .rev()
+            .find(|pattern| pattern.covers(date))
+            .map_or(&self.hours, |pattern| &pattern.hours);
+        Duration::seconds((hours.on(date.weekday()) * 3600.0).round() as i64)
+    }
+}
+
+/// Expected and recorded time over one day or one week
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Balance {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    #[serde(rename = "expected_seconds", serialize_with = "serialize_seconds")]
+    pub expected: Duration,
+    #[serde(rename = "recorded_seconds", serialize_with = "serialize_seconds")]
+    pub recorded: Duration,
+    /// Running total of recorded minus expected time, from the start of the
+    /// range to the end of this day or week
+    #[serde(rename = "balance_seconds", serialize_with = "serialize_seconds")]
+    pub balance: Duration,
+}
+
+impl Balance {
+    /// Recorded time beyond what was expected
+    pub fn overtime(&self) -> Duration {
+        (self.recorded - self.expected).max(Duration::zero())
+    }
+
+    /// Expected time that was not recorded
+    pub fn undertime(&self) -> Duration {
+        (self.expected - self.recorded).max(Duration::zero())
+    }
+}
+
+/// Expected versus recorded hours over a date range
+///
+/// Overtime and undertime are worked out per day in `days` and per ISO week
+/// in `weeks`, so a long Monday and a short Tuesday show as both overtime and
+/// undertime by day but may cancel out over the week.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WorkingHours {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<Balance>,
+    pub weeks: Vec<Balance>,
+    #[serde(rename = "expected_seconds", serialize_with = "serialize_seconds")]
+    pub expected: Duration,
+    #[serde(rename = "recorded_seconds", serialize_with = "serialize_seconds")]
+    pub recorded: Duration,
+    #[serde(rename = "balance_seconds", serialize_with = "serialize_seconds")]
+    pub balance: Duration,
+}
+
+impl WorkingHours {
+    /// Compare `schedule` with the time recorded in logs dated `start..=end`
+    ///
+    /// Days without a log count as nothing recorded. Recorded time comes
+    /// from `Log::total_recorded_time`, so an open session counts up to now.
+    pub fn calculate(
+        schedule: &Schedule,
+        logs: &[Log],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Self> {
+        let logs: HashMap<NaiveDate, &Log> = logs.iter().map(|log| (log.date, log)).collect();
+
+        let mut days = Vec::new();
+        let mut weeks: Vec<Balance> = Vec::new();
+        let mut balance = Duration::zero();
+        for date in start.iter_days().take_while(|date| *date <= end) {
+            let expected = schedule.expected(date);
+            let recorded = match logs.get(&date) {
+                Some(log) => log
+                    .total_recorded_time()
+                    .with_context(|| format!("Failed to total recorded time on {date}"))?,
+                None => Duration::zero(),
+            };
+            balance += recorded - expected;
+            days.push(Balance {
+                start: date,
+                end: date,
+                expected,
+                recorded,
+                balance,
+            });
+
+            match weeks.last_mut() {
+                Some(week) if date.iso_week() == week.start.iso_week() => {
+                    week.end = date;
+                    week.expected += expected;
+                    week.recorded += recorded;
+                    week.balance = balance;
+                }
+                _ => weeks.push(Balance {
+                    start: date,
+                    end: date,
+                    expected,
+                    recorded,
+                    balance,
+                }),
+            }
+        }
+
+        let expected = days
+            .iter()
+            .fold(Duration::zero(), |sum, d| sum + d.expected);
+        let recorded = days
+            .iter()
+            .fold(Duration::zero(), |sum, d| sum + d.recorded);
+        Ok(Self {
+            start,
+            end,
+            days,
+            weeks,
+            expected,
+            recorded,
+            balance,
+        })
+    }
+
+    /// Overtime summed over the days
+    pub fn overtime(&self) -> Duration {
+        self.days
+            .iter()
+            .fold(Duration::zero(), |sum, d| sum + d.overtime())
+    }
+
+    /// Undertime summed over the days
+    pub fn undertime(&self) -> Duration {
+        self.days
+            .iter()
+            .fold(Duration::zero(), |sum, d| sum + d.undertime())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{date, log, session};
+
+    fn worked(day: u32, hours: i64) -> Log {
+        log(day, vec![session(day, 9, hours * 60).build()])
+    }
+
+    fn schedule() -> Schedule {
+        toml::from_str(
+            r#"
+            hours = { monday = 8, tuesday = 8, wednesday = 8, thursday = 8, friday = 8 }
+            holidays = ["2025-03-14"]
+
+            [[pattern]]
+            from = "2025-03-17"
+            until = "2025-03-23"
+            hours = { monday = 4, tuesday = 4 }
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_expected() {
+        let schedule = schedule();
+        // Monday
+        assert_eq!(schedule.expected(date(10)), Duration::hours(8));
+        // Saturday
+        assert_eq!(schedule.expected(date(15)), Duration::zero());
+        // Holiday on a Friday
+        assert_eq!(schedule.expected(date(14)), Duration::zero());
+        // Part-time week
+        assert_eq!(schedule.expected(date(17)), Duration::hours(4));
+        assert_eq!(schedule.expected(date(19)), Duration::zero());
+        // Pattern over
+        assert_eq!(schedule.expected(date(24)), Duration::hours(8));
+        assert!(Schedule::default().is_default());
+    }
+
+    #[test]
+    fn test_calculate() {
+        let logs = vec![worked(10, 10), worked(11, 6), worked(17, 5)];
+        let hours = WorkingHours::calculate(&schedule(), &logs, date(10), date(18)).unwrap();
+
+        assert_eq!(hours.days.len(), 9);
+        assert_eq!(hours.days[0].overtime(), Duration::hours(2));
+        assert_eq!(hours.days[1].undertime(), Duration::hours(2));
+        assert_eq!(hours.days[1].balance, Duration::zero());
+
+        // Week of the 10th: 32 hours expected (Friday is a holiday), 16 recorded
+        assert_eq!(hours.weeks.len(), 2);
+        assert_eq!(hours.weeks[0].expected, Duration::hours(32));
+        assert_eq!(hours.weeks[0].recorded, Duration::hours(16));
+        assert_eq!(hours.weeks[0].undertime(), Duration::hours(16));
+        assert_eq!(hours.weeks[1].start, date(17));
+        assert_eq!(hours.weeks[1].end, date(18));
+
+        assert_eq!(hours.expected, Duration::hours(40));
+        assert_eq!(hours.recorded, Duration::hours(21));
+        assert_eq!(hours.balance, Duration::hours(-19));
+        assert_eq!(hours.overtime(), Duration::hours(3));
+        assert_eq!(hours.undertime(), Duration::hours(22));
+    }
+}
@@ -0,0 +1,98 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDelta};
+
+use crate::models::budget::BudgetStatus as RustBudgetStatus;
+use crate::type_mapping;
+
+/// The Python-visible BudgetStatus class
+#[pyclass(name = "BudgetStatus")]
+#[derive(Clone)]
+pub struct PyBudgetStatus {
+    pub inner: RustBudgetStatus,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBudgetStatus>()?;
+    Ok(())
+}
+
+#[pymethods]
+impl PyBudgetStatus {
+    #[getter]
+    fn label(&self) -> String {
+        self.inner.label.clone()
+    }
+
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.inner.kind.as_str()
+    }
+
+    #[getter]
+    fn period(&self) -> &'static str {
+        self.inner.period.as_str()
+    }
+
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.start)
+    }
+
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.end)
+    }
+
+    #[getter]
+    fn budget<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.budget)
+    }
+
+    #[getter]
+    fn consumed<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.consumed)
+    }
+
+    #[getter]
+    fn remaining<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.remaining)
+    }
+
+    #[getter]
+    fn projected<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.projected)
+    }
+
+    #[getter]
+    fn overrun<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDelta>>> {
+        self.inner
+            .overrun()
+            .map(|d| type_mapping::duration_rust_to_py(py, &d))
+            .transpose()
+    }
+
+    #[getter]
+    fn projected_overrun<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDelta>>> {
+        self.inner
+            .projected_overrun()
+            .map(|d| type_mapping::duration_rust_to_py(py, &d))
+            .transpose()
+    }
+
+    #[getter]
+    fn warning(&self) -> Option<String> {
+        self.inner.warning()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BudgetStatus(label={}, kind={}, start={}, end={}, consumed={}, budget={})",
+            self.inner.label,
+            self.inner.kind.as_str(),
+            self.inner.start,
+            self.inner.end,
+            crate::models::report::format_hours(self.inner.consumed),
+            crate::models::report::format_hours(self.inner.budget)
+        )
+    }
+}
@@ -1,9 +1,14 @@
+use crate::models::allocation::{Allocation as RustAllocation, AllocationStrategy, TrackerTotals};
 use crate::models::config::{
     Config as RustConfig, PlanDefaults as RustPlanDefaults, PlanRemote as RustPlanRemote,
     Role as RustRole, TimesheetAudience as RustTimesheetAudience,
 };
+use crate::py_models::rounding::PyRounding;
+use crate::py_models::session::PySession;
+use crate::type_mapping;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyType};
+use pyo3::types::{PyDelta, PyDict, PyType};
+use std::collections::HashMap;
 
 #[pyclass(name = "Config")]
 #[derive(Clone)]
@@ -35,12 +40,41 @@ pub struct PyRole {
     pub inner: RustRole,
 }
 
+#[pyclass(name = "Allocation")]
+#[derive(Clone)]
+pub struct PyAllocation {
+    pub inner: RustAllocation,
+}
+
+/// Time credited to each tracker, with time in sessions without trackers
+/// keyed by None
+pub fn tracker_totals_to_py<'py>(
+    py: Python<'py>,
+    totals: &TrackerTotals,
+) -> PyResult<HashMap<Option<String>, Bound<'py, PyDelta>>> {
+    let mut result = HashMap::new();
+    for (tracker, duration) in &totals.trackers {
+        result.insert(
+            Some(tracker.clone()),
+            type_mapping::duration_rust_to_py(py, duration)?,
+        );
+    }
+    if !totals.untracked.is_zero() {
+        result.insert(
+            None,
+            type_mapping::duration_rust_to_py(py, &totals.untracked)?,
+        );
+    }
+    Ok(result)
+}
+
 pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyConfig>()?;
     m.add_class::<PyPlanRemote>()?;
     m.add_class::<PyPlanDefaults>()?;
     m.add_class::<PyTimesheetAudience>()?;
     m.add_class::<PyRole>()?;
+    m.add_class::<PyAllocation>()?;
     Ok(())
 }
 
@@ -87,6 +121,13 @@ impl PyConfig {
             .collect()
     }
 
+    #[getter]
+    fn allocation(&self) -> PyAllocation {
+        PyAllocation {
+            inner: self.inner.allocation.clone(),
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Config(timezone={}, plan_remotes={}, audiences={}, roles={})",
@@ -189,6 +230,19 @@ impl PyTimesheetAudience {
         self.inner.signing_ids.clone()
     }
 
+    /// Which sessions the audience sees: "all", "billable" or "non-billable"
+    #[getter]
+    fn include(&self) -> String {
+        self.inner.include.to_string()
+    }
+
+    #[getter]
+    fn rounding(&self) -> PyRounding {
+        PyRounding {
+            inner: self.inner.rounding.clone(),
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "TimesheetAudience(name={}, plugin={})",
@@ -213,7 +267,59 @@ impl PyRole {
         })
     }
 
+    #[getter]
+    fn billable(&self) -> Option<bool> {
+        self.inner.billable
+    }
+
     fn __repr__(&self) -> String {
         format!("Role(name={})", self.inner.name)
     }
 }
+
+#[pymethods]
+impl PyAllocation {
+    #[new]
+    #[pyo3(signature = (strategy="even", weights=None))]
+    fn py_new(strategy: &str, weights: Option<HashMap<String, f64>>) -> PyResult<Self> {
+        let strategy: AllocationStrategy = strategy
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(Self {
+            inner: RustAllocation {
+                strategy,
+                weights: weights.unwrap_or_default(),
+            },
+        })
+    }
+
+    #[getter]
+    fn strategy(&self) -> String {
+        self.inner.strategy.to_string()
+    }
+
+    #[getter]
+    fn weights(&self) -> HashMap<String, f64> {
+        self.inner.weights.clone()
+    }
+
+    /// Time credited to each tracker by the closed sessions
+    ///
+    /// Time in sessions without trackers is keyed by None.
+    fn tracker_totals<'py>(
+        &self,
+        py: Python<'py>,
+        sessions: Vec<PySession>,
+    ) -> PyResult<HashMap<Option<String>, Bound<'py, PyDelta>>> {
+        let totals = self.inner.tracker_totals(sessions.iter().map(|s| &s.inner));
+        tracker_totals_to_py(py, &totals)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Allocation(strategy={}, weights={})",
+            self.inner.strategy,
+            self.inner.weights.len()
+        )
+    }
+}
@@ -0,0 +1,127 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDelta};
+
+use crate::import::{ImportDay as RustImportDay, ImportReport as RustImportReport};
+use crate::py_models::session::PySession;
+use crate::type_mapping;
+
+/// The Python-visible ImportReport class
+#[pyclass(name = "ImportReport")]
+#[derive(Clone)]
+pub struct PyImportReport {
+    pub inner: RustImportReport,
+}
+
+/// The Python-visible ImportDay class
+#[pyclass(name = "ImportDay")]
+#[derive(Clone)]
+pub struct PyImportDay {
+    pub inner: RustImportDay,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyImportReport>()?;
+    m.add_class::<PyImportDay>()?;
+    Ok(())
+}
+
+fn wrap(session: &crate::models::Session) -> PySession {
+    PySession {
+        inner: session.clone(),
+    }
+}
+
+#[pymethods]
+impl PyImportReport {
+    #[getter]
+    fn dry_run(&self) -> bool {
+        self.inner.dry_run
+    }
+
+    #[getter]
+    fn added(&self) -> Vec<PySession> {
+        self.inner.added.iter().map(wrap).collect()
+    }
+
+    #[getter]
+    fn duplicates(&self) -> Vec<PySession> {
+        self.inner.duplicates.iter().map(wrap).collect()
+    }
+
+    #[getter]
+    fn overlaps(&self) -> Vec<(PySession, PySession)> {
+        self.inner
+            .overlaps
+            .iter()
+            .map(|(imported, existing)| (wrap(imported), wrap(existing)))
+            .collect()
+    }
+
+    #[getter]
+    fn skipped(&self) -> Vec<(String, String)> {
+        self.inner
+            .skipped
+            .iter()
+            .map(|skip| (skip.title.clone(), skip.reason.clone()))
+            .collect()
+    }
+
+    #[getter]
+    fn days(&self) -> Vec<PyImportDay> {
+        self.inner
+            .days
+            .iter()
+            .map(|day| PyImportDay { inner: day.clone() })
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ImportReport(dry_run={}, added={}, duplicates={}, overlaps={}, skipped={})",
+            self.inner.dry_run,
+            self.inner.added.len(),
+            self.inner.duplicates.len(),
+            self.inner.overlaps.len(),
+            self.inner.skipped.len()
+        )
+    }
+}
+
+#[pymethods]
+impl PyImportDay {
+    #[getter]
+    fn date<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.date)
+    }
+
+    #[getter]
+    fn added(&self) -> usize {
+        self.inner.added
+    }
+
+    #[getter]
+    fn duplicates(&self) -> usize {
+        self.inner.duplicates
+    }
+
+    #[getter]
+    fn overlaps(&self) -> usize {
+        self.inner.overlaps
+    }
+
+    #[getter]
+    fn recorded<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.recorded)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ImportDay(date={}, added={}, duplicates={}, overlaps={}, recorded={}s)",
+            self.inner.date,
+            self.inner.added,
+            self.inner.duplicates,
+            self.inner.overlaps,
+            self.inner.recorded.num_seconds()
+        )
+    }
+}
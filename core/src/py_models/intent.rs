@@ -24,7 +24,8 @@ pub(crate) fn intent_from_dict_internal(dict: &Bound<'_, PyDict>) -> PyResult<Py
 #[pymethods]
 impl PyIntent {
     #[new]
-    #[pyo3(signature = (alias=None, role=None, objective=None, action=None, subject=None, trackers=vec![]))]
+    #[pyo3(signature = (alias=None, role=None, objective=None, action=None, subject=None, trackers=vec![], id=None, billable=None))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         alias: Option<String>,
         role: Option<String>,
@@ -32,10 +33,13 @@ impl PyIntent {
         action: Option<String>,
         subject: Option<String>,
         trackers: Vec<String>,
+        id: Option<String>,
+        billable: Option<bool>,
     ) -> Self {
-        Self {
-            inner: RustIntent::new(alias, role, objective, action, subject, trackers),
-        }
+        let mut inner = RustIntent::new(alias, role, objective, action, subject, trackers);
+        inner.id = id;
+        inner.billable = billable;
+        Self { inner }
     }
 
     #[getter]
@@ -68,6 +72,28 @@ impl PyIntent {
         self.inner.trackers.clone()
     }
 
+    #[getter]
+    fn id(&self) -> Option<String> {
+        self.inner.id.clone()
+    }
+
+    #[getter]
+    fn billable(&self) -> Option<bool> {
+        self.inner.billable
+    }
+
+    /// Copy of this intent marked billable or not, or unmarked with None
+    fn with_billable(&self, billable: Option<bool>) -> PyIntent {
+        PyIntent {
+            inner: self.inner.with_billable(billable),
+        }
+    }
+
+    /// The explicit id if set, otherwise one derived from the fields
+    fn effective_id(&self) -> String {
+        self.inner.effective_id()
+    }
+
     #[classmethod]
     fn from_dict(_cls: &Bound<'_, PyType>, dict: &Bound<'_, PyAny>) -> PyResult<Self> {
         let py_dict = dict.downcast::<PyDict>()?;
@@ -104,13 +130,14 @@ impl PyIntent {
 
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!(
-            "Intent(alias={:?}, role={:?}, objective={:?}, action={:?}, subject={:?}, trackers={:?})",
+            "Intent(alias={:?}, role={:?}, objective={:?}, action={:?}, subject={:?}, trackers={:?}, id={:?})",
             self.inner.alias,
             self.inner.role,
             self.inner.objective,
             self.inner.action,
             self.inner.subject,
             self.inner.trackers,
+            self.inner.id,
         ))
     }
 
@@ -120,6 +147,7 @@ impl PyIntent {
 
     // XXX: I'm acutely aware that I do not know what this is or how it works.
     // It _is_ needed, however.
+    #[allow(clippy::type_complexity)]
     fn __reduce__(
         &self,
         py: Python,
@@ -132,6 +160,7 @@ impl PyIntent {
             Option<String>,
             Option<String>,
             Vec<String>,
+            Option<String>,
         ),
     )> {
         let intent_type = py.get_type::<Self>();
@@ -144,6 +173,7 @@ impl PyIntent {
                 self.inner.action.clone(),
                 self.inner.subject.clone(),
                 self.inner.trackers.clone(),
+                self.inner.id.clone(),
             ),
         ))
     }
@@ -0,0 +1,60 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDate;
+
+use crate::models::intent_search::IntentMatch as RustIntentMatch;
+use crate::py_models::intent::PyIntent;
+use crate::type_mapping;
+
+/// The Python-visible IntentMatch class
+#[pyclass(name = "IntentMatch")]
+#[derive(Clone)]
+pub struct PyIntentMatch {
+    pub inner: RustIntentMatch,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyIntentMatch>()?;
+    Ok(())
+}
+
+#[pymethods]
+impl PyIntentMatch {
+    #[getter]
+    fn intent(&self) -> PyIntent {
+        PyIntent {
+            inner: self.inner.intent.clone(),
+        }
+    }
+
+    #[getter]
+    fn score(&self) -> f64 {
+        self.inner.score
+    }
+
+    #[getter]
+    fn matched_fields(&self) -> Vec<String> {
+        self.inner.matched_fields.clone()
+    }
+
+    #[getter]
+    fn last_used<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDate>>> {
+        match &self.inner.last_used {
+            Some(date) => Ok(Some(type_mapping::date_rust_to_py(py, date)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "IntentMatch(alias={:?}, score={:.3}, matched_fields={:?}, last_used={:?})",
+            self.inner.intent.alias,
+            self.inner.score,
+            self.inner.matched_fields,
+            self.inner.last_used,
+        ))
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        self.__repr__()
+    }
+}
@@ -0,0 +1,124 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDelta};
+
+use crate::models::intent_stats::{
+    IntentStats as RustIntentStats, IntentSuggestions as RustIntentSuggestions,
+};
+use crate::py_models::intent::PyIntent;
+use crate::type_mapping;
+
+/// The Python-visible IntentStats class
+#[pyclass(name = "IntentStats")]
+#[derive(Clone)]
+pub struct PyIntentStats {
+    pub inner: RustIntentStats,
+}
+
+/// The Python-visible IntentSuggestions class
+#[pyclass(name = "IntentSuggestions")]
+#[derive(Clone)]
+pub struct PyIntentSuggestions {
+    pub inner: RustIntentSuggestions,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyIntentStats>()?;
+    m.add_class::<PyIntentSuggestions>()?;
+    Ok(())
+}
+
+fn wrap_all(stats: &[RustIntentStats]) -> Vec<PyIntentStats> {
+    stats
+        .iter()
+        .map(|s| PyIntentStats { inner: s.clone() })
+        .collect()
+}
+
+#[pymethods]
+impl PyIntentStats {
+    #[getter]
+    fn intent(&self) -> PyIntent {
+        PyIntent {
+            inner: self.inner.intent.clone(),
+        }
+    }
+
+    #[getter]
+    fn session_count(&self) -> usize {
+        self.inner.session_count
+    }
+
+    #[getter]
+    fn total_duration<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.total_duration)
+    }
+
+    #[getter]
+    fn week_session_count(&self) -> usize {
+        self.inner.week_session_count
+    }
+
+    #[getter]
+    fn week_duration<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.week_duration)
+    }
+
+    #[getter]
+    fn starts_near_now(&self) -> usize {
+        self.inner.starts_near_now
+    }
+
+    #[getter]
+    fn last_used<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDate>>> {
+        self.inner
+            .last_used
+            .map(|d| type_mapping::date_rust_to_py(py, &d))
+            .transpose()
+    }
+
+    #[getter]
+    fn last_used_on_weekday<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDate>>> {
+        self.inner
+            .last_used_on_weekday
+            .map(|d| type_mapping::date_rust_to_py(py, &d))
+            .transpose()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "IntentStats(alias={:?}, session_count={}, week_session_count={}, starts_near_now={}, last_used={:?})",
+            self.inner.intent.alias,
+            self.inner.session_count,
+            self.inner.week_session_count,
+            self.inner.starts_near_now,
+            self.inner.last_used,
+        ))
+    }
+}
+
+#[pymethods]
+impl PyIntentSuggestions {
+    #[getter]
+    fn most_used_this_week(&self) -> Vec<PyIntentStats> {
+        wrap_all(&self.inner.most_used_this_week)
+    }
+
+    #[getter]
+    fn usual_at_this_time(&self) -> Vec<PyIntentStats> {
+        wrap_all(&self.inner.usual_at_this_time)
+    }
+
+    #[getter]
+    fn last_used_on_weekday(&self) -> Vec<PyIntentStats> {
+        wrap_all(&self.inner.last_used_on_weekday)
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "IntentSuggestions(most_used_this_week={}, usual_at_this_time={}, last_used_on_weekday={})",
+            self.inner.most_used_this_week.len(),
+            self.inner.usual_at_this_time.len(),
+            self.inner.last_used_on_weekday.len(),
+        ))
+    }
+}
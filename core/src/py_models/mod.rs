@@ -1,7 +1,16 @@
+pub mod budget;
 pub mod config;
+pub mod import;
 pub mod intent;
+pub mod intent_search;
+pub mod intent_stats;
 pub mod log;
 pub mod plan;
+pub mod plan_lint;
+pub mod rate;
+pub mod report;
+pub mod rounding;
 pub mod session;
 pub mod timesheet;
 pub mod toy;
+pub mod working_hours;
@@ -21,6 +21,7 @@ pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
 #[pymethods]
 impl PyPlan {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (source, valid_from, valid_until=None, roles=vec![], actions=vec![], objectives=vec![], subjects=vec![], trackers=None, intents=vec![]))]
     fn py_new(
         source: String,
@@ -211,6 +212,18 @@ impl PyPlan {
         self.inner.id()
     }
 
+    #[getter]
+    fn billable(&self) -> HashMap<String, bool> {
+        self.inner.billable.clone()
+    }
+
+    /// Mark a tracker billable or not, or clear the mark with None
+    fn set_billable(&self, id: &str, billable: Option<bool>) -> PyPlan {
+        PyPlan {
+            inner: self.inner.set_billable(id, billable),
+        }
+    }
+
     fn add_intent(&self, intent: PyIntent) -> PyPlan {
         PyPlan {
             inner: self.inner.add_intent(intent.inner),
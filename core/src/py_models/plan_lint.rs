@@ -0,0 +1,86 @@
+use pyo3::prelude::*;
+
+use crate::models::plan_lint::{
+    PlanDiagnostic as RustPlanDiagnostic, PlanFileReport as RustPlanFileReport,
+};
+
+/// The Python-visible PlanDiagnostic class
+#[pyclass(name = "PlanDiagnostic")]
+#[derive(Clone)]
+pub struct PyPlanDiagnostic {
+    pub inner: RustPlanDiagnostic,
+}
+
+/// The Python-visible PlanFileReport class
+#[pyclass(name = "PlanFileReport")]
+#[derive(Clone)]
+pub struct PyPlanFileReport {
+    pub inner: RustPlanFileReport,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPlanDiagnostic>()?;
+    m.add_class::<PyPlanFileReport>()?;
+    Ok(())
+}
+
+#[pymethods]
+impl PyPlanDiagnostic {
+    /// "error" or "warning"
+    #[getter]
+    fn severity(&self) -> String {
+        self.inner.severity.to_string()
+    }
+
+    #[getter]
+    fn code(&self) -> String {
+        self.inner.code.clone()
+    }
+
+    #[getter]
+    fn message(&self) -> String {
+        self.inner.message.clone()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "PlanDiagnostic(severity={:?}, code={:?}, message={:?})",
+            self.inner.severity.to_string(),
+            self.inner.code,
+            self.inner.message,
+        ))
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        Ok(self.inner.to_string())
+    }
+}
+
+#[pymethods]
+impl PyPlanFileReport {
+    #[getter]
+    fn path(&self) -> String {
+        self.inner.path.to_string_lossy().to_string()
+    }
+
+    #[getter]
+    fn diagnostics(&self) -> Vec<PyPlanDiagnostic> {
+        self.inner
+            .diagnostics
+            .iter()
+            .map(|d| PyPlanDiagnostic { inner: d.clone() })
+            .collect()
+    }
+
+    fn has_errors(&self) -> bool {
+        self.inner.has_errors()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "PlanFileReport(path={:?}, diagnostics={})",
+            self.inner.path,
+            self.inner.diagnostics.len(),
+        ))
+    }
+}
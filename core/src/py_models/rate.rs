@@ -0,0 +1,114 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDelta};
+use std::collections::BTreeMap;
+
+use crate::models::rate::{Charge as RustCharge, Charges as RustCharges};
+use crate::type_mapping;
+
+/// The Python-visible Charge class
+#[pyclass(name = "Charge")]
+#[derive(Clone)]
+pub struct PyCharge {
+    pub inner: RustCharge,
+}
+
+/// The Python-visible Charges class
+#[pyclass(name = "Charges")]
+#[derive(Clone)]
+pub struct PyCharges {
+    pub inner: RustCharges,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCharge>()?;
+    m.add_class::<PyCharges>()?;
+    Ok(())
+}
+
+#[pymethods]
+impl PyCharge {
+    #[getter]
+    fn date<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.date)
+    }
+
+    #[getter]
+    fn rate(&self) -> String {
+        self.inner.rate.clone()
+    }
+
+    #[getter]
+    fn currency(&self) -> String {
+        self.inner.currency.clone()
+    }
+
+    #[getter]
+    fn hourly(&self) -> f64 {
+        self.inner.hourly
+    }
+
+    #[getter]
+    fn raw<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.raw)
+    }
+
+    #[getter]
+    fn rounded<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.rounded)
+    }
+
+    #[getter]
+    fn amount(&self) -> f64 {
+        self.inner.amount
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Charge(date={}, rate={}, amount={:.2} {})",
+            self.inner.date, self.inner.rate, self.inner.amount, self.inner.currency
+        )
+    }
+}
+
+#[pymethods]
+impl PyCharges {
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.inner.kind.as_str()
+    }
+
+    #[getter]
+    fn charges(&self) -> Vec<PyCharge> {
+        self.inner
+            .charges
+            .iter()
+            .cloned()
+            .map(|inner| PyCharge { inner })
+            .collect()
+    }
+
+    /// Total amount by currency
+    #[getter]
+    fn totals(&self) -> BTreeMap<String, f64> {
+        self.inner.totals.clone()
+    }
+
+    #[getter]
+    fn unrated<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.unrated)
+    }
+
+    fn __repr__(&self) -> String {
+        let totals: Vec<String> = self
+            .inner
+            .totals
+            .iter()
+            .map(|(currency, total)| format!("{total:.2} {currency}"))
+            .collect();
+        format!(
+            "Charges(kind={}, totals=[{}])",
+            self.inner.kind,
+            totals.join(", ")
+        )
+    }
+}
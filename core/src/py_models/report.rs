@@ -0,0 +1,108 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDelta};
+
+use crate::models::report::{Report as RustReport, ReportRow as RustReportRow};
+use crate::type_mapping;
+
+/// The Python-visible Report class
+#[pyclass(name = "Report")]
+#[derive(Clone)]
+pub struct PyReport {
+    pub inner: RustReport,
+}
+
+/// The Python-visible ReportRow class
+#[pyclass(name = "ReportRow")]
+#[derive(Clone)]
+pub struct PyReportRow {
+    pub inner: RustReportRow,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyReport>()?;
+    m.add_class::<PyReportRow>()?;
+    Ok(())
+}
+
+#[pymethods]
+impl PyReport {
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.start)
+    }
+
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.end)
+    }
+
+    #[getter]
+    fn group_by(&self) -> Vec<String> {
+        self.inner
+            .group_by
+            .iter()
+            .map(|d| d.as_str().to_string())
+            .collect()
+    }
+
+    #[getter]
+    fn rows(&self) -> Vec<PyReportRow> {
+        self.inner
+            .rows
+            .iter()
+            .map(|r| PyReportRow { inner: r.clone() })
+            .collect()
+    }
+
+    #[getter]
+    fn sessions(&self) -> usize {
+        self.inner.sessions
+    }
+
+    #[getter]
+    fn total<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.total)
+    }
+
+    /// Render as rows of strings: a header, one row per group and a total row
+    fn table(&self) -> Vec<Vec<String>> {
+        self.inner.table()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Report(start={}, end={}, rows={}, total={})",
+            self.inner.start,
+            self.inner.end,
+            self.inner.rows.len(),
+            crate::models::report::format_hours(self.inner.total)
+        )
+    }
+}
+
+#[pymethods]
+impl PyReportRow {
+    #[getter]
+    fn key(&self) -> Vec<Option<String>> {
+        self.inner.key.clone()
+    }
+
+    #[getter]
+    fn sessions(&self) -> usize {
+        self.inner.sessions
+    }
+
+    #[getter]
+    fn duration<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.duration)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ReportRow(key={:?}, sessions={}, duration={})",
+            self.inner.key,
+            self.inner.sessions,
+            crate::models::report::format_hours(self.inner.duration)
+        )
+    }
+}
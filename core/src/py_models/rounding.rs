@@ -0,0 +1,157 @@
+use chrono::Duration;
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDateTime, PyDelta};
+
+use crate::models::rounding::{
+    RoundedTime as RustRoundedTime, Rounding as RustRounding, RoundingMode, RoundingScope,
+};
+use crate::py_models::log::PyLog;
+use crate::type_mapping;
+
+/// The Python-visible Rounding class
+#[pyclass(name = "Rounding")]
+#[derive(Clone)]
+pub struct PyRounding {
+    pub inner: RustRounding,
+}
+
+/// The Python-visible RoundedTime class
+#[pyclass(name = "RoundedTime")]
+#[derive(Clone)]
+pub struct PyRoundedTime {
+    pub inner: RustRoundedTime,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyRounding>()?;
+    m.add_class::<PyRoundedTime>()?;
+    Ok(())
+}
+
+#[pymethods]
+impl PyRounding {
+    #[new]
+    #[pyo3(signature = (increment=0, mode="nearest", scope="session"))]
+    fn py_new(increment: u32, mode: &str, scope: &str) -> PyResult<Self> {
+        let mode: RoundingMode = mode
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let scope: RoundingScope = scope
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(Self {
+            inner: RustRounding::new(increment, mode).with_scope(scope),
+        })
+    }
+
+    #[getter]
+    fn increment(&self) -> u32 {
+        self.inner.increment
+    }
+
+    #[getter]
+    fn mode(&self) -> String {
+        self.inner.mode.to_string()
+    }
+
+    #[getter]
+    fn scope(&self) -> String {
+        self.inner.scope.to_string()
+    }
+
+    /// Round a duration, such as an aggregate, to the increment
+    fn round<'py>(
+        &self,
+        py: Python<'py>,
+        duration: Bound<'py, PyDelta>,
+    ) -> PyResult<Bound<'py, PyDelta>> {
+        let seconds: f64 = duration.call_method0("total_seconds")?.extract()?;
+        let rounded = self.inner.round(Duration::seconds(seconds.round() as i64));
+        type_mapping::duration_rust_to_py(py, &rounded)
+    }
+
+    /// Round the closed sessions in the logs per the policy's scope
+    ///
+    /// Sessions belong to the date of the log they're in.
+    fn apply(&self, logs: Vec<PyLog>) -> PyRoundedTime {
+        let logs: Vec<_> = logs.into_iter().map(|log| log.inner).collect();
+        PyRoundedTime {
+            inner: self.inner.apply(&logs),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Rounding(increment={}, mode={}, scope={})",
+            self.inner.increment, self.inner.mode, self.inner.scope
+        )
+    }
+}
+
+type RoundedSessionTuple<'py> = (
+    Bound<'py, PyDateTime>,
+    Option<String>,
+    Bound<'py, PyDelta>,
+    Bound<'py, PyDelta>,
+);
+
+type RoundedDayTuple<'py> = (Bound<'py, PyDate>, Bound<'py, PyDelta>, Bound<'py, PyDelta>);
+
+#[pymethods]
+impl PyRoundedTime {
+    /// (start, alias, raw, rounded) for each closed session
+    #[getter]
+    fn sessions<'py>(&self, py: Python<'py>) -> PyResult<Vec<RoundedSessionTuple<'py>>> {
+        self.inner
+            .sessions
+            .iter()
+            .map(|s| {
+                Ok((
+                    type_mapping::datetime_rust_to_py(py, &s.start)?,
+                    s.alias.clone(),
+                    type_mapping::duration_rust_to_py(py, &s.raw)?,
+                    type_mapping::duration_rust_to_py(py, &s.rounded)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// (date, raw, rounded) for each day
+    #[getter]
+    fn days<'py>(&self, py: Python<'py>) -> PyResult<Vec<RoundedDayTuple<'py>>> {
+        self.inner
+            .days
+            .iter()
+            .map(|d| {
+                Ok((
+                    type_mapping::date_rust_to_py(py, &d.date)?,
+                    type_mapping::duration_rust_to_py(py, &d.raw)?,
+                    type_mapping::duration_rust_to_py(py, &d.rounded)?,
+                ))
+            })
+            .collect()
+    }
+
+    #[getter]
+    fn raw<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.raw)
+    }
+
+    #[getter]
+    fn rounded<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.rounded)
+    }
+
+    #[getter]
+    fn adjustment<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.adjustment())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RoundedTime(raw={}s, rounded={}s)",
+            self.inner.raw.num_seconds(),
+            self.inner.rounded.num_seconds()
+        )
+    }
+}
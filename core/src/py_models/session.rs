@@ -81,7 +81,7 @@ pub(crate) fn session_from_dict_internal(
     }
 
     let inner = RustSession::from_dict_with_tz(data, date, tz)
-        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e))?;
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
 
     Ok(PySession { inner })
 }
@@ -209,7 +209,7 @@ impl PySession {
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
 
         let inner = RustSession::from_dict_with_tz(data, date, tz)
-            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e))?;
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
 
         Ok(Self { inner })
     }
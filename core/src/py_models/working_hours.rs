@@ -0,0 +1,138 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDelta};
+
+use crate::models::working_hours::{Balance as RustBalance, WorkingHours as RustWorkingHours};
+use crate::type_mapping;
+
+/// The Python-visible WorkingHours class
+#[pyclass(name = "WorkingHours")]
+#[derive(Clone)]
+pub struct PyWorkingHours {
+    pub inner: RustWorkingHours,
+}
+
+/// The Python-visible Balance class
+#[pyclass(name = "Balance")]
+#[derive(Clone)]
+pub struct PyBalance {
+    pub inner: RustBalance,
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWorkingHours>()?;
+    m.add_class::<PyBalance>()?;
+    Ok(())
+}
+
+fn wrap_all(balances: &[RustBalance]) -> Vec<PyBalance> {
+    balances
+        .iter()
+        .map(|b| PyBalance { inner: b.clone() })
+        .collect()
+}
+
+#[pymethods]
+impl PyWorkingHours {
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.start)
+    }
+
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.end)
+    }
+
+    #[getter]
+    fn days(&self) -> Vec<PyBalance> {
+        wrap_all(&self.inner.days)
+    }
+
+    #[getter]
+    fn weeks(&self) -> Vec<PyBalance> {
+        wrap_all(&self.inner.weeks)
+    }
+
+    #[getter]
+    fn expected<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.expected)
+    }
+
+    #[getter]
+    fn recorded<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.recorded)
+    }
+
+    #[getter]
+    fn overtime<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.overtime())
+    }
+
+    #[getter]
+    fn undertime<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.undertime())
+    }
+
+    #[getter]
+    fn balance<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.balance)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "WorkingHours(start={}, end={}, expected={}, recorded={})",
+            self.inner.start,
+            self.inner.end,
+            crate::models::report::format_hours(self.inner.expected),
+            crate::models::report::format_hours(self.inner.recorded)
+        )
+    }
+}
+
+#[pymethods]
+impl PyBalance {
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.start)
+    }
+
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        type_mapping::date_rust_to_py(py, &self.inner.end)
+    }
+
+    #[getter]
+    fn expected<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.expected)
+    }
+
+    #[getter]
+    fn recorded<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.recorded)
+    }
+
+    #[getter]
+    fn overtime<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.overtime())
+    }
+
+    #[getter]
+    fn undertime<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.undertime())
+    }
+
+    #[getter]
+    fn balance<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        type_mapping::duration_rust_to_py(py, &self.inner.balance)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Balance(start={}, end={}, expected={}, recorded={})",
+            self.inner.start,
+            self.inner.end,
+            crate::models::report::format_hours(self.inner.expected),
+            crate::models::report::format_hours(self.inner.recorded)
+        )
+    }
+}
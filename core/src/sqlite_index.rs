@@ -0,0 +1,1073 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::managers::{plan_helpers, LogManager, PlanManager, TimesheetManager};
+use crate::models::{Log, Plan, Session, Timesheet, TimesheetMeta};
+use crate::storage::{Storage, VersionToken};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS logs (
+        date TEXT PRIMARY KEY,
+        timezone TEXT NOT NULL,
+        version TEXT
+    );
+    CREATE TABLE IF NOT EXISTS sessions (
+        date TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        start_utc INTEGER NOT NULL,
+        end_utc INTEGER,
+        intent_id TEXT NOT NULL,
+        alias TEXT,
+        role TEXT,
+        objective TEXT,
+        action TEXT,
+        subject TEXT,
+        session TEXT NOT NULL,
+        PRIMARY KEY (date, position)
+    );
+    CREATE INDEX IF NOT EXISTS sessions_by_start ON sessions (start_utc);
+    CREATE INDEX IF NOT EXISTS sessions_by_intent ON sessions (intent_id);
+    CREATE TABLE IF NOT EXISTS session_trackers (
+        date TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        tracker TEXT NOT NULL,
+        PRIMARY KEY (date, position, tracker)
+    );
+    CREATE INDEX IF NOT EXISTS session_trackers_by_tracker ON session_trackers (tracker);
+    CREATE TABLE IF NOT EXISTS plan_files (
+        source TEXT NOT NULL,
+        file_date TEXT NOT NULL,
+        plan TEXT NOT NULL,
+        version TEXT,
+        PRIMARY KEY (source, file_date)
+    );
+    CREATE TABLE IF NOT EXISTS timesheets (
+        audience_id TEXT NOT NULL,
+        date TEXT NOT NULL,
+        timesheet TEXT NOT NULL,
+        meta TEXT NOT NULL,
+        version TEXT,
+        PRIMARY KEY (audience_id, date)
+    );
+";
+
+/// Optional SQLite mirror of logs, sessions, plans and timesheets
+///
+/// The TOML and JSON files stay the source of truth. Managers given an index
+/// with `with_index` update it on every write, and `rebuild` recreates it from
+/// storage, e.g. after files were changed by hand or by a sync. Range queries
+/// and reports then avoid reading and parsing every file.
+///
+/// Each log, plan and timesheet is indexed with the version token of the
+/// file it came from, so the managers can tell when a file has changed
+/// outside the process (another faff instance, an editor, a git checkout)
+/// and re-index it before serving it from the index.
+pub struct SqliteIndex {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteIndex {
+    /// Open (or create) an index database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open index: {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    /// Open a throwaway index held in memory
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory().context("Failed to open index")?)
+    }
+
+    /// Where a workspace keeps its index: `index.sqlite` next to the config file
+    pub fn default_path(storage: &dyn Storage) -> PathBuf {
+        let config_file = storage.config_file();
+        config_file
+            .parent()
+            .map(|dir| dir.join("index.sqlite"))
+            .unwrap_or_else(|| PathBuf::from("index.sqlite"))
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create index schema")?;
+        // Indexes created before rows had versions; their rows count as stale
+        for table in ["logs", "plan_files", "timesheets"] {
+            let has_versions = conn
+                .prepare(&format!(
+                    "SELECT 1 FROM pragma_table_info('{table}') WHERE name = 'version'"
+                ))?
+                .exists([])?;
+            if !has_versions {
+                conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN version TEXT"))
+                    .context("Failed to upgrade index schema")?;
+            }
+        }
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Replace the index contents with everything currently in storage
+    pub fn rebuild(&self, storage: Arc<dyn Storage>) -> Result<()> {
+        // Logs carry their own timezone, so the manager's default is unused
+        let log_manager = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let plan_manager = PlanManager::new(storage.clone());
+        let timesheet_manager = TimesheetManager::new(storage.clone());
+
+        let mut logs = Vec::new();
+        for date in log_manager.list_logs()? {
+            let (log, version) = log_manager.get_log_versioned(date)?;
+            if version.is_some() {
+                logs.push((log, version));
+            }
+        }
+        let mut plans = Vec::new();
+        for (source, file_date) in plan_manager.list_plans()? {
+            // Versions are taken before reading, so a file changed in between
+            // is re-read rather than indexed with stale contents
+            let version = plan_manager.plan_version(&source, file_date)?;
+            if let Some(plan) = plan_manager.get_plan(&source, file_date)? {
+                plans.push((source, file_date, plan, version));
+            }
+        }
+        let mut timesheets = Vec::new();
+        for (audience_id, date) in timesheet_manager.list_timesheet_keys(None)? {
+            let version = timesheet_manager.timesheet_version(&audience_id, date)?;
+            if let Some(timesheet) = timesheet_manager.get_timesheet(&audience_id, date)? {
+                timesheets.push((timesheet, version));
+            }
+        }
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute_batch(
+            "DELETE FROM logs; DELETE FROM sessions; DELETE FROM session_trackers;
+             DELETE FROM plan_files; DELETE FROM timesheets;",
+        )?;
+        for (log, version) in &logs {
+            insert_log(&tx, log, version.as_ref())?;
+        }
+        for (source, file_date, plan, version) in &plans {
+            insert_plan(&tx, source, *file_date, plan, version.as_ref())?;
+        }
+        for (timesheet, version) in &timesheets {
+            insert_timesheet(&tx, timesheet, version.as_ref())?;
+        }
+        tx.commit().context("Failed to rebuild index")
+    }
+
+    /// Add or replace a log and its sessions
+    ///
+    /// `version` is that of the file the log was read from or written to;
+    /// without one, the log is re-read the next time its range is.
+    pub fn index_log(&self, log: &Log, version: Option<&VersionToken>) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        delete_log(&tx, log.date)?;
+        insert_log(&tx, log, version)?;
+        tx.commit()
+            .with_context(|| format!("Failed to index log for {}", log.date))
+    }
+
+    /// Remove a log and its sessions
+    pub fn remove_log(&self, date: NaiveDate) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        delete_log(&tx, date)?;
+        tx.commit()
+            .with_context(|| format!("Failed to remove log for {} from index", date))
+    }
+
+    /// Add or replace the plan in the file for `source` dated `file_date`
+    ///
+    /// Plans are keyed by file, like `PlanManager` stores them. `version` is
+    /// that of the file; without one, the file is re-read the next time
+    /// plans are.
+    pub fn index_plan(
+        &self,
+        source: &str,
+        file_date: NaiveDate,
+        plan: &Plan,
+        version: Option<&VersionToken>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        insert_plan(&conn, source, file_date, plan, version)
+            .with_context(|| format!("Failed to index plan {}", source))
+    }
+
+    /// Remove the plan file for `source` dated `file_date`
+    pub fn remove_plan(&self, source: &str, file_date: NaiveDate) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM plan_files WHERE source = ?1 AND file_date = ?2",
+            params![source, file_date],
+        )
+        .with_context(|| format!("Failed to remove plan {} from index", source))?;
+        Ok(())
+    }
+
+    /// Add or replace a timesheet
+    ///
+    /// `version` is that of its files, as `TimesheetManager::timesheet_version`
+    /// gives it; without one, the timesheet is re-read the next time it's listed.
+    pub fn index_timesheet(
+        &self,
+        timesheet: &Timesheet,
+        version: Option<&VersionToken>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        insert_timesheet(&conn, timesheet, version).with_context(|| {
+            format!(
+                "Failed to index timesheet for {} on {}",
+                timesheet.meta.audience_id, timesheet.date
+            )
+        })
+    }
+
+    /// Remove a timesheet
+    pub fn remove_timesheet(&self, audience_id: &str, date: NaiveDate) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM timesheets WHERE audience_id = ?1 AND date = ?2",
+            params![audience_id, date],
+        )
+        .with_context(|| format!("Failed to remove timesheet for {} from index", audience_id))?;
+        Ok(())
+    }
+
+    /// Dates with a log in the inclusive range `start..=end`, sorted
+    pub fn log_dates(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<NaiveDate>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT date FROM logs WHERE date BETWEEN ?1 AND ?2 ORDER BY date")?;
+        let dates = stmt
+            .query_map(params![start, end], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<NaiveDate>>>()?;
+        Ok(dates)
+    }
+
+    /// Version tokens of the indexed logs dated `start..=end`
+    ///
+    /// Logs indexed without a version are left out.
+    pub fn log_versions(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<HashMap<NaiveDate, VersionToken>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, version FROM logs
+             WHERE date BETWEEN ?1 AND ?2 AND version IS NOT NULL",
+        )?;
+        let versions = stmt
+            .query_map(params![start, end], |row| {
+                Ok((row.get(0)?, VersionToken::new(row.get(1)?)))
+            })?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        Ok(versions)
+    }
+
+    /// Version tokens of the indexed plan files, keyed by source and file date
+    ///
+    /// Plans indexed without a version are left out.
+    pub fn plan_versions(&self) -> Result<HashMap<(String, NaiveDate), VersionToken>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT source, file_date, version FROM plan_files WHERE version IS NOT NULL",
+        )?;
+        let versions = stmt
+            .query_map([], |row| {
+                Ok(((row.get(0)?, row.get(1)?), VersionToken::new(row.get(2)?)))
+            })?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        Ok(versions)
+    }
+
+    /// Version tokens of the indexed timesheets, optionally for one date,
+    /// keyed by audience and date
+    ///
+    /// Timesheets indexed without a version are left out.
+    pub fn timesheet_versions(
+        &self,
+        date: Option<NaiveDate>,
+    ) -> Result<HashMap<(String, NaiveDate), VersionToken>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT audience_id, date, version FROM timesheets
+             WHERE (?1 IS NULL OR date = ?1) AND version IS NOT NULL",
+        )?;
+        let versions = stmt
+            .query_map(params![date], |row| {
+                Ok(((row.get(0)?, row.get(1)?), VersionToken::new(row.get(2)?)))
+            })?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        Ok(versions)
+    }
+
+    /// Logs dated `start..=end`, sorted by date, as `LogManager::get_logs_between` returns them
+    pub fn logs_between(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Log>> {
+        let timezones: Vec<(NaiveDate, String)> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT date, timezone FROM logs WHERE date BETWEEN ?1 AND ?2 ORDER BY date",
+            )?;
+            let rows = stmt
+                .query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            rows
+        };
+        let mut sessions = self.sessions_between(start, end)?.into_iter().peekable();
+
+        timezones
+            .into_iter()
+            .map(|(date, timezone)| {
+                let timezone: Tz = timezone
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid timezone in index: {}", e))?;
+                let mut timeline = Vec::new();
+                while let Some((_, session)) = sessions.next_if(|(d, _)| *d == date) {
+                    timeline.push(session);
+                }
+                Ok(Log::new(date, timezone, timeline))
+            })
+            .collect()
+    }
+
+    /// Sessions from logs dated `start..=end`, in log order, with their log date
+    ///
+    /// Times are in each log's timezone, as `LogManager` would return them.
+    pub fn sessions_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Session)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT s.date, l.timezone, s.session FROM sessions s JOIN logs l ON l.date = s.date
+             WHERE s.date BETWEEN ?1 AND ?2 ORDER BY s.date, s.position",
+        )?;
+        let rows = stmt
+            .query_map(params![start, end], |row| {
+                Ok((
+                    row.get::<_, NaiveDate>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(date, timezone, json)| {
+                let timezone: Tz = timezone
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid timezone in index: {}", e))?;
+                let mut session: Session = serde_json::from_str(&json)
+                    .with_context(|| format!("Corrupt session in index for {}", date))?;
+                session.start = session.start.with_timezone(&timezone);
+                session.end = session.end.map(|end| end.with_timezone(&timezone));
+                Ok((date, session))
+            })
+            .collect()
+    }
+
+    /// Plans valid on `date`, keyed by source, as `PlanManager::get_plans` returns them
+    ///
+    /// Like `PlanManager`, takes each source's most recent file dated on or
+    /// before `date` by its name, then checks the plan's own validity dates.
+    pub fn plans_on(&self, date: NaiveDate) -> Result<HashMap<String, Plan>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT p.plan FROM plan_files p
+             WHERE p.file_date = (SELECT MAX(file_date) FROM plan_files
+                                  WHERE source = p.source AND file_date <= ?1)",
+        )?;
+        let rows = stmt
+            .query_map(params![date], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut plans = HashMap::new();
+        for toml_str in rows {
+            let plan: Plan = toml::from_str(&toml_str).context("Corrupt plan in index")?;
+            plan_helpers::insert_if_valid(&mut plans, plan, date);
+        }
+        Ok(plans)
+    }
+
+    /// Timesheets dated `start..=end`, optionally for one audience, sorted by date
+    pub fn timesheets_between(
+        &self,
+        audience_id: Option<&str>,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Timesheet>> {
+        self.select_timesheets(
+            "WHERE date BETWEEN ?1 AND ?2 AND (?3 IS NULL OR audience_id = ?3)",
+            params![start, end, audience_id],
+        )
+    }
+
+    /// Every timesheet, sorted by date
+    pub fn timesheets(&self) -> Result<Vec<Timesheet>> {
+        self.select_timesheets("", params![])
+    }
+
+    fn select_timesheets(
+        &self,
+        filter: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<Timesheet>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT timesheet, meta FROM timesheets {filter} ORDER BY date, audience_id"
+        ))?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.iter()
+            .map(|(timesheet_json, meta_json)| {
+                let mut timesheet: Timesheet =
+                    serde_json::from_str(timesheet_json).context("Corrupt timesheet in index")?;
+                timesheet.meta = serde_json::from_str::<TimesheetMeta>(meta_json)
+                    .context("Corrupt timesheet metadata in index")?;
+                Ok(timesheet)
+            })
+            .collect()
+    }
+}
+
+fn delete_log(conn: &Connection, date: NaiveDate) -> Result<()> {
+    conn.execute("DELETE FROM logs WHERE date = ?1", params![date])?;
+    conn.execute("DELETE FROM sessions WHERE date = ?1", params![date])?;
+    conn.execute(
+        "DELETE FROM session_trackers WHERE date = ?1",
+        params![date],
+    )?;
+    Ok(())
+}
+
+fn insert_log(conn: &Connection, log: &Log, version: Option<&VersionToken>) -> Result<()> {
+    conn.execute(
+        "INSERT INTO logs (date, timezone, version) VALUES (?1, ?2, ?3)",
+        params![
+            log.date,
+            log.timezone.name(),
+            version.map(VersionToken::to_string)
+        ],
+    )?;
+
+    for (position, session) in log.timeline.iter().enumerate() {
+        let intent = &session.intent;
+        conn.execute(
+            "INSERT INTO sessions (date, position, start_utc, end_utc, intent_id,
+                                   alias, role, objective, action, subject, session)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                log.date,
+                position,
+                session.start.timestamp(),
+                session.end.as_ref().map(DateTime::timestamp),
+                intent.effective_id(),
+                intent.alias,
+                intent.role,
+                intent.objective,
+                intent.action,
+                intent.subject,
+                serde_json::to_string(session)?,
+            ],
+        )?;
+        for tracker in &intent.trackers {
+            conn.execute(
+                "INSERT OR IGNORE INTO session_trackers (date, position, tracker)
+                 VALUES (?1, ?2, ?3)",
+                params![log.date, position, tracker],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_plan(
+    conn: &Connection,
+    source: &str,
+    file_date: NaiveDate,
+    plan: &Plan,
+    version: Option<&VersionToken>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO plan_files (source, file_date, plan, version)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            source,
+            file_date,
+            plan.to_toml()?,
+            version.map(VersionToken::to_string)
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_timesheet(
+    conn: &Connection,
+    timesheet: &Timesheet,
+    version: Option<&VersionToken>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO timesheets (audience_id, date, timesheet, meta, version)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            timesheet.meta.audience_id,
+            timesheet.date,
+            serde_json::to_string(timesheet)?,
+            serde_json::to_string(&timesheet.meta)?,
+            version.map(VersionToken::to_string),
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system_storage::FileSystemStorage;
+    use crate::memory_storage::MemoryStorage;
+    use crate::models::Intent;
+    use chrono::{Duration, TimeZone};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    fn log(day: u32, sessions: &[(&str, &[&str], u32, Option<u32>)]) -> Log {
+        let tz = chrono_tz::Europe::London;
+        let timeline = sessions
+            .iter()
+            .map(|(alias, trackers, start, end)| {
+                let intent = Intent::new(
+                    Some(alias.to_string()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    trackers.iter().map(|t| t.to_string()).collect(),
+                );
+                Session::new(
+                    intent,
+                    tz.with_ymd_and_hms(2025, 3, day, *start, 0, 0).unwrap(),
+                    end.map(|end| tz.with_ymd_and_hms(2025, 3, day, end, 0, 0).unwrap()),
+                    None,
+                )
+            })
+            .collect();
+        Log::new(date(day), tz, timeline)
+    }
+
+    /// A timesheet for "acme" on `date(day)`, compiled at `hour` the next day
+    fn timesheet(day: u32, hour: u32) -> Timesheet {
+        Timesheet::new(
+            HashMap::new(),
+            date(day),
+            chrono_tz::UTC
+                .with_ymd_and_hms(2025, 3, day + 1, hour, 0, 0)
+                .unwrap(),
+            chrono_tz::UTC,
+            vec![],
+            HashMap::new(),
+            TimesheetMeta::new("acme".to_string(), None, None),
+        )
+    }
+
+    #[test]
+    fn test_session_queries_and_reports() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+        index
+            .index_log(&log(10, &[("Email", &[], 9, Some(10))]), None)
+            .unwrap();
+        index
+            .index_log(
+                &log(
+                    11,
+                    &[
+                        ("Code", &["work:1", "work:2"], 9, Some(12)),
+                        ("Email", &[], 12, Some(13)),
+                        ("Code", &[], 14, None),
+                    ],
+                ),
+                None,
+            )
+            .unwrap();
+        index
+            .index_log(&log(20, &[("Email", &[], 9, Some(10))]), None)
+            .unwrap();
+
+        assert_eq!(
+            index.log_dates(date(10), date(19)).unwrap(),
+            vec![date(10), date(11)]
+        );
+
+        let sessions = index.sessions_between(date(11), date(11)).unwrap();
+        assert_eq!(sessions.len(), 3);
+        // Times come back in the log's timezone
+        let expected = &log(11, &[("Code", &[], 9, Some(12))]).timeline[0];
+        assert_eq!(sessions[0].1.start, expected.start);
+        assert_eq!(sessions[0].1.start.timezone(), chrono_tz::Europe::London);
+        assert_eq!(sessions[0].1.end, expected.end);
+        assert_eq!(sessions[2].1.end, None);
+
+        // Re-indexing replaces the log's sessions; removing drops them
+        index.index_log(&log(11, &[]), None).unwrap();
+        assert!(index
+            .sessions_between(date(11), date(11))
+            .unwrap()
+            .is_empty());
+        index.remove_log(date(10)).unwrap();
+        assert_eq!(
+            index.log_dates(date(1), date(31)).unwrap(),
+            vec![date(11), date(20)]
+        );
+    }
+
+    #[test]
+    fn test_managers_keep_index_up_to_date() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let index = Arc::new(SqliteIndex::open_in_memory().unwrap());
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC).with_index(index.clone());
+        let plans = PlanManager::new(storage.clone()).with_index(index.clone());
+
+        let plan = plans.add_tracker(date(1), "work:1", "Work").unwrap();
+        assert_eq!(index.plans_on(date(15)).unwrap()["local"], plan);
+        assert!(index
+            .plans_on(date(1) - Duration::days(1))
+            .unwrap()
+            .is_empty());
+
+        let now = chrono_tz::UTC
+            .with_ymd_and_hms(2025, 3, 15, 9, 0, 0)
+            .unwrap();
+        logs.start_intent_now(
+            Intent::new(Some("Code".to_string()), None, None, None, None, vec![]),
+            None,
+            date(15),
+            now,
+            &plans.get_trackers(date(15)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(index.sessions_between(date(15), date(15)).unwrap().len(), 1);
+
+        logs.delete_log(date(15)).unwrap();
+        assert!(index.log_dates(date(15), date(15)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rebuild_from_storage() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC);
+        let plans = PlanManager::new(storage.clone());
+        let timesheets = TimesheetManager::new(storage.clone());
+
+        logs.write_log(&log(10, &[("Email", &[], 9, Some(10))]), &HashMap::new())
+            .unwrap();
+        plans.add_tracker(date(1), "work:1", "Work").unwrap();
+        let timesheet = timesheet(10, 9);
+        timesheets.write_timesheet(&timesheet).unwrap();
+
+        let index = SqliteIndex::open_in_memory().unwrap();
+        index.index_log(&log(3, &[]), None).unwrap();
+        index.rebuild(storage).unwrap();
+
+        assert_eq!(index.log_dates(date(1), date(31)).unwrap(), vec![date(10)]);
+        assert_eq!(index.plans_on(date(10)).unwrap().len(), 1);
+        assert_eq!(
+            index
+                .timesheets_between(Some("acme"), date(1), date(31))
+                .unwrap(),
+            vec![timesheet]
+        );
+        assert!(index
+            .timesheets_between(Some("other"), date(1), date(31))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_managers_read_through_index() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let index = Arc::new(SqliteIndex::open_in_memory().unwrap());
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC).with_index(index.clone());
+        let timesheets = TimesheetManager::new(storage.clone()).with_index(index.clone());
+
+        // Indexed at the stored file's version but with other sessions, so
+        // the log manager can only have found them in the index
+        logs.write_log(&log(10, &[]), &HashMap::new()).unwrap();
+        let version = storage.version(&storage.log_file_path(date(10))).unwrap();
+        let indexed = log(10, &[("Email", &[], 9, Some(10))]);
+        index.index_log(&indexed, version.as_ref()).unwrap();
+        // Likewise indexed at the stored files' version, but compiled later
+        timesheets.write_timesheet(&timesheet(10, 9)).unwrap();
+        let version = timesheets.timesheet_version("acme", date(10)).unwrap();
+        let timesheet = timesheet(10, 17);
+        index.index_timesheet(&timesheet, version.as_ref()).unwrap();
+
+        assert_eq!(
+            logs.get_logs_between(date(1), date(31)).unwrap(),
+            vec![indexed]
+        );
+        assert_eq!(
+            timesheets.list_timesheets(Some(date(10))).unwrap(),
+            vec![timesheet.clone()]
+        );
+        assert_eq!(timesheets.list_timesheets(None).unwrap(), vec![timesheet]);
+        assert!(timesheets
+            .list_timesheets(Some(date(11)))
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Storage that counts the files read through it
+    struct CountingStorage {
+        inner: FileSystemStorage,
+        reads: AtomicUsize,
+    }
+
+    impl CountingStorage {
+        fn reads(&self) -> usize {
+            self.reads.swap(0, Ordering::SeqCst)
+        }
+
+        fn count(&self) {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl Storage for CountingStorage {
+        fn root_dir(&self) -> PathBuf {
+            self.inner.root_dir()
+        }
+
+        fn log_dir(&self) -> PathBuf {
+            self.inner.log_dir()
+        }
+
+        fn plan_dir(&self) -> PathBuf {
+            self.inner.plan_dir()
+        }
+
+        fn identity_dir(&self) -> PathBuf {
+            self.inner.identity_dir()
+        }
+
+        fn timesheet_dir(&self) -> PathBuf {
+            self.inner.timesheet_dir()
+        }
+
+        fn config_file(&self) -> PathBuf {
+            self.inner.config_file()
+        }
+
+        fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+            self.count();
+            self.inner.read_bytes(path)
+        }
+
+        fn read_string(&self, path: &Path) -> Result<String> {
+            self.count();
+            self.inner.read_string(path)
+        }
+
+        fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
+            self.inner.write_bytes(path, data)
+        }
+
+        fn write_string(&self, path: &Path, data: &str) -> Result<()> {
+            self.inner.write_string(path, data)
+        }
+
+        fn delete(&self, path: &Path) -> Result<()> {
+            self.inner.delete(path)
+        }
+
+        fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+            self.inner.version(path)
+        }
+
+        fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+            self.count();
+            self.inner.read_string_versioned(path)
+        }
+
+        fn write_string_if(
+            &self,
+            path: &Path,
+            data: &str,
+            expected: Option<&VersionToken>,
+        ) -> Result<VersionToken> {
+            self.inner.write_string_if(path, data, expected)
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            self.inner.exists(path)
+        }
+
+        fn create_dir_all(&self, path: &Path) -> Result<()> {
+            self.inner.create_dir_all(path)
+        }
+
+        fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+            self.inner.list_files(dir, pattern)
+        }
+    }
+
+    #[test]
+    fn test_warm_range_reads_skip_unchanged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".faff")).unwrap();
+        let storage = Arc::new(CountingStorage {
+            inner: FileSystemStorage::from_path(dir.path().to_path_buf()).unwrap(),
+            reads: AtomicUsize::new(0),
+        });
+        let index = Arc::new(SqliteIndex::open_in_memory().unwrap());
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC).with_index(index);
+        for day in [10, 11, 12] {
+            logs.write_log(&log(day, &[]), &HashMap::new()).unwrap();
+        }
+
+        storage.reads();
+        assert_eq!(logs.get_logs_between(date(1), date(31)).unwrap().len(), 3);
+        assert_eq!(storage.reads(), 0);
+
+        // Only the file changed by hand is read again
+        let edited = log(11, &[("Email", &[], 9, Some(10))]);
+        std::fs::write(
+            storage.log_file_path(date(11)),
+            edited.to_log_file(&HashMap::new()),
+        )
+        .unwrap();
+        assert_eq!(logs.get_logs_between(date(1), date(31)).unwrap()[1], edited);
+        assert_eq!(storage.reads(), 1);
+    }
+
+    #[test]
+    fn test_stale_logs_are_reindexed() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let index = Arc::new(SqliteIndex::open_in_memory().unwrap());
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC).with_index(index.clone());
+        logs.write_log(&log(10, &[]), &HashMap::new()).unwrap();
+        logs.write_log(&log(11, &[]), &HashMap::new()).unwrap();
+
+        // Changed, added and removed behind the manager's back
+        let edited = log(10, &[("Email", &[], 9, Some(10))]);
+        let added = log(12, &[("Code", &[], 9, Some(11))]);
+        for log in [&edited, &added] {
+            storage
+                .write_string(
+                    &storage.log_file_path(log.date),
+                    &log.to_log_file(&HashMap::new()),
+                )
+                .unwrap();
+        }
+        storage.delete(&storage.log_file_path(date(11))).unwrap();
+
+        assert_eq!(
+            logs.get_logs_between(date(1), date(31)).unwrap(),
+            vec![edited, added]
+        );
+        assert_eq!(
+            index.log_dates(date(1), date(31)).unwrap(),
+            vec![date(10), date(12)]
+        );
+    }
+
+    #[test]
+    fn test_unparseable_logs_fail_reads() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let index = Arc::new(SqliteIndex::open_in_memory().unwrap());
+        let logs = LogManager::new(storage.clone(), chrono_tz::UTC).with_index(index.clone());
+        logs.write_log(&log(10, &[]), &HashMap::new()).unwrap();
+
+        logs.write_log_raw(date(10), "not a log").unwrap();
+        let err = logs.get_logs_between(date(1), date(31)).unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to parse log file"));
+
+        // Also when the file was broken outside the manager
+        logs.write_log(&log(10, &[]), &HashMap::new()).unwrap();
+        storage
+            .write_string(&storage.log_file_path(date(10)), "not a log")
+            .unwrap();
+        assert!(logs.get_logs_between(date(1), date(31)).is_err());
+    }
+
+    #[test]
+    fn test_stale_plans_are_reindexed() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let index = Arc::new(SqliteIndex::open_in_memory().unwrap());
+        let plans = PlanManager::new(storage.clone()).with_index(index.clone());
+        plans.add_tracker(date(1), "work:1", "Work").unwrap();
+        let other = plans.add_tracker(date(5), "work:2", "Other").unwrap();
+
+        // Edited and broken behind the manager's back
+        let mut edited = plans.get_plan("local", date(1)).unwrap().unwrap();
+        edited
+            .trackers
+            .insert("work:3".to_string(), "Edited".to_string());
+        storage
+            .write_string(
+                &storage.plan_file_path("local", date(1)),
+                &edited.to_toml().unwrap(),
+            )
+            .unwrap();
+        assert_eq!(plans.get_plans(date(3)).unwrap()["local"], edited);
+
+        let broken = storage.plan_file_path("local", date(5));
+        storage.write_string(&broken, "not a plan").unwrap();
+        let (loaded, skipped) = plans.get_plans_with_diagnostics(date(10)).unwrap();
+        assert!(loaded.is_empty());
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, broken);
+
+        // Repaired, then removed
+        storage
+            .write_string(&broken, &other.to_toml().unwrap())
+            .unwrap();
+        assert_eq!(plans.get_plans(date(10)).unwrap()["local"], other);
+        storage.delete(&broken).unwrap();
+        assert_eq!(plans.get_plans(date(10)).unwrap()["local"], edited);
+        assert_eq!(index.plan_versions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_stale_timesheets_are_reindexed() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let index = Arc::new(SqliteIndex::open_in_memory().unwrap());
+        let timesheets = TimesheetManager::new(storage.clone()).with_index(index.clone());
+        timesheets.write_timesheet(&timesheet(10, 9)).unwrap();
+        timesheets.write_timesheet(&timesheet(11, 9)).unwrap();
+
+        // Recompiled and deleted behind the manager's back
+        let recompiled = timesheet(10, 17);
+        TimesheetManager::new(storage.clone())
+            .write_timesheet(&recompiled)
+            .unwrap();
+        storage
+            .delete(&storage.timesheet_file_path("acme", date(11)))
+            .unwrap();
+
+        assert_eq!(timesheets.list_timesheets(None).unwrap(), vec![recompiled]);
+
+        // Metadata changes alone count too
+        let mut submitted = timesheets.get_timesheet("acme", date(10)).unwrap().unwrap();
+        submitted.meta.submitted_at = Some(submitted.compiled);
+        storage
+            .write_bytes(
+                &storage.timesheet_meta_file_path("acme", date(10)),
+                &serde_json::to_vec(&submitted.meta).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            timesheets.list_timesheets(Some(date(10))).unwrap(),
+            vec![submitted]
+        );
+    }
+
+    #[test]
+    fn test_plans_on_picks_by_file_date() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let plan = |valid_from| {
+            Plan::new(
+                "local".to_string(),
+                valid_from,
+                None,
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                HashMap::new(),
+                vec![],
+            )
+        };
+        // A file dated after the plan's valid_from, and one dated before it
+        for (file_date, valid_from) in [(date(5), date(1)), (date(20), date(25))] {
+            storage
+                .write_string(
+                    &storage.plan_file_path("local", file_date),
+                    &plan(valid_from).to_toml().unwrap(),
+                )
+                .unwrap();
+        }
+
+        let index = SqliteIndex::open_in_memory().unwrap();
+        index.rebuild(storage.clone()).unwrap();
+        let plans = PlanManager::new(storage);
+
+        for day in [3, 5, 15, 22, 28] {
+            assert_eq!(
+                index.plans_on(date(day)).unwrap(),
+                plans.get_plans(date(day)).unwrap(),
+                "plans on day {}",
+                day
+            );
+        }
+        assert!(index.plans_on(date(3)).unwrap().is_empty());
+        assert!(index.plans_on(date(22)).unwrap().is_empty());
+        assert_eq!(
+            index.plans_on(date(28)).unwrap()["local"].valid_from,
+            date(25)
+        );
+    }
+
+    #[test]
+    fn test_index_persists_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.sqlite");
+        SqliteIndex::open(&path)
+            .unwrap()
+            .index_log(&log(10, &[]), None)
+            .unwrap();
+
+        let reopened = SqliteIndex::open(&path).unwrap();
+        assert_eq!(
+            reopened.log_dates(date(1), date(31)).unwrap(),
+            vec![date(10)]
+        );
+    }
+
+    #[test]
+    fn test_index_without_versions_is_upgraded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.sqlite");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE logs (date TEXT PRIMARY KEY, timezone TEXT NOT NULL);
+                 INSERT INTO logs VALUES ('2025-03-10', 'UTC');
+                 CREATE TABLE plan_files (source TEXT NOT NULL, file_date TEXT NOT NULL,
+                                          plan TEXT NOT NULL, PRIMARY KEY (source, file_date));
+                 INSERT INTO plan_files VALUES ('local', '2025-03-01', '');
+                 CREATE TABLE timesheets (audience_id TEXT NOT NULL, date TEXT NOT NULL,
+                                          timesheet TEXT NOT NULL, meta TEXT NOT NULL,
+                                          PRIMARY KEY (audience_id, date));
+                 INSERT INTO timesheets VALUES ('acme', '2025-03-10', '', '');",
+            )
+            .unwrap();
+
+        let index = SqliteIndex::open(&path).unwrap();
+        // Rows indexed before versions were recorded count as stale
+        assert!(index.log_versions(date(1), date(31)).unwrap().is_empty());
+        assert!(index.plan_versions().unwrap().is_empty());
+        assert!(index.timesheet_versions(None).unwrap().is_empty());
+        let version = VersionToken::of(b"log");
+        index.index_log(&log(11, &[]), Some(&version)).unwrap();
+        assert_eq!(
+            index.log_versions(date(1), date(31)).unwrap(),
+            HashMap::from([(date(11), version)])
+        );
+    }
+}
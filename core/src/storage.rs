@@ -1,6 +1,74 @@
 use anyhow::Result;
 use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::layout;
+
+/// Opaque identifier for one version of a file's contents
+///
+/// Returned alongside reads and passed back to conditional writes, so a
+/// caller can tell whether a file changed between reading and writing it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VersionToken(String);
+
+impl VersionToken {
+    pub fn new(token: String) -> Self {
+        Self(token)
+    }
+
+    /// Token derived from file contents (SHA-256)
+    ///
+    /// Content hashes work for any backing store, including ones that don't
+    /// expose modification times.
+    pub fn of(data: &[u8]) -> Self {
+        Self(hex::encode(Sha256::digest(data)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for VersionToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A conditional write found the file changed since it was read
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{} was changed by another process since it was read", path.display())]
+pub struct VersionConflict {
+    pub path: PathBuf,
+    /// The version the caller read, or None if the file didn't exist
+    pub expected: Option<VersionToken>,
+    /// The version now in storage, or None if the file has been deleted
+    pub actual: Option<VersionToken>,
+}
+
+/// Guard for an advisory lock on a workspace, released when dropped
+///
+/// Held by managers around read-modify-write operations so that the CLI, an
+/// editor plugin and scheduled plan pulls don't overwrite each other's changes.
+pub struct StorageLock {
+    _guard: Option<Box<dyn Send>>,
+}
+
+impl StorageLock {
+    /// Wrap whatever keeps the lock held (e.g. a locked file handle)
+    pub fn new(guard: impl Send + 'static) -> Self {
+        Self {
+            _guard: Some(Box::new(guard)),
+        }
+    }
+
+    /// A lock that coordinates nothing, for storage with no concurrent writers
+    pub fn unlocked() -> Self {
+        Self { _guard: None }
+    }
+}
 
 /// Storage abstraction for Faffage data persistence.
 ///
@@ -19,33 +87,103 @@ pub trait Storage: Send + Sync {
     fn config_file(&self) -> PathBuf;
 
     // File operations
+    //
+    // Writes should replace the target atomically: a reader must see either the
+    // previous contents or the new ones, never a partially written file.
     fn read_bytes(&self, path: &Path) -> Result<Vec<u8>>;
     fn read_string(&self, path: &Path) -> Result<String>;
     fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()>;
     fn write_string(&self, path: &Path, data: &str) -> Result<()>;
     fn delete(&self, path: &Path) -> Result<()>;
 
+    // Versioned file operations
+    //
+    // The defaults derive tokens from file contents. Storage with a cheaper
+    // notion of version may override all three, but tokens must stay consistent
+    // between them.
+    fn version(&self, path: &Path) -> Result<Option<VersionToken>> {
+        if !self.exists(path) {
+            return Ok(None);
+        }
+        Ok(Some(VersionToken::of(&self.read_bytes(path)?)))
+    }
+
+    fn read_string_versioned(&self, path: &Path) -> Result<(String, VersionToken)> {
+        let data = self.read_string(path)?;
+        let version = VersionToken::of(data.as_bytes());
+        Ok((data, version))
+    }
+
+    /// Write only if the file is still at `expected` (None: must not exist)
+    ///
+    /// Fails with a `VersionConflict` otherwise. Returns the new version.
+    /// The check isn't atomic with the write, so callers that need a hard
+    /// guarantee should also hold `lock`.
+    fn write_string_if(
+        &self,
+        path: &Path,
+        data: &str,
+        expected: Option<&VersionToken>,
+    ) -> Result<VersionToken> {
+        let actual = self.version(path)?;
+        if actual.as_ref() != expected {
+            return Err(VersionConflict {
+                path: path.to_path_buf(),
+                expected: expected.cloned(),
+                actual,
+            }
+            .into());
+        }
+        self.write_string(path, data)?;
+        Ok(VersionToken::of(data.as_bytes()))
+    }
+
     // Directory operations
     fn exists(&self, path: &Path) -> bool;
     fn create_dir_all(&self, path: &Path) -> Result<()>;
     fn list_files(&self, dir: &Path, pattern: &str) -> Result<Vec<PathBuf>>;
 
-    // Faffage-specific path construction helpers
+    // Concurrency
+    //
+    // Take the workspace-wide advisory lock, waiting for other holders to
+    // release it. Implementations should give up with an error rather than
+    // wait forever. Not reentrant: callers must not lock while holding it.
+    // The default does no locking.
+    fn lock(&self) -> Result<StorageLock> {
+        Ok(StorageLock::unlocked())
+    }
+
+    // History
+    //
+    // Mark the writes since the last commit as one change described by
+    // `message`. Managers call this once per operation, e.g. "start session:
+    // Email". Storage that keeps history records a revision; the default
+    // does nothing.
+    fn commit(&self, _message: &str) -> Result<()> {
+        Ok(())
+    }
+
+    // Forget the writes since the last commit, because the operation that
+    // made them failed, so they don't end up in the next operation's
+    // revision. The default does nothing.
+    fn discard(&self) {}
+
+    // Faffage-specific path construction helpers (see `layout`)
     fn log_file_path(&self, date: NaiveDate) -> PathBuf {
-        self.log_dir().join(format!("{}.toml", date))
+        self.log_dir().join(layout::log_file_name(date))
     }
 
-    fn plan_file_path(&self, date: NaiveDate) -> PathBuf {
-        self.plan_dir().join(format!("{}.json", date))
+    fn plan_file_path(&self, source: &str, date: NaiveDate) -> PathBuf {
+        self.plan_dir().join(layout::plan_file_name(source, date))
     }
 
     fn timesheet_file_path(&self, audience_id: &str, date: NaiveDate) -> PathBuf {
         self.timesheet_dir()
-            .join(format!("{}.{}.json", audience_id, date))
+            .join(layout::timesheet_file_name(audience_id, date))
     }
 
     fn timesheet_meta_file_path(&self, audience_id: &str, date: NaiveDate) -> PathBuf {
         self.timesheet_dir()
-            .join(format!("{}.{}.meta.json", audience_id, date))
+            .join(layout::timesheet_meta_file_name(audience_id, date))
     }
 }
@@ -1,8 +1,10 @@
-use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Timelike};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike};
 use chrono_tz::Tz;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::{PyDate, PyDateAccess, PyDateTime, PyTimeAccess, PyTzInfo, PyTzInfoAccess};
+use pyo3::types::{
+    PyDate, PyDateAccess, PyDateTime, PyDelta, PyTimeAccess, PyTzInfo, PyTzInfoAccess,
+};
 
 pub fn datetime_py_to_rust<'py>(py_dt: Bound<'py, PyDateTime>) -> PyResult<DateTime<Tz>> {
     // Extract datetime components
@@ -34,7 +36,7 @@ pub fn datetime_py_to_rust<'py>(py_dt: Bound<'py, PyDateTime>) -> PyResult<DateT
         .map_err(|_| PyValueError::new_err(format!("Unrecognized timezone '{}'", tz_name)))?;
 
     // Build datetime in Rust
-    tz.with_ymd_and_hms(year.into(), month, day, hour, minute, second)
+    tz.with_ymd_and_hms(year, month, day, hour, minute, second)
         .single()
         .ok_or_else(|| PyValueError::new_err("Ambiguous or invalid datetime"))?
         .with_nanosecond(micro * 1000)
@@ -77,3 +79,14 @@ pub fn date_py_to_rust<'py>(py_date: Bound<'py, PyDate>) -> PyResult<NaiveDate>
 pub fn date_rust_to_py<'py>(py: Python<'py>, date: &NaiveDate) -> PyResult<Bound<'py, PyDate>> {
     PyDate::new(py, date.year(), date.month() as u8, date.day() as u8)
 }
+
+pub fn duration_rust_to_py<'py>(
+    py: Python<'py>,
+    duration: &Duration,
+) -> PyResult<Bound<'py, PyDelta>> {
+    let total_micros = duration.num_microseconds().unwrap_or(0);
+    let days = (total_micros / 86_400_000_000) as i32;
+    let seconds = ((total_micros % 86_400_000_000) / 1_000_000) as i32;
+    let micros = (total_micros % 1_000_000) as i32;
+    PyDelta::new(py, days, seconds, micros, true)
+}
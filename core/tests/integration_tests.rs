@@ -5,6 +5,7 @@
 
 use chrono::NaiveDate;
 use faff_core::managers::{IdentityManager, LogManager, PlanManager, TimesheetManager};
+use faff_core::memory_storage::MemoryStorage;
 use faff_core::models::intent::Intent;
 use faff_core::models::log::Log;
 use faff_core::models::plan::Plan;
@@ -12,121 +13,13 @@ use faff_core::models::session::Session;
 use faff_core::models::timesheet::{Timesheet, TimesheetMeta};
 use faff_core::storage::Storage;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
-
-/// Shared in-memory storage for integration tests
-struct IntegrationStorage {
-    files: RwLock<HashMap<PathBuf, String>>,
-}
-
-impl IntegrationStorage {
-    fn new() -> Self {
-        Self {
-            files: RwLock::new(HashMap::new()),
-        }
-    }
-
-    fn add_file(&self, path: PathBuf, content: String) {
-        let mut files = self.files.write().unwrap();
-        files.insert(path, content);
-    }
-}
-
-impl Storage for IntegrationStorage {
-    fn root_dir(&self) -> PathBuf {
-        PathBuf::from("/faff")
-    }
-
-    fn log_dir(&self) -> PathBuf {
-        PathBuf::from("/faff/logs")
-    }
-
-    fn plan_dir(&self) -> PathBuf {
-        PathBuf::from("/faff/plans")
-    }
-
-    fn identity_dir(&self) -> PathBuf {
-        PathBuf::from("/faff/keys")
-    }
-
-    fn timesheet_dir(&self) -> PathBuf {
-        PathBuf::from("/faff/timesheets")
-    }
-
-    fn config_file(&self) -> PathBuf {
-        PathBuf::from("/faff/config.toml")
-    }
-
-    fn read_bytes(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
-        let files = self.files.read().unwrap();
-        files
-            .get(path)
-            .map(|s| s.as_bytes().to_vec())
-            .ok_or_else(|| anyhow::anyhow!("File not found: {:?}", path))
-    }
-
-    fn read_string(&self, path: &Path) -> anyhow::Result<String> {
-        let files = self.files.read().unwrap();
-        files
-            .get(path)
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("File not found: {:?}", path))
-    }
-
-    fn write_bytes(&self, path: &Path, data: &[u8]) -> anyhow::Result<()> {
-        let content = String::from_utf8(data.to_vec())?;
-        let mut files = self.files.write().unwrap();
-        files.insert(path.to_path_buf(), content);
-        Ok(())
-    }
-
-    fn write_string(&self, path: &Path, data: &str) -> anyhow::Result<()> {
-        let mut files = self.files.write().unwrap();
-        files.insert(path.to_path_buf(), data.to_string());
-        Ok(())
-    }
-
-    fn delete(&self, path: &Path) -> anyhow::Result<()> {
-        let mut files = self.files.write().unwrap();
-        files
-            .remove(path)
-            .ok_or_else(|| anyhow::anyhow!("File not found: {:?}", path))?;
-        Ok(())
-    }
-
-    fn exists(&self, path: &Path) -> bool {
-        let files = self.files.read().unwrap();
-        files.contains_key(path)
-    }
-
-    fn create_dir_all(&self, _path: &Path) -> anyhow::Result<()> {
-        Ok(())
-    }
-
-    fn list_files(&self, dir: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
-        let files = self.files.read().unwrap();
-        let glob_pattern = glob::Pattern::new(pattern)?;
-
-        Ok(files
-            .keys()
-            .filter(|path| {
-                path.parent() == Some(dir)
-                    && path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .map(|n| glob_pattern.matches(n))
-                        .unwrap_or(false)
-            })
-            .cloned()
-            .collect())
-    }
-}
+use std::path::PathBuf;
+use std::sync::Arc;
 
 #[test]
 fn test_plan_and_log_integration() {
     // Create shared storage
-    let storage = Arc::new(IntegrationStorage::new());
+    let storage = Arc::new(MemoryStorage::new());
 
     // Add a plan with trackers
     storage.add_file(
@@ -202,7 +95,7 @@ trackers = ["PROJ-123"]
 
 #[test]
 fn test_log_and_timesheet_integration() {
-    let storage = Arc::new(IntegrationStorage::new());
+    let storage = Arc::new(MemoryStorage::new());
 
     let log_manager = LogManager::new(storage.clone(), chrono_tz::UTC);
     let timesheet_manager = TimesheetManager::new(storage.clone());
@@ -272,7 +165,7 @@ fn test_log_and_timesheet_integration() {
 
 #[test]
 fn test_identity_and_timesheet_integration() {
-    let storage = Arc::new(IntegrationStorage::new());
+    let storage = Arc::new(MemoryStorage::new());
 
     let identity_manager = IdentityManager::new(storage.clone());
     let timesheet_manager = TimesheetManager::new(storage.clone());
@@ -320,7 +213,7 @@ fn test_identity_and_timesheet_integration() {
 
 #[test]
 fn test_multiple_managers_share_storage() {
-    let storage = Arc::new(IntegrationStorage::new());
+    let storage = Arc::new(MemoryStorage::new());
 
     // Create all managers
     let log_manager = LogManager::new(storage.clone(), chrono_tz::UTC);
@@ -344,7 +237,7 @@ fn test_multiple_managers_share_storage() {
 
 #[test]
 fn test_plan_caching_across_calls() {
-    let storage = Arc::new(IntegrationStorage::new());
+    let storage = Arc::new(MemoryStorage::new());
 
     storage.add_file(
         PathBuf::from("/faff/plans/local.20250315.toml"),
@@ -394,7 +287,7 @@ roles = ["engineer"]
 
 #[test]
 fn test_log_list_and_read_integration() {
-    let storage = Arc::new(IntegrationStorage::new());
+    let storage = Arc::new(MemoryStorage::new());
     let log_manager = LogManager::new(storage.clone(), chrono_tz::UTC);
 
     // Create multiple logs
@@ -430,7 +323,7 @@ fn test_log_list_and_read_integration() {
 
 #[test]
 fn test_timesheet_list_filtering() {
-    let storage = Arc::new(IntegrationStorage::new());
+    let storage = Arc::new(MemoryStorage::new());
     let timesheet_manager = TimesheetManager::new(storage.clone());
 
     let date1 = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
@@ -465,3 +358,35 @@ fn test_timesheet_list_filtering() {
     assert_eq!(filtered2.len(), 1);
     assert_eq!(filtered2[0].date, date2);
 }
+
+#[test]
+fn test_concurrent_plan_edits_are_not_lost() {
+    use faff_core::file_system_storage::FileSystemStorage;
+
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir(temp.path().join(".faff")).unwrap();
+    let date = NaiveDate::from_ymd_opt(2025, 3, 20).unwrap();
+
+    // Each thread stands in for a separate process with its own manager
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let root = temp.path().to_path_buf();
+            std::thread::spawn(move || {
+                let storage = Arc::new(FileSystemStorage::from_path(root).unwrap());
+                PlanManager::new(storage)
+                    .add_tracker(date, &format!("PROJ-{}", i), "Project")
+                    .unwrap();
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let storage = Arc::new(FileSystemStorage::from_path(temp.path().to_path_buf()).unwrap());
+    let plan = PlanManager::new(storage)
+        .get_local_plan(date)
+        .unwrap()
+        .unwrap();
+    assert_eq!(plan.trackers.len(), 8);
+}